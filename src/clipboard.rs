@@ -0,0 +1,35 @@
+//! Copying text to the system clipboard.
+//!
+//! The text is passed to the terminal using the OSC 52 escape sequence, so
+//! copying works without access to a display server, and over SSH, in
+//! terminals supporting the sequence.
+
+use std::io::{self, Write};
+
+use crate::image::base64;
+
+/// Copies text to the system clipboard.
+///
+/// # Arguments
+/// *  `target` - The terminal.
+/// *  `text` - The text to copy.
+pub fn copy<W>(target: &mut W, text: &str) -> io::Result<()>
+where
+    W: Write,
+{
+    write!(target, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    target.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copy_sequence() {
+        let mut target = Vec::new();
+        copy(&mut target, "foo").unwrap();
+
+        assert_eq!(b"\x1b]52;c;Zm9v\x07".as_slice(), target.as_slice());
+    }
+}
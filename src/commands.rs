@@ -0,0 +1,542 @@
+//! External commands run while presenting.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{self, Stdio};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::configuration::interpolate;
+
+/// The commands run while presenting.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Commands {
+    /// The command run whenever the page shown changes.
+    pub update: Option<Command>,
+
+    /// Whether to print the command line of commands to the error output
+    /// instead of running them.
+    pub dry_run: bool,
+}
+
+impl Commands {
+    /// Starts the update command in the background, if one is configured.
+    ///
+    /// The command is run in the directory containing the presentation
+    /// document, and the variables `${presentation.path}`, `${page.current}`,
+    /// `${page.total}` and `${env.NAME}` in its arguments are replaced.
+    ///
+    /// In dry-run mode, the command is not started, and its command line is
+    /// returned instead.
+    ///
+    /// # Arguments
+    /// *  `background` - The commands running in the background, to which
+    ///    the command is added; if too many commands are running, it is
+    ///    started once one of them has finished.
+    /// *  `presentation` - The path to the presentation document.
+    /// *  `page` - The index of the current page.
+    /// *  `count` - The number of pages.
+    pub fn dispatch(
+        &self,
+        background: &mut Background,
+        presentation: &Path,
+        page: usize,
+        count: usize,
+    ) -> Result<Option<String>, String> {
+        let command = match &self.update {
+            Some(command) => command.clone(),
+            None => return Ok(None),
+        };
+        let presentation = presentation.canonicalize().map_err(|e| {
+            format!("Failed to resolve {}: {}", presentation.display(), e)
+        })?;
+        let dry_run = self.dry_run;
+        let job = move || {
+            let directory = presentation.parent().unwrap_or(&presentation);
+            command.execute(
+                directory,
+                |name| variable(name, &presentation, page, count),
+                dry_run,
+            )
+        };
+        if dry_run {
+            job().map(Some)
+        } else {
+            background.start(page, Box::new(job));
+            Ok(None)
+        }
+    }
+}
+
+/// A command waiting to be run in the background.
+type Job = Box<dyn FnOnce() -> Result<String, String> + Send>;
+
+/// Commands running in the background.
+#[derive(Default)]
+pub struct Background {
+    /// The threads waiting for the commands to finish, with the index of the
+    /// page for which each command was started.
+    commands: Vec<(usize, thread::JoinHandle<Result<String, String>>)>,
+
+    /// The command waiting for a running command to finish, if any, with
+    /// the index of its page.
+    pending: Option<(usize, Job)>,
+}
+
+impl Background {
+    /// The largest number of commands run at once.
+    pub const MAX_RUNNING: usize = 4;
+
+    /// The number of commands still running, or finished but not yet reaped.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Whether no commands are running or waiting to run.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty() && self.pending.is_none()
+    }
+
+    /// Collects the results of the commands that have finished.
+    ///
+    /// Commands still running are left running, and a waiting command is
+    /// started if fewer than [`Self::MAX_RUNNING`] commands are running. The
+    /// result of a command is its output, or an error message, and is paired
+    /// with the index of the page for which the command was started.
+    pub fn reap(&mut self) -> Vec<(usize, Result<String, String>)> {
+        let (finished, running) = self
+            .commands
+            .drain(..)
+            .partition::<Vec<_>, _>(|(_, command)| command.is_finished());
+        self.commands = running;
+        if let Some((page, job)) = self.pending.take() {
+            self.start(page, job);
+        }
+        finished
+            .into_iter()
+            .map(|(page, command)| {
+                let result = command
+                    .join()
+                    .unwrap_or_else(|_| Err("Command thread panicked".into()));
+                (page, result)
+            })
+            .collect()
+    }
+
+    /// Starts a command, or keeps it waiting if too many commands are
+    /// running.
+    ///
+    /// Only the most recent command is kept waiting, since the commands
+    /// dispatched before it are for pages no longer shown.
+    ///
+    /// # Arguments
+    /// *  `page` - The index of the page for which the command is started.
+    /// *  `job` - The command to start.
+    fn start(&mut self, page: usize, job: Job) {
+        if self.commands.len() < Self::MAX_RUNNING {
+            self.commands.push((page, thread::spawn(job)));
+        } else {
+            self.pending = Some((page, job));
+        }
+    }
+}
+
+/// The value of a variable in the arguments of a command.
+///
+/// Variables named `env.NAME` are read from the environment; this function
+/// returns `None` for unset environment variables and unknown names.
+///
+/// # Arguments
+/// *  `name` - The name of the variable.
+/// *  `presentation` - The path to the presentation document.
+/// *  `page` - The index of the current page.
+/// *  `count` - The number of pages.
+fn variable(
+    name: &str,
+    presentation: &Path,
+    page: usize,
+    count: usize,
+) -> Option<String> {
+    match name {
+        "presentation.path" => {
+            Some(presentation.to_string_lossy().into_owned())
+        }
+        "page.current" => Some((page + 1).to_string()),
+        "page.total" => Some(count.to_string()),
+        _ => name
+            .strip_prefix("env.")
+            .and_then(|name| env::var(name).ok()),
+    }
+}
+
+/// An external command.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Command {
+    /// The binary to run.
+    ///
+    /// Relative paths containing a directory, such as `./update.sh`, are
+    /// relative to the presentation document, and other names are looked up
+    /// in `PATH`.
+    pub binary: String,
+
+    /// The arguments passed to the binary.
+    #[serde(default)]
+    pub arguments: Vec<String>,
+}
+
+impl Command {
+    /// Runs this command and waits for it to finish.
+    ///
+    /// The output of the command is collected, since the terminal is owned
+    /// by the UI, and returned with surrounding whitespace removed. The error
+    /// output is included in the error returned if the command fails.
+    ///
+    /// # Arguments
+    /// *  `directory` - The directory in which to run the command.
+    /// *  `variables` - A function returning the value of a variable in the
+    ///    arguments, or `None` for unknown variables.
+    /// *  `dry_run` - Whether to return the command line, including the
+    ///    working directory, instead of running the command. The caller
+    ///    prints it, since the UI owns the terminal while presenting.
+    pub fn execute<F>(
+        &self,
+        directory: &Path,
+        variables: F,
+        dry_run: bool,
+    ) -> Result<String, String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if dry_run {
+            return Ok(self.command_line(directory, variables));
+        }
+
+        let output = process::Command::new(self.program(directory))
+            .args(
+                self.arguments
+                    .iter()
+                    .map(|argument| interpolate(argument, &variables)),
+            )
+            .current_dir(directory)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("Failed to run {}: {}", self.binary, e))?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.trim().is_empty() {
+                Err(format!("{} failed: {}", self.binary, output.status))
+            } else {
+                Err(format!(
+                    "{} failed: {}: {}",
+                    self.binary,
+                    output.status,
+                    stderr.trim(),
+                ))
+            }
+        }
+    }
+
+    /// The command line of this command, as run by a shell.
+    ///
+    /// The command line changes to the working directory before running the
+    /// program, and words are quoted when necessary.
+    ///
+    /// # Arguments
+    /// *  `directory` - The directory in which to run the command.
+    /// *  `variables` - A function returning the value of a variable in the
+    ///    arguments, or `None` for unknown variables.
+    pub fn command_line<F>(&self, directory: &Path, variables: F) -> String
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        [
+            "cd".to_string(),
+            quote(&directory.to_string_lossy()),
+            "&&".to_string(),
+            quote(&self.program(directory).to_string_lossy()),
+        ]
+        .into_iter()
+        .chain(
+            self.arguments
+                .iter()
+                .map(|argument| quote(&interpolate(argument, &variables))),
+        )
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+
+    /// The program to run.
+    ///
+    /// Whether relative paths passed to a process are resolved against its
+    /// working directory differs between platforms, so they are resolved
+    /// explicitly. Absolute paths and bare names are returned as they are.
+    ///
+    /// # Arguments
+    /// *  `directory` - The directory containing the presentation document.
+    pub fn program(&self, directory: &Path) -> PathBuf {
+        let binary = Path::new(&self.binary);
+        if binary.is_relative() && binary.components().count() > 1 {
+            directory.join(binary)
+        } else {
+            binary.into()
+        }
+    }
+}
+
+/// Quotes a word for a shell, unless it contains only safe characters.
+///
+/// # Arguments
+/// *  `word` - The word to quote.
+fn quote(word: &str) -> String {
+    if !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "%+,-./:=@_".contains(c))
+    {
+        word.into()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    #[test]
+    fn program() {
+        let command = |binary: &str| Command {
+            binary: binary.into(),
+            arguments: Vec::new(),
+        };
+        let directory = Path::new("/presentations/talk");
+
+        assert_eq!(
+            Path::new("/presentations/talk/./update.sh"),
+            command("./update.sh").program(directory),
+        );
+        assert_eq!(
+            Path::new("/presentations/talk/scripts/update.sh"),
+            command("scripts/update.sh").program(directory),
+        );
+        assert_eq!(
+            Path::new("/usr/bin/update"),
+            command("/usr/bin/update").program(directory),
+        );
+        assert_eq!(Path::new("update"), command("update").program(directory));
+    }
+
+    #[test]
+    fn environment() {
+        let presentation = Path::new("presentation.md");
+        let variables = |name: &str| variable(name, presentation, 2, 12);
+
+        assert_eq!(env::var("PATH").ok(), variables("env.PATH"));
+        assert_eq!(
+            format!(
+                "{} of 12 ${{env.RUPERT_UNDEFINED}}",
+                env::var("PATH").unwrap(),
+            ),
+            interpolate(
+                "${env.PATH} of ${page.total} ${env.RUPERT_UNDEFINED}",
+                variables,
+            ),
+        );
+    }
+
+    #[test]
+    fn command_line() {
+        let command = Command {
+            binary: "./update.sh".into(),
+            arguments: vec![
+                "${presentation.path}".into(),
+                "Page ${page.current}".into(),
+                "it's".into(),
+            ],
+        };
+        let directory = Path::new("/presentations/my talk");
+
+        assert_eq!(
+            "cd '/presentations/my talk' && \
+            '/presentations/my talk/./update.sh' \
+            '/presentations/my talk/presentation.md' 'Page 3' 'it'\\''s'",
+            command.command_line(directory, |name| variable(
+                name,
+                &directory.join("presentation.md"),
+                2,
+                12,
+            )),
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn dispatch_relative() {
+        let commands = Commands {
+            update: Some(Command {
+                binary: "./update.sh".into(),
+                arguments: vec!["${page.current}".into()],
+            }),
+            ..Default::default()
+        };
+        let mut background = Background::default();
+
+        commands
+            .dispatch(
+                &mut background,
+                Path::new("test-resources/commands/presentation.md"),
+                0,
+                1,
+            )
+            .unwrap();
+        commands
+            .dispatch(
+                &mut background,
+                Path::new("test-resources/headings.md"),
+                0,
+                1,
+            )
+            .unwrap();
+        let results = wait(&mut background);
+        assert_eq!((0, Ok(String::new())), results[0]);
+        assert!(results[1].1.is_err());
+    }
+
+    #[test]
+    fn dispatch_background() {
+        let commands = Commands {
+            update: Some(Command {
+                binary: "sleep".into(),
+                arguments: vec!["1".into()],
+            }),
+            ..Default::default()
+        };
+        let mut background = Background::default();
+
+        let start = Instant::now();
+        commands
+            .dispatch(
+                &mut background,
+                Path::new("test-resources/headings.md"),
+                0,
+                1,
+            )
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert!(background.reap().is_empty());
+        assert_eq!(1, background.len());
+        assert_eq!(vec![(0, Ok(String::new()))], wait(&mut background));
+        assert!(background.is_empty());
+    }
+
+    #[test]
+    fn dispatch_output() {
+        let commands = Commands {
+            update: Some(Command {
+                binary: "echo".into(),
+                arguments: vec!["Page ${page.current} of ${page.total}".into()],
+            }),
+            ..Default::default()
+        };
+        let mut background = Background::default();
+
+        commands
+            .dispatch(
+                &mut background,
+                Path::new("test-resources/headings.md"),
+                1,
+                3,
+            )
+            .unwrap();
+        assert_eq!(
+            vec![(1, Ok("Page 2 of 3".to_string()))],
+            wait(&mut background),
+        );
+    }
+
+    #[test]
+    fn dispatch_dry_run() {
+        let commands = Commands {
+            update: Some(Command {
+                binary: "false".into(),
+                arguments: vec!["${page.current}".into()],
+            }),
+            dry_run: true,
+        };
+        let mut background = Background::default();
+
+        let presentation = Path::new("test-resources/headings.md");
+        let directory = presentation.canonicalize().unwrap();
+        let directory = directory.parent().unwrap();
+        assert_eq!(
+            Ok(Some(format!(
+                "cd {} && false 1",
+                quote(&directory.to_string_lossy())
+            ))),
+            commands.dispatch(&mut background, presentation, 0, 1),
+        );
+        assert!(background.is_empty());
+    }
+
+    #[test]
+    fn dispatch_limit() {
+        let commands = Commands {
+            update: Some(Command {
+                binary: "sleep".into(),
+                arguments: vec!["1".into()],
+            }),
+            ..Default::default()
+        };
+        let mut background = Background::default();
+
+        for page in 0..Background::MAX_RUNNING + 2 {
+            commands
+                .dispatch(
+                    &mut background,
+                    Path::new("test-resources/headings.md"),
+                    page,
+                    Background::MAX_RUNNING + 2,
+                )
+                .unwrap();
+        }
+        assert_eq!(Background::MAX_RUNNING, background.len());
+
+        // Only the last of the commands waiting is run
+        let pages = wait(&mut background)
+            .into_iter()
+            .map(|(page, _)| page)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            (0..Background::MAX_RUNNING)
+                .chain([Background::MAX_RUNNING + 1])
+                .collect::<Vec<_>>(),
+            pages,
+        );
+        assert!(background.is_empty());
+    }
+
+    /// Waits for all commands running or waiting in the background, and
+    /// returns their results, with the index of their page, in the order
+    /// they were started.
+    fn wait(
+        background: &mut Background,
+    ) -> Vec<(usize, Result<String, String>)> {
+        let mut results = Vec::new();
+        while !background.is_empty() {
+            results.extend(
+                background
+                    .commands
+                    .drain(..)
+                    .map(|(page, command)| (page, command.join().unwrap())),
+            );
+            results.extend(background.reap());
+        }
+        results
+    }
+}
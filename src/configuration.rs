@@ -2,27 +2,928 @@ use std::env;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
-use toml;
+use tui::layout;
+use tui::style::{Color, Modifier, Style};
 
+use crate::commands::Commands;
 use crate::presentation;
 
+pub mod color;
+
 /// The application configuration file.
 #[derive(Deserialize, Serialize)]
 pub struct Configuration {
     /// The title of the presentation.
     pub title: String,
 
+    /// The subtitle of the presentation, shown on the metadata page.
+    pub subtitle: Option<String>,
+
+    /// The author of the presentation, shown on the metadata page.
+    pub author: Option<String>,
+
+    /// The date of the presentation, shown on the metadata page.
+    pub date: Option<String>,
+
     /// Information about the source.
     pub source: Source,
 
     /// The page break configuration.
     pub page_break: Option<presentation::PageBreakCondition>,
+
+    /// Whether to split pages too tall for the terminal into several pages.
+    #[serde(default = "Configuration::default_auto_paginate")]
+    pub auto_paginate: bool,
+
+    /// The number of empty lines between top level sections of a page.
+    #[serde(default = "Configuration::default_section_spacing")]
+    pub section_spacing: u16,
+
+    /// Whether to wrap long lines of paragraphs, rather than to clip them.
+    #[serde(default = "Configuration::default_paragraph_wrap")]
+    pub paragraph_wrap: bool,
+
+    /// Whether to drop whitespace at the start of lines of paragraphs when
+    /// wrapping them.
+    #[serde(default = "Configuration::default_paragraph_trim")]
+    pub paragraph_trim: bool,
+
+    /// The text ending content cut off to fit, such as table cells and
+    /// clipped lines of paragraphs.
+    #[serde(default = "Configuration::default_ellipsis")]
+    pub ellipsis: String,
+
+    /// The number of cells each level of list items is indented.
+    #[serde(default = "Configuration::default_indent_width")]
+    pub indent_width: u16,
+
+    /// The marker shown before headings.
+    #[serde(default)]
+    pub heading_marker: HeadingMarker,
+
+    /// The configuration of headings.
+    #[serde(default)]
+    pub headings: Headings,
+
+    /// The configuration of thematic breaks.
+    #[serde(default)]
+    pub thematic_break: ThematicBreak,
+
+    /// The text shown on the line above block quotes; if empty, the line is
+    /// left out.
+    #[serde(default = "Configuration::default_blockquote_marker")]
+    pub blockquote_marker: String,
+
+    /// The name of the theme used to highlight code on dark terminals.
+    #[serde(default = "Configuration::default_code_theme")]
+    pub code_theme: String,
+
+    /// The name of the theme used to highlight code on light terminals.
+    #[serde(default = "Configuration::default_light_code_theme")]
+    pub light_code_theme: String,
+
+    /// Whether the terminal has a light or dark background, which selects
+    /// the theme used to highlight code.
+    #[serde(default)]
+    pub theme_mode: ThemeMode,
+
+    /// Whether to draw a border around code blocks.
+    #[serde(default)]
+    pub code_border: bool,
+
+    /// Whether to show the language of code blocks as a caption to the right
+    /// above the code.
+    #[serde(default = "Configuration::default_code_caption")]
+    pub code_caption: bool,
+
+    /// The language used to highlight code blocks not specifying one, such
+    /// as indented code blocks.
+    pub default_code_language: Option<String>,
+
+    /// A directory containing additional syntax definitions used to
+    /// highlight code, relative to the configuration file.
+    pub syntax_dir: Option<PathBuf>,
+
+    /// How footnotes are numbered.
+    #[serde(default)]
+    pub footnote_numbering: FootnoteNumbering,
+
+    /// The line shown after the footnotes of a page when not all of them
+    /// fit; `${count}` is replaced by the number of footnotes left out.
+    #[serde(default = "Configuration::default_footnote_overflow")]
+    pub footnote_overflow: String,
+
+    /// Whether to draw a rule above the footnotes of a page.
+    #[serde(default)]
+    pub footnote_rule: bool,
+
+    /// Whether to show a page with the title, subtitle, author and date of
+    /// the presentation as the first page.
+    #[serde(default)]
+    pub metadata_slide: bool,
+
+    /// Whether to show a table of contents as the first page.
+    #[serde(default)]
+    pub table_of_contents: bool,
+
+    /// The transition shown when changing pages.
+    #[serde(default)]
+    pub transition: Transition,
+
+    /// The layout of pages.
+    #[serde(default)]
+    pub layout: PageLayout,
+
+    /// The page counter shown in the bottom border.
+    #[serde(default)]
+    pub page_counter: PageCounter,
+
+    /// The number of words read per minute, used to estimate the reading
+    /// time of pages.
+    #[serde(default = "Configuration::default_words_per_minute")]
+    pub words_per_minute: u32,
+
+    /// The number of milliseconds to show every page before advancing
+    /// automatically, wrapping around after the last page.
+    pub autoplay_ms: Option<u64>,
+
+    /// The colour of text.
+    #[serde(default = "Configuration::default_color", with = "color")]
+    pub text_color: Color,
+
+    /// The background colour of pages.
+    #[serde(default = "Configuration::default_color", with = "color")]
+    pub text_background: Color,
+
+    /// The background drawn behind pages.
+    #[serde(default)]
+    pub background: BackgroundConfig,
+
+    /// The style of text marked as `==highlighted==`.
+    #[serde(default = "Configuration::default_highlight")]
+    pub highlight: StyleConfig,
+
+    /// The style of inline code.
+    #[serde(default = "Configuration::default_inline_code")]
+    pub inline_code: StyleConfig,
+
+    /// The style of link text.
+    #[serde(default = "Configuration::default_link_style")]
+    pub link_style: StyleConfig,
+
+    /// The style of the URL shown after link text, applied over the style of
+    /// the link text.
+    #[serde(default)]
+    pub link_url_style: StyleConfig,
+
+    /// Whether to render inline math written as `$...$` using Unicode
+    /// approximations.
+    #[serde(default)]
+    pub math: bool,
+
+    /// Whether to draw a border around tables.
+    #[serde(default = "Configuration::default_table_border")]
+    pub table_border: bool,
+
+    /// The widths of the columns of tables.
+    ///
+    /// Tables with a different number of columns, and all tables if this is
+    /// empty, have columns of equal width.
+    #[serde(default)]
+    pub table_widths: Vec<ColumnWidth>,
+
+    /// Whether to give every other row of tables a different background.
+    #[serde(default)]
+    pub table_zebra: bool,
+
+    /// The language of the presentation, as a language tag such as `en` or
+    /// `zh-TW`.
+    ///
+    /// Text in Chinese, Japanese and Korean may be wrapped between any wide
+    /// characters, rather than only at whitespace.
+    pub language: Option<String>,
+
+    /// Whether to leave pages out of the presentation.
+    ///
+    /// This is usually set for single pages, to keep drafts in the document
+    /// without presenting them.
+    #[serde(default)]
+    pub skip: bool,
+
+    /// The maximum width of page content, which is centered on wider
+    /// terminals.
+    pub max_content_width: Option<u16>,
+
+    /// Whether quitting requires pressing `q` twice.
+    #[serde(default)]
+    pub confirm_quit: bool,
+
+    /// Whether to show the page last shown when presenting again.
+    #[serde(default)]
+    pub resume: bool,
+
+    /// Whether to render without colours, using only modifiers such as bold
+    /// and underline for emphasis.
+    #[serde(default)]
+    pub monochrome: bool,
+
+    /// The number of colours supported by the terminal; RGB colours are
+    /// replaced by the closest colour in the palette for lower depths.
+    ///
+    /// If this is not set, the depth is detected when presenting.
+    pub color_depth: Option<ColorDepth>,
+
+    /// The commands run while presenting.
+    #[serde(default)]
+    pub commands: Commands,
 }
 
-/// Information about the source.
+impl Configuration {
+    /// The name of the default theme used to highlight code.
+    pub const DEFAULT_CODE_THEME: &'static str = "base16-ocean.dark";
+
+    /// The name of the default theme used to highlight code on light
+    /// terminals.
+    pub const DEFAULT_LIGHT_CODE_THEME: &'static str = "base16-ocean.light";
+
+    fn default_auto_paginate() -> bool {
+        true
+    }
+
+    fn default_code_caption() -> bool {
+        true
+    }
+
+    fn default_section_spacing() -> u16 {
+        1
+    }
+
+    fn default_ellipsis() -> String {
+        "…".into()
+    }
+
+    fn default_paragraph_wrap() -> bool {
+        true
+    }
+
+    fn default_paragraph_trim() -> bool {
+        true
+    }
+
+    fn default_blockquote_marker() -> String {
+        ">>>".into()
+    }
+
+    fn default_table_border() -> bool {
+        true
+    }
+
+    fn default_footnote_overflow() -> String {
+        "… ${count} more".into()
+    }
+
+    fn default_indent_width() -> u16 {
+        4
+    }
+
+    fn default_words_per_minute() -> u32 {
+        200
+    }
+
+    /// Serialises this configuration as TOML.
+    ///
+    /// Values that are not set are left out.
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::Value::try_from(self)
+            .and_then(|value| toml::to_string(&value))
+            .map_err(|e| e.to_string())
+    }
+
+    /// Checks that the values of this configuration make sense.
+    ///
+    /// All problems found are returned.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        if self.title.trim().is_empty() {
+            problems.push("title: must not be empty".into());
+        }
+        if let Some(Err(e)) = self.page_break.as_ref().map(|c| c.validate()) {
+            problems.push(format!("page_break: {}", e));
+        }
+        if self.table_widths.iter().any(|width| match width {
+            ColumnWidth::Percentage(percentage) => *percentage > 100,
+            ColumnWidth::Length(_) => false,
+        }) {
+            problems.push(
+                "table_widths: percentages must not be greater than 100".into(),
+            );
+        }
+        if self.indent_width == 0 {
+            problems.push("indent_width: must be greater than 0".into());
+        }
+        if self.words_per_minute == 0 {
+            problems.push("words_per_minute: must be greater than 0".into());
+        }
+        if self.autoplay_ms == Some(0) {
+            problems.push("autoplay_ms: must be greater than 0".into());
+        }
+        if self.max_content_width == Some(0) {
+            problems.push("max_content_width: must be greater than 0".into());
+        }
+        if self.thematic_break.symbol.is_empty() {
+            problems.push("thematic_break.symbol: must not be empty".into());
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// The style on which the styles of all page content are based.
+    pub fn default_style(&self) -> Style {
+        Style::default()
+            .fg(self.text_color)
+            .bg(self.text_background)
+    }
+
+    /// Whether lines may be wrapped between any wide characters, as is usual
+    /// for the language of the presentation.
+    pub fn wraps_wide_characters(&self) -> bool {
+        self.language.as_deref().is_some_and(|language| {
+            let primary = language.split(['-', '_']).next().unwrap_or("");
+            ["zh", "ja", "ko"]
+                .iter()
+                .any(|cjk| primary.eq_ignore_ascii_case(cjk))
+        })
+    }
+
+    /// The name of the theme used to highlight code for the theme mode.
+    ///
+    /// Unless the mode is light, the dark theme is used.
+    pub fn code_theme(&self) -> &str {
+        match self.theme_mode {
+            ThemeMode::Light => &self.light_code_theme,
+            ThemeMode::Auto | ThemeMode::Dark => &self.code_theme,
+        }
+    }
+
+    fn default_code_theme() -> String {
+        Self::DEFAULT_CODE_THEME.into()
+    }
+
+    fn default_light_code_theme() -> String {
+        Self::DEFAULT_LIGHT_CODE_THEME.into()
+    }
+
+    fn default_color() -> Color {
+        Color::Reset
+    }
+
+    fn default_highlight() -> StyleConfig {
+        StyleConfig {
+            reversed: true,
+            ..Default::default()
+        }
+    }
+
+    fn default_inline_code() -> StyleConfig {
+        StyleConfig {
+            dim: true,
+            ..Default::default()
+        }
+    }
+
+    fn default_link_style() -> StyleConfig {
+        StyleConfig {
+            fg: Some(Color::Blue),
+            underline: true,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Self {
+            title: String::new(),
+            subtitle: None,
+            author: None,
+            date: None,
+            source: Source::default(),
+            page_break: None,
+            auto_paginate: Self::default_auto_paginate(),
+            section_spacing: Self::default_section_spacing(),
+            paragraph_wrap: Self::default_paragraph_wrap(),
+            paragraph_trim: Self::default_paragraph_trim(),
+            ellipsis: Self::default_ellipsis(),
+            indent_width: Self::default_indent_width(),
+            heading_marker: HeadingMarker::default(),
+            headings: Headings::default(),
+            thematic_break: ThematicBreak::default(),
+            blockquote_marker: Self::default_blockquote_marker(),
+            code_theme: Self::default_code_theme(),
+            light_code_theme: Self::default_light_code_theme(),
+            theme_mode: ThemeMode::default(),
+            code_border: false,
+            code_caption: Self::default_code_caption(),
+            default_code_language: None,
+            syntax_dir: None,
+            footnote_numbering: FootnoteNumbering::default(),
+            footnote_overflow: Self::default_footnote_overflow(),
+            footnote_rule: false,
+            metadata_slide: false,
+            table_of_contents: false,
+            transition: Transition::default(),
+            layout: PageLayout::default(),
+            page_counter: PageCounter::default(),
+            words_per_minute: Self::default_words_per_minute(),
+            autoplay_ms: None,
+            text_color: Self::default_color(),
+            text_background: Self::default_color(),
+            background: BackgroundConfig::default(),
+            highlight: Self::default_highlight(),
+            inline_code: Self::default_inline_code(),
+            link_style: Self::default_link_style(),
+            link_url_style: StyleConfig::default(),
+            math: false,
+            table_border: Self::default_table_border(),
+            table_widths: Vec::new(),
+            table_zebra: false,
+            language: None,
+            skip: false,
+            max_content_width: None,
+            confirm_quit: false,
+            resume: false,
+            monochrome: false,
+            color_depth: None,
+            commands: Commands::default(),
+        }
+    }
+}
+
+/// The marker shown before headings.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HeadingMarker {
+    /// One `#` per level, followed by the configured prefix.
+    #[default]
+    Hash,
+
+    /// No marker at all.
+    None,
+
+    /// Only the configured prefix.
+    PrefixOnly,
+}
+
+/// The horizontal alignment of text.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Alignment {
+    /// Text is aligned to the left.
+    #[default]
+    Left,
+
+    /// Text is centered.
+    Center,
+
+    /// Text is aligned to the right.
+    Right,
+}
+
+impl From<Alignment> for layout::Alignment {
+    fn from(source: Alignment) -> Self {
+        match source {
+            Alignment::Left => layout::Alignment::Left,
+            Alignment::Center => layout::Alignment::Center,
+            Alignment::Right => layout::Alignment::Right,
+        }
+    }
+}
+
+/// The width of a table column.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColumnWidth {
+    /// A number of cells.
+    Length(u16),
+
+    /// A percentage of the width of the table.
+    Percentage(u16),
+}
+
+impl From<ColumnWidth> for layout::Constraint {
+    fn from(source: ColumnWidth) -> Self {
+        match source {
+            ColumnWidth::Length(length) => layout::Constraint::Length(length),
+            ColumnWidth::Percentage(percentage) => {
+                layout::Constraint::Percentage(percentage)
+            }
+        }
+    }
+}
+
+/// How footnotes are numbered.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FootnoteNumbering {
+    /// Footnotes are numbered throughout the presentation, in the order they
+    /// are first referenced.
+    #[default]
+    Global,
+
+    /// Footnotes are numbered from 1 on every page, in the order they are
+    /// first referenced on the page.
+    PerPage,
+}
+
+/// The transition shown when changing pages.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transition {
+    /// The new page replaces the old page immediately.
+    #[default]
+    None,
+
+    /// The new page slides in from the side.
+    Slide,
+
+    /// The old page fades out, and the new page fades in.
+    Fade,
+}
+
+/// The layout of a page.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PageLayout {
+    /// The content is placed at the top of the page.
+    #[default]
+    Content,
+
+    /// The content is centered vertically, and the progress gauge is hidden.
+    Title,
+}
+
+/// Whether the terminal has a light or dark background.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeMode {
+    /// The background is detected when presenting.
+    #[default]
+    Auto,
+
+    /// The background is light.
+    Light,
+
+    /// The background is dark.
+    Dark,
+}
+
+impl ThemeMode {
+    /// Determines whether a terminal has a light or dark background given
+    /// its environment.
+    ///
+    /// The background is read from `COLORFGBG`; if it is not set, the
+    /// background is expected to be dark.
+    ///
+    /// # Arguments
+    /// *  `var` - A function returning the value of an environment variable.
+    pub fn detect<F>(var: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        var("COLORFGBG")
+            .and_then(|value| Self::from_colorfgbg(&value))
+            .unwrap_or(Self::Dark)
+    }
+
+    /// Parses the value of `COLORFGBG`.
+    ///
+    /// The value lists palette indices separated by `;`, the last one being
+    /// the background. The first 7 colours of the palette, and dark gray, are
+    /// dark. This function returns `None` if the background is not a palette
+    /// index.
+    ///
+    /// # Arguments
+    /// *  `value` - The value of the variable.
+    pub fn from_colorfgbg(value: &str) -> Option<Self> {
+        match value.rsplit(';').next()?.trim().parse::<u8>().ok()? {
+            0..=6 | 8 => Some(Self::Dark),
+            _ => Some(Self::Light),
+        }
+    }
+}
+
+/// The number of colours supported by the terminal.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub enum ColorDepth {
+    /// Any RGB colour.
+    #[default]
+    #[serde(rename = "truecolor")]
+    TrueColor,
+
+    /// The 256 colour palette.
+    #[serde(rename = "256")]
+    Palette256,
+
+    /// The 16 named colours.
+    #[serde(rename = "16")]
+    Palette16,
+}
+
+impl ColorDepth {
+    /// Determines the colour depth of a terminal given its environment.
+    ///
+    /// Terminals announcing support for RGB colours in `COLORTERM`, and
+    /// terminals not setting it, are expected to support them, and terminals
+    /// announcing anything else the 256 colour palette.
+    ///
+    /// # Arguments
+    /// *  `var` - A function returning the value of an environment variable.
+    pub fn detect<F>(var: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        match var("COLORTERM").as_deref() {
+            None | Some("truecolor" | "24bit") => Self::TrueColor,
+            Some(_) => Self::Palette256,
+        }
+    }
+}
+
+/// The configuration of headings, by level.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Headings {
+    /// Level 1 headings.
+    pub h1: Heading,
+
+    /// Level 2 headings.
+    pub h2: Heading,
+
+    /// Level 3 headings.
+    pub h3: Heading,
+
+    /// Level 4 headings.
+    pub h4: Heading,
+
+    /// Level 5 headings.
+    pub h5: Heading,
+
+    /// Level 6 headings.
+    pub h6: Heading,
+}
+
+impl Headings {
+    /// The configuration of headings of a specific level.
+    ///
+    /// Levels above 6 use the configuration for level 6.
+    ///
+    /// # Arguments
+    /// *  `level` - The heading level.
+    pub fn level(&self, level: u8) -> &Heading {
+        match level {
+            0 | 1 => &self.h1,
+            2 => &self.h2,
+            3 => &self.h3,
+            4 => &self.h4,
+            5 => &self.h5,
+            _ => &self.h6,
+        }
+    }
+}
+
+/// The configuration of a heading level.
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct Heading {
+    /// The text inserted before the heading text.
+    ///
+    /// This is not used when the heading marker is `none`.
+    pub prefix: String,
+
+    /// The number of empty lines above the heading.
+    ///
+    /// This is only used if the heading is not the first section.
+    pub padding_top: u16,
+
+    /// The number of empty lines below the heading.
+    ///
+    /// This is only used if the heading is not the last section.
+    pub padding_bottom: u16,
+
+    /// The style of the heading text.
+    pub style: StyleConfig,
+
+    /// The horizontal alignment of the heading.
+    pub alignment: Alignment,
+}
+
+impl Heading {
+    /// The style of the heading text.
+    pub fn style(&self) -> Style {
+        (&self.style).into()
+    }
+}
+
+impl Default for Heading {
+    fn default() -> Self {
+        Self {
+            prefix: String::new(),
+            padding_top: 1,
+            padding_bottom: 0,
+            style: StyleConfig {
+                underline: true,
+                ..Default::default()
+            },
+            alignment: Alignment::default(),
+        }
+    }
+}
+
+/// The configuration of a text style.
+///
+/// Colours that are not set, and modifiers that are not enabled, are
+/// inherited from the surrounding text.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct StyleConfig {
+    /// The foreground colour.
+    #[serde(with = "color::option", skip_serializing_if = "Option::is_none")]
+    pub fg: Option<Color>,
+
+    /// The background colour.
+    #[serde(with = "color::option", skip_serializing_if = "Option::is_none")]
+    pub bg: Option<Color>,
+
+    /// Whether the text is bold.
+    pub bold: bool,
+
+    /// Whether the text is italic.
+    pub italic: bool,
+
+    /// Whether the text is underlined.
+    pub underline: bool,
+
+    /// Whether the foreground and background colours are swapped.
+    pub reversed: bool,
+
+    /// Whether the text is dimmed.
+    pub dim: bool,
+}
+
+impl From<&StyleConfig> for Style {
+    fn from(source: &StyleConfig) -> Self {
+        let mut style = Style::default();
+        if let Some(fg) = source.fg {
+            style = style.fg(fg);
+        }
+        if let Some(bg) = source.bg {
+            style = style.bg(bg);
+        }
+        [
+            (source.bold, Modifier::BOLD),
+            (source.italic, Modifier::ITALIC),
+            (source.underline, Modifier::UNDERLINED),
+            (source.reversed, Modifier::REVERSED),
+            (source.dim, Modifier::DIM),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .fold(style, |style, (_, modifier)| style.add_modifier(modifier))
+    }
+}
+
+/// The configuration of thematic breaks.
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThematicBreak {
+    /// The text drawn as the rule.
+    ///
+    /// A text containing several lines yields a rule of several lines.
+    pub symbol: String,
+
+    /// Whether to repeat the text to span the full width, rather than to
+    /// draw it once, centered.
+    pub full_width: bool,
+
+    /// The colour of the rule.
+    #[serde(with = "color")]
+    pub color: Color,
+}
+
+impl Default for ThematicBreak {
+    fn default() -> Self {
+        Self {
+            symbol: "─".into(),
+            full_width: true,
+            color: Color::White,
+        }
+    }
+}
+
+/// The configuration of the background drawn behind pages.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct BackgroundConfig {
+    /// The colour filling the background, if any.
+    #[serde(with = "color::option", skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+
+    /// The text repeated to fill the background; if empty, no pattern is
+    /// drawn.
+    ///
+    /// A text containing several lines yields a pattern of several lines.
+    pub pattern: String,
+
+    /// The colour of the pattern.
+    #[serde(with = "color::option", skip_serializing_if = "Option::is_none")]
+    pub pattern_color: Option<Color>,
+}
+
+/// The configuration of the page counter.
 #[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct PageCounter {
+    /// The text of the counter.
+    ///
+    /// The variables `${page.current}` and `${page.total}` are replaced by
+    /// the number of the current page and the number of pages, and
+    /// `${page.words}` and `${page.reading_time}` by the number of words on
+    /// the current page and the estimated time to read them.
+    pub format: String,
+
+    /// The position of the counter.
+    pub position: CounterPosition,
+}
+
+impl Default for PageCounter {
+    fn default() -> Self {
+        Self {
+            format: "${page.current} / ${page.total}".into(),
+            position: CounterPosition::default(),
+        }
+    }
+}
+
+/// The position of the page counter in the bottom border.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CounterPosition {
+    /// The counter is shown to the left.
+    Left,
+
+    /// The counter is centered.
+    Center,
+
+    /// The counter is shown to the right.
+    #[default]
+    Right,
+
+    /// The counter is not shown.
+    Hidden,
+}
+
+/// Replaces the variables of a template, written as `${name}`, by their
+/// values.
+///
+/// Unknown variables are left as they are.
+///
+/// # Arguments
+/// *  `template` - The template.
+/// *  `variables` - A function returning the value of a variable, or `None`
+///    for unknown variables.
+pub fn interpolate<F>(template: &str, variables: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match rest.find('}') {
+            Some(end) => {
+                match variables(&rest[2..end]) {
+                    Some(value) => result.push_str(&value),
+                    None => result.push_str(&rest[..=end]),
+                }
+                rest = &rest[end + 1..];
+            }
+            None => break,
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Information about the source.
+#[derive(Default, Deserialize, Serialize)]
 pub struct Source {
     /// The path to the source document.
     pub path: String,
@@ -50,5 +951,283 @@ where
                 configuration,
             )
         })
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        .map_err(io::Error::other)
+}
+
+/// A partial configuration, overriding parts of a configuration.
+///
+/// Fragments are read from the front matter of presentations, and use the
+/// same format as the configuration file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConfigurationFragment(toml::value::Table);
+
+impl ConfigurationFragment {
+    /// Merges this fragment over a configuration.
+    ///
+    /// Tables are merged recursively, and all other values in this fragment
+    /// replace those of the configuration. Relative paths in this fragment
+    /// are resolved against the directory of the presentation document, like
+    /// those of the configuration file are against its directory.
+    ///
+    /// # Arguments
+    /// *  `configuration` - The configuration to override.
+    /// *  `directory` - The directory of the presentation document.
+    pub fn merge(
+        &self,
+        configuration: &Configuration,
+        directory: &Path,
+    ) -> Result<Configuration, String> {
+        let mut table = match toml::Value::try_from(configuration) {
+            Ok(toml::Value::Table(table)) => table,
+            Ok(_) => unreachable!(),
+            Err(e) => return Err(e.to_string()),
+        };
+        merge_tables(&mut table, &self.0);
+        let mut merged: Configuration = toml::Value::Table(table)
+            .try_into()
+            .map_err(|e| e.to_string())?;
+        if self.0.contains_key("syntax_dir") {
+            merged.syntax_dir = merged
+                .syntax_dir
+                .map(|syntax_dir| directory.join(syntax_dir));
+        }
+        Ok(merged)
+    }
+}
+
+impl FromStr for ConfigurationFragment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s).map(Self).map_err(|e| e.to_string())
+    }
+}
+
+/// Merges a table over another table.
+///
+/// # Arguments
+/// *  `target` - The table to modify.
+/// *  `source` - The table whose values to merge.
+fn merge_tables(target: &mut toml::value::Table, source: &toml::value::Table) {
+    for (key, value) in source {
+        match (target.get_mut(key), value) {
+            (Some(toml::Value::Table(target)), toml::Value::Table(source)) => {
+                merge_tables(target, source)
+            }
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_sample() {
+        let (root, configuration) = load("doc/configuration.toml").unwrap();
+
+        assert_eq!(Path::new("doc"), root);
+        assert_eq!("My Presentation", configuration.title);
+        assert_eq!("─", configuration.thematic_break.symbol);
+        assert_eq!(Ok(()), configuration.validate());
+    }
+
+    #[test]
+    fn theme_mode_detection() {
+        assert_eq!(Some(ThemeMode::Dark), ThemeMode::from_colorfgbg("15;0"));
+        assert_eq!(Some(ThemeMode::Dark), ThemeMode::from_colorfgbg("7;8"));
+        assert_eq!(
+            Some(ThemeMode::Dark),
+            ThemeMode::from_colorfgbg("15;default;0"),
+        );
+        assert_eq!(Some(ThemeMode::Light), ThemeMode::from_colorfgbg("0;15"));
+        assert_eq!(Some(ThemeMode::Light), ThemeMode::from_colorfgbg("0;7"));
+        assert_eq!(None, ThemeMode::from_colorfgbg("0;default"));
+        assert_eq!(None, ThemeMode::from_colorfgbg(""));
+
+        assert_eq!(ThemeMode::Dark, ThemeMode::detect(|_| None));
+        assert_eq!(
+            ThemeMode::Light,
+            ThemeMode::detect(
+                |name| (name == "COLORFGBG").then(|| "0;15".into())
+            ),
+        );
+    }
+
+    #[test]
+    fn color_depth_detection() {
+        let colorterm = |value: Option<&'static str>| {
+            move |name: &str| {
+                value.filter(|_| name == "COLORTERM").map(String::from)
+            }
+        };
+
+        assert_eq!(
+            ColorDepth::TrueColor,
+            ColorDepth::detect(colorterm(Some("truecolor"))),
+        );
+        assert_eq!(
+            ColorDepth::TrueColor,
+            ColorDepth::detect(colorterm(Some("24bit"))),
+        );
+        assert_eq!(
+            ColorDepth::Palette256,
+            ColorDepth::detect(colorterm(Some("yes"))),
+        );
+        assert_eq!(ColorDepth::TrueColor, ColorDepth::detect(colorterm(None)));
+        assert_eq!(
+            Some(ColorDepth::Palette16),
+            toml::from_str::<Configuration>(
+                "title = \"\"\n\
+                color_depth = \"16\"\n\
+                [source]\n\
+                path = \"\"",
+            )
+            .unwrap()
+            .color_depth,
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let valid = || Configuration {
+            title: "Title".into(),
+            ..Default::default()
+        };
+        assert_eq!(Ok(()), valid().validate());
+        assert_eq!(
+            Err(vec!["title: must not be empty".to_string()]),
+            Configuration {
+                title: " ".into(),
+                ..valid()
+            }
+            .validate(),
+        );
+
+        let configuration = Configuration {
+            page_break: Some(presentation::PageBreakCondition::Heading {
+                level: 0,
+            }),
+            autoplay_ms: Some(0),
+            max_content_width: Some(0),
+            thematic_break: ThematicBreak {
+                symbol: String::new(),
+                ..Default::default()
+            },
+            ..valid()
+        };
+        assert_eq!(
+            Err(vec![
+                "page_break: invalid heading level 0, expected 1 to 6"
+                    .to_string(),
+                "autoplay_ms: must be greater than 0".to_string(),
+                "max_content_width: must be greater than 0".to_string(),
+                "thematic_break.symbol: must not be empty".to_string(),
+            ]),
+            configuration.validate(),
+        );
+
+        let configuration = Configuration {
+            page_break: Some(presentation::PageBreakCondition::Comment {
+                marker: " ".into(),
+            }),
+            ..valid()
+        };
+        assert_eq!(
+            Err(vec!["page_break: the marker is empty".to_string()]),
+            configuration.validate(),
+        );
+    }
+
+    #[test]
+    fn merge_fragment() {
+        let mut configuration = Configuration::default();
+        configuration.headings.h1.padding_top = 3;
+        let fragment: ConfigurationFragment = "title = \"Merged\"\n\
+            [headings.h1]\n\
+            prefix = \"> \""
+            .parse()
+            .unwrap();
+        let merged = fragment.merge(&configuration, Path::new("")).unwrap();
+
+        assert_eq!("Merged", merged.title);
+        assert_eq!("> ", merged.headings.h1.prefix);
+        assert_eq!(3, merged.headings.h1.padding_top);
+        assert_eq!(configuration.code_theme, merged.code_theme);
+
+        assert!("title = ".parse::<ConfigurationFragment>().is_err());
+        assert!("section_spacing = \"wide\""
+            .parse::<ConfigurationFragment>()
+            .unwrap()
+            .merge(&configuration, Path::new(""))
+            .is_err());
+
+        // Paths are resolved against the directory of the document, and
+        // those not set by the fragment are kept
+        let configuration = Configuration {
+            syntax_dir: Some("/syntaxes".into()),
+            ..Default::default()
+        };
+        let directory = Path::new("presentations");
+        assert_eq!(
+            Some(Path::new("/syntaxes")),
+            ConfigurationFragment::default()
+                .merge(&configuration, directory)
+                .unwrap()
+                .syntax_dir
+                .as_deref(),
+        );
+        assert_eq!(
+            Some(Path::new("presentations/syntaxes")),
+            "syntax_dir = \"syntaxes\""
+                .parse::<ConfigurationFragment>()
+                .unwrap()
+                .merge(&configuration, directory)
+                .unwrap()
+                .syntax_dir
+                .as_deref(),
+        );
+    }
+
+    #[test]
+    fn style_config() {
+        let style: StyleConfig = toml::from_str(
+            "fg = \"blue\"\n\
+            italic = true\n\
+            underline = true",
+        )
+        .unwrap();
+
+        assert_eq!(
+            StyleConfig {
+                fg: Some(Color::Blue),
+                bg: None,
+                bold: false,
+                italic: true,
+                underline: true,
+                reversed: false,
+                dim: false,
+            },
+            style,
+        );
+        assert_eq!(
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::ITALIC | Modifier::UNDERLINED),
+            Style::from(&style),
+        );
+        assert_eq!(Style::default(), Style::from(&StyleConfig::default()));
+    }
+
+    #[test]
+    fn invalid_color() {
+        let error = toml::from_str::<StyleConfig>("fg = \"purple\"")
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("Invalid colour: purple"), "{}", error);
+    }
 }
@@ -0,0 +1,333 @@
+use serde::{Deserialize, Deserializer, Serializer};
+use tui::style::Color;
+
+use super::ColorDepth;
+
+/// The named colours, in the order of the terminal palette.
+const NAMES: [(&str, Color); 16] = [
+    ("black", Color::Black),
+    ("red", Color::Red),
+    ("green", Color::Green),
+    ("yellow", Color::Yellow),
+    ("blue", Color::Blue),
+    ("magenta", Color::Magenta),
+    ("cyan", Color::Cyan),
+    ("gray", Color::Gray),
+    ("dark-gray", Color::DarkGray),
+    ("light-red", Color::LightRed),
+    ("light-green", Color::LightGreen),
+    ("light-yellow", Color::LightYellow),
+    ("light-blue", Color::LightBlue),
+    ("light-magenta", Color::LightMagenta),
+    ("light-cyan", Color::LightCyan),
+    ("white", Color::White),
+];
+
+/// Alternative names of colours, as commonly used for the terminal palette.
+const ALIASES: [(&str, Color); 10] = [
+    ("grey", Color::Gray),
+    ("bright-black", Color::DarkGray),
+    ("dark-grey", Color::DarkGray),
+    ("bright-red", Color::LightRed),
+    ("bright-green", Color::LightGreen),
+    ("bright-yellow", Color::LightYellow),
+    ("bright-blue", Color::LightBlue),
+    ("bright-magenta", Color::LightMagenta),
+    ("bright-cyan", Color::LightCyan),
+    ("bright-white", Color::White),
+];
+
+/// Parses a colour.
+///
+/// A colour is one of the 16 named terminal colours, `default` for the
+/// colour of the terminal, `#rrggbb` for a specific colour, or an index into
+/// the terminal palette. Names are case insensitive, and words may be
+/// separated by `-`, `_` or nothing at all, so both `light-red` and `LightRed`
+/// are accepted.
+///
+/// # Arguments
+/// *  `s` - The string to parse.
+pub fn parse(s: &str) -> Result<Color, String> {
+    let invalid = || format!("Invalid colour: {}", s);
+    if let Some(hex) = s.strip_prefix('#') {
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let component = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+        Ok(Color::Rgb(
+            component(0).map_err(|_| invalid())?,
+            component(2).map_err(|_| invalid())?,
+            component(4).map_err(|_| invalid())?,
+        ))
+    } else if let Ok(index) = s.parse::<u8>() {
+        Ok(Color::Indexed(index))
+    } else {
+        let normalized = normalize(s);
+        match normalized.as_str() {
+            "default" | "reset" => Ok(Color::Reset),
+            _ => NAMES
+                .iter()
+                .chain(ALIASES.iter())
+                .find(|(name, _)| normalize(name) == normalized)
+                .map(|(_, color)| *color)
+                .ok_or_else(invalid),
+        }
+    }
+}
+
+/// The name of a colour, as accepted by [`parse`].
+///
+/// # Arguments
+/// *  `color` - The colour.
+pub fn name(color: Color) -> String {
+    match color {
+        Color::Reset => "default".into(),
+        Color::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        Color::Indexed(index) => index.to_string(),
+        color => NAMES
+            .iter()
+            .find(|(_, c)| *c == color)
+            .map(|(name, _)| name.to_string())
+            .unwrap(),
+    }
+}
+
+/// The RGB values of the 16 named colours, as used by xterm.
+const PALETTE_16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// The levels of the components of the colour cube of the 256 colour
+/// palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Replaces an RGB colour by the closest colour supported at a colour depth.
+///
+/// All other colours are returned as they are.
+///
+/// # Arguments
+/// *  `color` - The colour to replace.
+/// *  `depth` - The colour depth.
+pub fn quantize(color: Color, depth: ColorDepth) -> Color {
+    let rgb = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        color => return color,
+    };
+    match depth {
+        ColorDepth::TrueColor => color,
+        ColorDepth::Palette256 => {
+            // The closest colour in the 6x6x6 colour cube
+            let level = |component: u8| {
+                (0..CUBE_LEVELS.len())
+                    .min_by_key(|&i| CUBE_LEVELS[i].abs_diff(component))
+                    .unwrap()
+            };
+            let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+            let cube = (
+                16 + 36 * r as u8 + 6 * g as u8 + b as u8,
+                (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]),
+            );
+
+            // The closest colour in the grayscale ramp
+            let average = (rgb.0 as u16 + rgb.1 as u16 + rgb.2 as u16) / 3;
+            let step = ((average.saturating_sub(3)) / 10).min(23) as u8;
+            let level = 8 + 10 * step;
+            let gray = (232 + step, (level, level, level));
+
+            let (index, _) = [cube, gray]
+                .into_iter()
+                .min_by_key(|(_, candidate)| distance(rgb, *candidate))
+                .unwrap();
+            Color::Indexed(index)
+        }
+        ColorDepth::Palette16 => PALETTE_16
+            .iter()
+            .min_by_key(|(_, candidate)| distance(rgb, *candidate))
+            .map(|(color, _)| *color)
+            .unwrap(),
+    }
+}
+
+/// The squared distance between two RGB colours.
+///
+/// # Arguments
+/// *  `a` - The first colour.
+/// *  `b` - The second colour.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    [(a.0, b.0), (a.1, b.1), (a.2, b.2)]
+        .into_iter()
+        .map(|(a, b)| (a.abs_diff(b) as u32).pow(2))
+        .sum()
+}
+
+/// Normalizes the name of a colour for comparison.
+///
+/// # Arguments
+/// *  `name` - The name of the colour.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Serializes a colour as its name.
+pub fn serialize<S>(color: &Color, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&name(*color))
+}
+
+/// Deserializes a colour from its name.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Color, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    parse(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+/// Serialization of optional colours.
+pub mod option {
+    use super::*;
+
+    /// Serializes an optional colour as its name.
+    pub fn serialize<S>(
+        color: &Option<Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match color {
+            Some(color) => super::serialize(color, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserializes an optional colour from its name.
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<Color>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize(deserializer).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_names() {
+        assert_eq!(Ok(Color::Blue), parse("blue"));
+        assert_eq!(Ok(Color::Blue), parse("Blue"));
+        assert_eq!(Ok(Color::LightRed), parse("light-red"));
+        assert_eq!(Ok(Color::LightRed), parse("LightRed"));
+        assert_eq!(Ok(Color::LightRed), parse("light_red"));
+        assert_eq!(Ok(Color::LightRed), parse("bright-red"));
+        assert_eq!(Ok(Color::DarkGray), parse("bright-black"));
+        assert_eq!(Ok(Color::Gray), parse("grey"));
+        assert_eq!(Ok(Color::White), parse("White"));
+        assert_eq!(Ok(Color::White), parse("bright-white"));
+        assert_eq!(Ok(Color::Reset), parse("default"));
+        assert_eq!(Ok(Color::Reset), parse("Reset"));
+    }
+
+    #[test]
+    fn parse_hex() {
+        assert_eq!(Ok(Color::Rgb(0x11, 0x22, 0x33)), parse("#112233"));
+        assert_eq!(Ok(Color::Rgb(0xab, 0xcd, 0xef)), parse("#ABCDEF"));
+        assert_eq!(Err("Invalid colour: #1122".into()), parse("#1122"));
+        assert_eq!(Err("Invalid colour: #11223g".into()), parse("#11223g"));
+        assert_eq!(Err("Invalid colour: #+11223".into()), parse("#+11223"));
+        assert_eq!(Err("Invalid colour: #ééé".into()), parse("#ééé"));
+    }
+
+    #[test]
+    fn parse_indexed() {
+        assert_eq!(Ok(Color::Indexed(0)), parse("0"));
+        assert_eq!(Ok(Color::Indexed(255)), parse("255"));
+        assert_eq!(Err("Invalid colour: 256".into()), parse("256"));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(Err("Invalid colour: ".into()), parse(""));
+        assert_eq!(Err("Invalid colour: purple".into()), parse("purple"));
+        assert_eq!(Err("Invalid colour: light".into()), parse("light"));
+    }
+
+    #[test]
+    fn quantize_truecolor() {
+        let color = Color::Rgb(95, 135, 175);
+        assert_eq!(color, quantize(color, ColorDepth::TrueColor));
+    }
+
+    #[test]
+    fn quantize_256() {
+        let depth = ColorDepth::Palette256;
+        assert_eq!(Color::Indexed(16), quantize(Color::Rgb(0, 0, 0), depth));
+        assert_eq!(Color::Indexed(196), quantize(Color::Rgb(255, 0, 0), depth));
+        assert_eq!(
+            Color::Indexed(67),
+            quantize(Color::Rgb(95, 135, 175), depth),
+        );
+        assert_eq!(
+            Color::Indexed(67),
+            quantize(Color::Rgb(100, 130, 180), depth),
+        );
+        assert_eq!(
+            Color::Indexed(244),
+            quantize(Color::Rgb(128, 128, 128), depth),
+        );
+        assert_eq!(
+            Color::Indexed(231),
+            quantize(Color::Rgb(255, 255, 255), depth),
+        );
+        assert_eq!(Color::Blue, quantize(Color::Blue, depth));
+        assert_eq!(Color::Reset, quantize(Color::Reset, depth));
+    }
+
+    #[test]
+    fn quantize_16() {
+        let depth = ColorDepth::Palette16;
+        assert_eq!(Color::Black, quantize(Color::Rgb(10, 10, 10), depth));
+        assert_eq!(Color::LightRed, quantize(Color::Rgb(250, 20, 20), depth));
+        assert_eq!(Color::Red, quantize(Color::Rgb(180, 30, 30), depth));
+        assert_eq!(Color::DarkGray, quantize(Color::Rgb(120, 120, 130), depth));
+        assert_eq!(Color::LightBlue, quantize(Color::Rgb(90, 90, 240), depth));
+        assert_eq!(Color::White, quantize(Color::Rgb(250, 250, 250), depth));
+        assert_eq!(Color::Indexed(42), quantize(Color::Indexed(42), depth));
+    }
+
+    #[test]
+    fn name_round_trip() {
+        for color in NAMES.iter().map(|(_, color)| *color).chain([
+            Color::Reset,
+            Color::Rgb(1, 2, 3),
+            Color::Indexed(42),
+        ]) {
+            assert_eq!(Ok(color), parse(&name(color)));
+        }
+        assert_eq!("light-red", name(Color::LightRed));
+        assert_eq!("#0102ff", name(Color::Rgb(1, 2, 255)));
+    }
+}
@@ -0,0 +1,697 @@
+//! Loading and rendering presentations.
+//!
+//! A presentation document is parsed into an AST whose nodes are allocated in
+//! a [`comrak::Arena`] owned by the caller. A [`Deck`] and the pages created
+//! from it borrow the arena, so the arena must outlive them; create a new
+//! arena to load a document again.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use comrak::arena_tree::Node;
+use comrak::nodes::Ast;
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::widgets::{Block, Widget};
+
+use crate::configuration::{Configuration, ConfigurationFragment};
+use crate::presentation::{self, Page, PageBreakCondition, Presentation};
+use crate::transform::{self, Footnotes, Highlights};
+use crate::widget::PageWidget;
+
+/// The arena managing memory for the AST of a presentation document.
+pub type Arena<'a> = comrak::Arena<Node<'a, RefCell<Ast>>>;
+
+/// A loaded presentation document.
+pub struct Deck<'a, 'c> {
+    /// The presentation.
+    presentation: Presentation<'a>,
+
+    /// The application configuration.
+    configuration: &'c Configuration,
+
+    /// The application configuration merged with the front matter, if the
+    /// document has any.
+    front_matter: Option<Configuration>,
+
+    /// The non-empty pages of the presentation.
+    pages: Vec<Page<'a>>,
+
+    /// The configuration of every page that overrides it.
+    page_configurations: Vec<Option<Configuration>>,
+}
+
+impl<'a, 'c> Deck<'a, 'c> {
+    /// Loads a presentation document.
+    ///
+    /// # Arguments
+    /// *  `arena` - The arena managing memory for the AST.
+    /// *  `path` - The path to the presentation document.
+    /// *  `configuration` - The application configuration.
+    /// *  `page_break` - The page break condition overriding the
+    ///    configuration, if any.
+    pub fn load(
+        arena: &'a Arena<'a>,
+        path: &Path,
+        configuration: &'c Configuration,
+        page_break: Option<&PageBreakCondition>,
+    ) -> Result<Self, String> {
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        let presentation = presentation::load(arena, path).map_err(|e| {
+            format!(
+                "Failed to load markdown document {}: {}",
+                configuration.source.path, e
+            )
+        })?;
+        let front_matter = presentation
+            .front_matter()
+            .map(|front_matter| {
+                front_matter
+                    .parse::<ConfigurationFragment>()
+                    .and_then(|fragment| {
+                        fragment.merge(configuration, directory)
+                    })
+                    .and_then(validated)
+                    .map_err(|e| {
+                        format!(
+                            "Invalid front matter in {}: {}",
+                            configuration.source.path, e
+                        )
+                    })
+            })
+            .transpose()?;
+
+        let pages = {
+            let configuration = front_matter.as_ref().unwrap_or(configuration);
+            presentation
+                .pages(self::page_break(page_break, configuration))
+                .filter(|page| !page.is_empty())
+                .collect::<Vec<_>>()
+        };
+        let page_configurations = pages
+            .iter()
+            .enumerate()
+            .map(|(i, page)| {
+                page_configuration(
+                    page,
+                    front_matter.as_ref().unwrap_or(configuration),
+                    directory,
+                )
+                .map_err(|e| {
+                    format!("Invalid configuration on page {}: {}", i + 1, e)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Skipped pages are dropped before numbering, so they are not counted
+        let (pages, page_configurations): (Vec<_>, Vec<_>) = pages
+            .into_iter()
+            .zip(page_configurations)
+            .filter(|(_, page_configuration)| {
+                !page_configuration
+                    .as_ref()
+                    .or(front_matter.as_ref())
+                    .unwrap_or(configuration)
+                    .skip
+            })
+            .unzip();
+        if pages.is_empty() {
+            return Err("Invalid presentation: no pages".to_string());
+        }
+
+        let deck = Self {
+            presentation,
+            configuration,
+            front_matter,
+            pages,
+            page_configurations,
+        };
+
+        // Syntax definitions are loaded when transforming pages, where errors
+        // cannot be reported, so they are loaded up front
+        for (_, configuration) in deck.pages() {
+            transform::syntax_set(configuration.syntax_dir.as_deref())?;
+        }
+
+        Ok(deck)
+    }
+
+    /// Keeps only a range of the pages of the presentation.
+    ///
+    /// The pages are numbered after skipped pages have been dropped, and the
+    /// range is clamped to the number of pages.
+    ///
+    /// # Arguments
+    /// *  `range` - The range of pages to keep.
+    pub fn select(&mut self, range: &PageRange) -> Result<(), String> {
+        if range.first > self.pages.len() {
+            return Err(format!(
+                "Invalid page range {}: the presentation has {} pages",
+                range,
+                self.pages.len(),
+            ));
+        }
+        let pages = range.first - 1..range.last.min(self.pages.len());
+        self.pages = self.pages.drain(pages.clone()).collect();
+        self.page_configurations =
+            self.page_configurations.drain(pages).collect();
+        Ok(())
+    }
+
+    /// The configuration of the presentation.
+    pub fn configuration(&self) -> &Configuration {
+        self.front_matter.as_ref().unwrap_or(self.configuration)
+    }
+
+    /// The pages of the presentation with their configuration.
+    pub fn pages(&self) -> impl Iterator<Item = (&Page<'a>, &Configuration)> {
+        self.pages.iter().zip(self.page_configurations.iter().map(
+            |page_configuration| {
+                page_configuration
+                    .as_ref()
+                    .unwrap_or_else(|| self.configuration())
+            },
+        ))
+    }
+
+    /// Converts the footnote definitions of the presentation.
+    pub fn footnotes(&self) -> Footnotes<'a> {
+        Footnotes::from_presentation(&self.presentation, self.configuration())
+    }
+
+    /// Converts the pages of the presentation to widgets.
+    ///
+    /// If enabled, a metadata page and a table of contents precede the
+    /// pages, in that order.
+    pub fn widgets(&'a self) -> Vec<PageWidget<'a>> {
+        self.widgets_with_progress(|_| {})
+    }
+
+    /// Converts the pages of the presentation to widgets, reporting the
+    /// progress.
+    ///
+    /// Converting pages with many code blocks to highlight may take a while
+    /// for large documents. The code blocks of all pages are highlighted in
+    /// parallel before the first page is converted, and both highlighted code
+    /// blocks and converted pages are reported.
+    ///
+    /// # Arguments
+    /// *  `progress` - A function called after every code block has been
+    ///    highlighted and every page of the presentation has been converted.
+    pub fn widgets_with_progress<F>(
+        &'a self,
+        mut progress: F,
+    ) -> Vec<PageWidget<'a>>
+    where
+        F: FnMut(Progress),
+    {
+        let configuration = self.configuration();
+        let footnotes = self.footnotes();
+        let pages = self.pages.len();
+        let highlights = Highlights::from_pages_with_progress(
+            self.pages(),
+            |highlighted, blocks| {
+                progress(Progress::Highlighting(highlighted, blocks))
+            },
+        );
+        configuration
+            .metadata_slide
+            .then(|| PageWidget::metadata(configuration))
+            .into_iter()
+            .chain(configuration.table_of_contents.then(|| {
+                // The pages are numbered after the generated pages
+                let leading = usize::from(configuration.metadata_slide) + 1;
+                PageWidget::table_of_contents(
+                    &self.pages,
+                    leading,
+                    configuration,
+                )
+            }))
+            .chain(self.pages().enumerate().map(
+                |(i, (page, configuration))| {
+                    let widget = PageWidget::with_highlights(
+                        page,
+                        configuration,
+                        &footnotes,
+                        &highlights,
+                    );
+                    progress(Progress::Converting(i + 1, pages));
+                    widget
+                },
+            ))
+            .collect()
+    }
+}
+
+/// The progress of converting the pages of a presentation to widgets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Progress {
+    /// The number of code blocks highlighted, and the total number of code
+    /// blocks.
+    Highlighting(usize, usize),
+
+    /// The number of pages converted, and the total number of pages.
+    Converting(usize, usize),
+}
+
+/// An inclusive range of pages, numbered from 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageRange {
+    /// The number of the first page.
+    pub first: usize,
+
+    /// The number of the last page.
+    pub last: usize,
+}
+
+impl FromStr for PageRange {
+    type Err = String;
+
+    /// Parses a page range.
+    ///
+    /// The format is the first and last page separated by a dash, such as
+    /// `5-9`, or a single page.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (first, last) = s.split_once('-').unwrap_or((s, s));
+        let number = |n: &str| {
+            n.trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| format!("Invalid page range: {}", s))
+        };
+        let (first, last) = (number(first)?, number(last)?);
+        if first > last {
+            Err(format!("Invalid page range: {}", s))
+        } else {
+            Ok(Self { first, last })
+        }
+    }
+}
+
+impl fmt::Display for PageRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.first, self.last)
+    }
+}
+
+/// Loads a presentation document.
+///
+/// The page break condition of the configuration is used; see
+/// [`Deck::load`] to override it.
+///
+/// # Arguments
+/// *  `arena` - The arena managing memory for the AST.
+/// *  `path` - The path to the presentation document.
+/// *  `configuration` - The application configuration.
+pub fn load_presentation<'a, 'c, P>(
+    arena: &'a Arena<'a>,
+    path: P,
+    configuration: &'c Configuration,
+) -> Result<Deck<'a, 'c>, String>
+where
+    P: AsRef<Path>,
+{
+    Deck::load(arena, path.as_ref(), configuration, None)
+}
+
+/// Renders a page.
+///
+/// The area is filled with the background of the page before its content is
+/// rendered.
+///
+/// # Arguments
+/// *  `page` - The page to render.
+/// *  `area` - The area in which to render the page.
+/// *  `buf` - The target buffer.
+pub fn render_page(page: &PageWidget<'_>, area: Rect, buf: &mut Buffer) {
+    Block::default().style(page.style()).render(area, buf);
+    page.render(area, buf);
+}
+
+/// Renders a page into a new buffer, without a terminal.
+///
+/// This is useful to inspect the rendered cells of a page, for example in
+/// tests.
+///
+/// # Arguments
+/// *  `page` - The page to render.
+/// *  `width` - The width of the buffer.
+/// *  `height` - The height of the buffer.
+pub fn render_page_to_buffer(
+    page: &PageWidget<'_>,
+    width: u16,
+    height: u16,
+) -> Buffer {
+    let area = Rect::new(0, 0, width, height);
+    let mut buf = Buffer::empty(area);
+    render_page(page, area, &mut buf);
+    buf
+}
+
+/// Fails if any page contains elements that are not supported.
+///
+/// This is used when watching a presentation document, so that problems are
+/// shown as errors until they are fixed instead of as placeholders.
+///
+/// # Arguments
+/// *  `widgets` - The page widgets.
+pub fn check<'a>(
+    widgets: Vec<PageWidget<'a>>,
+) -> Result<Vec<PageWidget<'a>>, String> {
+    let problems = widgets
+        .iter()
+        .enumerate()
+        .flat_map(|(i, widget)| {
+            widget.diagnostics().iter().map(move |diagnostic| {
+                format!("page {}, {}", i + 1, diagnostic)
+            })
+        })
+        .collect::<Vec<_>>();
+    if problems.is_empty() {
+        Ok(widgets)
+    } else {
+        Err(format!("Invalid presentation: {}", problems.join(", ")))
+    }
+}
+
+/// Loads the configuration of a single page.
+///
+/// If the page contains a configuration block, it is merged over the
+/// configuration of the presentation; otherwise `None` is returned.
+///
+/// # Arguments
+/// *  `page` - The page.
+/// *  `configuration` - The configuration of the presentation.
+/// *  `directory` - The directory of the presentation document.
+pub fn page_configuration(
+    page: &Page<'_>,
+    configuration: &Configuration,
+    directory: &Path,
+) -> Result<Option<Configuration>, String> {
+    page.configuration()
+        .map(|source| {
+            source
+                .parse::<ConfigurationFragment>()
+                .and_then(|fragment| fragment.merge(configuration, directory))
+                .and_then(validated)
+        })
+        .transpose()
+}
+
+/// Validates a configuration merged with a fragment.
+///
+/// # Arguments
+/// *  `configuration` - The configuration to validate.
+fn validated(configuration: Configuration) -> Result<Configuration, String> {
+    configuration
+        .validate()
+        .map(|_| configuration)
+        .map_err(|problems| problems.join(", "))
+}
+
+/// The effective page break condition.
+///
+/// A condition passed explicitly takes precedence over the configuration.
+///
+/// # Arguments
+/// *  `page_break` - The page break condition overriding the configuration,
+///    if any.
+/// *  `configuration` - The application configuration.
+fn page_break(
+    page_break: Option<&PageBreakCondition>,
+    configuration: &Configuration,
+) -> PageBreakCondition {
+    page_break
+        .cloned()
+        .or_else(|| configuration.page_break.clone())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use tui::style::Color;
+
+    use super::*;
+
+    #[test]
+    fn page_break_override() {
+        let mut configuration: Configuration = toml::from_str(
+            "title = \"Title\"\n\
+            page_break = { type = \"heading\", level = 2 }\n\
+            [source]\n\
+            path = \"\"",
+        )
+        .unwrap();
+        let page_break = "heading:3".parse::<PageBreakCondition>().unwrap();
+
+        assert_eq!(
+            PageBreakCondition::Heading { level: 2 },
+            super::page_break(None, &configuration),
+        );
+        assert_eq!(
+            PageBreakCondition::Heading { level: 3 },
+            super::page_break(Some(&page_break), &configuration),
+        );
+        configuration.page_break = None;
+        assert_eq!(
+            PageBreakCondition::default(),
+            super::page_break(None, &configuration),
+        );
+    }
+
+    #[test]
+    fn skip() {
+        let configuration: Configuration = toml::from_str(
+            "title = \"Title\"\n\
+            [source]\n\
+            path = \"\"",
+        )
+        .unwrap();
+        let arena = comrak::Arena::new();
+        let deck =
+            load_presentation(&arena, "test-resources/skip.md", &configuration)
+                .unwrap();
+
+        let widgets = deck.widgets();
+        assert_eq!(2, widgets.len());
+        assert_eq!(
+            vec![Some("Page 1".to_string()), Some("Page 3".to_string())],
+            deck.pages()
+                .map(|(page, _)| page.title())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn page_range() {
+        assert_eq!(
+            Ok(PageRange { first: 5, last: 9 }),
+            "5-9".parse::<PageRange>(),
+        );
+        assert_eq!(
+            Ok(PageRange { first: 3, last: 3 }),
+            "3".parse::<PageRange>(),
+        );
+        assert!("0-2".parse::<PageRange>().is_err());
+        assert!("4-2".parse::<PageRange>().is_err());
+        assert!("2-".parse::<PageRange>().is_err());
+        assert!("a-b".parse::<PageRange>().is_err());
+    }
+
+    #[test]
+    fn select() {
+        let configuration: Configuration = toml::from_str(
+            "title = \"Title\"\n\
+            [source]\n\
+            path = \"\"",
+        )
+        .unwrap();
+        let titles = |deck: &Deck| {
+            deck.pages()
+                .map(|(page, _)| page.title().unwrap())
+                .collect::<Vec<_>>()
+        };
+        let arena = comrak::Arena::new();
+        let load = || {
+            load_presentation(
+                &arena,
+                "test-resources/footnotes-across.md",
+                &configuration,
+            )
+            .unwrap()
+        };
+
+        let mut deck = load();
+        let all = titles(&deck);
+        deck.select(&PageRange { first: 2, last: 9 }).unwrap();
+        assert_eq!(all[1..], titles(&deck));
+
+        let mut deck = load();
+        deck.select(&PageRange { first: 2, last: 2 }).unwrap();
+        assert_eq!(all[1..2], titles(&deck));
+        assert_eq!(1, deck.widgets().len());
+
+        // Footnotes are listed on the pages referencing them that are kept
+        let mut deck = load();
+        deck.select(&PageRange { first: 3, last: 3 }).unwrap();
+        let widgets = deck.widgets();
+        let area = Rect::new(0, 0, 30, 8);
+        let mut buf = Buffer::empty(area);
+        render_page(&widgets[0], area, &mut buf);
+        assert_eq!(
+            "¹   The source of the claim.  ",
+            (0..area.width)
+                .map(|x| buf.get(x, area.bottom() - 1).symbol.as_str())
+                .collect::<String>(),
+        );
+
+        let mut deck = load();
+        assert!(deck
+            .select(&PageRange {
+                first: all.len() + 1,
+                last: all.len() + 1,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn widgets_progress() {
+        let configuration: Configuration = toml::from_str(
+            "title = \"Title\"\n\
+            table_of_contents = true\n\
+            [source]\n\
+            path = \"\"",
+        )
+        .unwrap();
+        let arena = comrak::Arena::new();
+        let deck =
+            load_presentation(&arena, "test-resources/code.md", &configuration)
+                .unwrap();
+
+        // The highlighted code blocks and the converted pages are reported
+        // one by one
+        let mut calls = Vec::new();
+        let widgets =
+            deck.widgets_with_progress(|progress| calls.push(progress));
+        let pages = deck.pages().count();
+        assert_eq!(pages + 1, widgets.len());
+        assert_eq!(
+            (1..=6)
+                .map(|block| Progress::Highlighting(block, 6))
+                .chain(
+                    (1..=pages).map(|page| Progress::Converting(page, pages))
+                )
+                .collect::<Vec<_>>(),
+            calls,
+        );
+    }
+
+    #[test]
+    fn table_of_contents_numbers() {
+        let configuration: Configuration = toml::from_str(
+            "title = \"Title\"\n\
+            metadata_slide = true\n\
+            table_of_contents = true\n\
+            [source]\n\
+            path = \"\"",
+        )
+        .unwrap();
+        let arena = comrak::Arena::new();
+        let deck = load_presentation(
+            &arena,
+            "test-resources/presentation.md",
+            &configuration,
+        )
+        .unwrap();
+
+        // The pages follow the metadata page and the table of contents
+        let widgets = deck.widgets();
+        let area = Rect::new(0, 0, 20, 4);
+        let mut buf = Buffer::empty(area);
+        widgets[1].render(area, &mut buf);
+        assert_eq!(
+            "3.  Page 1",
+            (0..10)
+                .map(|x| buf.get(x, 2).symbol.as_str())
+                .collect::<String>(),
+        );
+    }
+
+    #[test]
+    fn check() {
+        let configuration: Configuration = toml::from_str(
+            "title = \"Title\"\n\
+            [source]\n\
+            path = \"\"",
+        )
+        .unwrap();
+        let arena = comrak::Arena::new();
+
+        let deck = load_presentation(
+            &arena,
+            "test-resources/headings.md",
+            &configuration,
+        )
+        .unwrap();
+        let count = deck.widgets().len();
+        assert_eq!(count, super::check(deck.widgets()).unwrap().len());
+
+        let deck = load_presentation(
+            &arena,
+            "test-resources/unsupported.md",
+            &configuration,
+        )
+        .unwrap();
+        assert_eq!(
+            Some(
+                "Invalid presentation: page 1, line 3: HTML is not supported"
+                    .to_string()
+            ),
+            super::check(deck.widgets()).err(),
+        );
+    }
+
+    #[test]
+    fn page_background() {
+        let configuration: Configuration = toml::from_str(
+            "title = \"Title\"\n\
+            text_background = \"black\"\n\
+            [source]\n\
+            path = \"\"",
+        )
+        .unwrap();
+        let arena = comrak::Arena::new();
+        let presentation =
+            presentation::load(&arena, "test-resources/page-configuration.md")
+                .unwrap();
+        let pages = presentation
+            .pages(PageBreakCondition::Heading { level: 1 })
+            .collect::<Vec<_>>();
+        let footnotes = Footnotes::default();
+
+        let directory = Path::new("test-resources");
+        assert!(page_configuration(&pages[0], &configuration, directory)
+            .unwrap()
+            .is_none());
+        let page_configuration =
+            page_configuration(&pages[1], &configuration, directory)
+                .unwrap()
+                .unwrap();
+        assert_eq!(Color::Blue, page_configuration.text_background);
+        assert_eq!(
+            Some(Color::Black),
+            PageWidget::new(&pages[0], &configuration, &footnotes)
+                .style()
+                .bg,
+        );
+        assert_eq!(
+            Some(Color::Blue),
+            PageWidget::new(&pages[1], &page_configuration, &footnotes)
+                .style()
+                .bg,
+        );
+    }
+}
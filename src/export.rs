@@ -0,0 +1,89 @@
+use std::str::FromStr;
+
+use tui::style::Color;
+
+use crate::configuration::Configuration;
+use crate::presentation;
+use crate::transform::{Context, Diagnostic, Footnotes, Sections};
+use crate::widget::FootnoteListing;
+
+pub mod html;
+pub mod text;
+
+/// A format to which a presentation can be exported.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// A standalone HTML document.
+    Html,
+
+    /// Plain UTF-8 text.
+    Text,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "html" => Ok(Format::Html),
+            "text" => Ok(Format::Text),
+            _ => Err(format!("Unknown export format: {}", s)),
+        }
+    }
+}
+
+/// A page of a presentation to export.
+pub struct Page<'a> {
+    /// The sections of the page.
+    pub sections: Sections<'a>,
+
+    /// The footnotes referenced from the page.
+    pub footnotes: FootnoteListing<'a>,
+
+    /// The background colour of the theme used to highlight code.
+    pub code_background: Option<Color>,
+}
+
+impl<'a> Page<'a> {
+    /// Converts a page of a presentation for export.
+    ///
+    /// The problems found when converting the page are returned with it.
+    ///
+    /// # Arguments
+    /// *  `page` - The page.
+    /// *  `configuration` - The application configuration.
+    /// *  `footnotes` - The footnotes of the presentation.
+    pub fn new(
+        page: &'a presentation::Page<'a>,
+        configuration: &Configuration,
+        footnotes: &Footnotes<'a>,
+    ) -> (Self, Vec<Diagnostic>) {
+        let (sections, diagnostics) = Sections::from_page(page, configuration);
+        let code_background = Context::new(configuration)
+            .theme
+            .settings
+            .background
+            .map(|color| Color::Rgb(color.r, color.g, color.b));
+        (
+            Self {
+                sections,
+                footnotes: FootnoteListing::new(page, configuration, footnotes),
+                code_background,
+            },
+            diagnostics,
+        )
+    }
+}
+
+/// Exports the pages of a presentation.
+///
+/// # Arguments
+/// *  `format` - The export format.
+/// *  `title` - The title of the presentation.
+/// *  `pages` - The pages.
+pub fn export(format: Format, title: &str, pages: &[Page<'_>]) -> String {
+    match format {
+        Format::Html => html::export(title, pages),
+        Format::Text => text::export(pages),
+    }
+}
@@ -0,0 +1,302 @@
+use tui::style::{Color, Modifier};
+use tui::text::Span;
+
+use super::Page;
+use crate::transform::{Footnotes, Section, Sections};
+
+/// The style sheet of exported documents.
+///
+/// Every page fills the viewport, and scrolling snaps to page boundaries.
+const STYLE: &str = "\
+html { scroll-snap-type: y mandatory; }
+body { margin: 0; font-family: sans-serif; }
+section.page {
+  box-sizing: border-box;
+  min-height: 100vh;
+  padding: 2em 4em;
+  scroll-snap-align: start;
+  break-after: page;
+}
+div.columns { display: flex; gap: 2em; }
+div.column { flex: 1; }
+pre { background: var(--code-background); padding: 0.5em; }
+aside.footnotes { border-top: 1px solid; font-size: smaller; }
+div.footnote { display: flex; gap: 0.5em; }
+";
+
+/// Exports the pages of a presentation as a standalone HTML document.
+///
+/// Code is coloured as when highlighted in the terminal, on the background of
+/// the highlighting theme, and the footnotes referenced from a page are listed
+/// at its end.
+///
+/// # Arguments
+/// *  `title` - The title of the presentation.
+/// *  `pages` - The pages.
+pub fn export(title: &str, pages: &[Page<'_>]) -> String {
+    let mut target = String::new();
+    target.push_str("<!DOCTYPE html>\n<html>\n<head>\n");
+    target.push_str("<meta charset=\"utf-8\">\n");
+    target.push_str(&format!("<title>{}</title>\n", escape(title)));
+    target.push_str(&format!("<style>\n{}</style>\n", STYLE));
+    target.push_str("</head>\n<body>\n");
+    for page in pages {
+        match page.code_background.and_then(color) {
+            Some(color) => target.push_str(&format!(
+                "<section class=\"page\" style=\"--code-background: {}\">\n",
+                color,
+            )),
+            None => target.push_str("<section class=\"page\">\n"),
+        }
+        sections(&page.sections, &mut target);
+        if !page.footnotes.is_empty() {
+            target.push_str("<aside class=\"footnotes\">\n");
+            for (index, content) in page.footnotes.footnotes() {
+                target.push_str(&format!(
+                    "<div class=\"footnote\">\n<sup>{}</sup>\n<div>\n",
+                    Footnotes::index_to_superscript(index),
+                ));
+                sections(content, &mut target);
+                target.push_str("</div>\n</div>\n");
+            }
+            target.push_str("</aside>\n");
+        }
+        target.push_str("</section>\n");
+    }
+    target.push_str("</body>\n</html>\n");
+    target
+}
+
+/// Writes a collection of sections as HTML.
+///
+/// # Arguments
+/// *  `source` - The sections to write.
+/// *  `target` - The target string.
+fn sections(source: &Sections<'_>, target: &mut String) {
+    for source in source.iter() {
+        section(source, target);
+    }
+}
+
+/// Writes a single section as HTML.
+///
+/// # Arguments
+/// *  `source` - The section to write.
+/// *  `target` - The target string.
+fn section(source: &Section<'_>, target: &mut String) {
+    use Section::*;
+    match source {
+        BlockQuote { content, .. } => {
+            target.push_str("<blockquote>\n");
+            sections(content, target);
+            target.push_str("</blockquote>\n");
+        }
+        Code { text, .. } => {
+            target.push_str("<pre><code>");
+            target.push_str(
+                &text
+                    .lines
+                    .iter()
+                    .map(|line| code_spans(&line.0))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+            target.push_str("</code></pre>\n");
+        }
+        Columns { columns } => {
+            target.push_str("<div class=\"columns\">\n");
+            for column in columns {
+                target.push_str("<div class=\"column\">\n");
+                sections(column, target);
+                target.push_str("</div>\n");
+            }
+            target.push_str("</div>\n");
+        }
+        Heading { text, level, .. } => {
+            target.push_str(&format!(
+                "<h{level}>{}</h{level}>\n",
+                spans(&text.0),
+                level = level,
+            ));
+        }
+        Image { path, alt } => {
+            target.push_str(&format!(
+                "<p><img src=\"{}\" alt=\"{}\"></p>\n",
+                escape(path),
+                escape(alt),
+            ));
+        }
+        Line { text, .. } => {
+            target.push_str(&format!("<p>{}</p>\n", spans(&text.0)));
+        }
+        List { content } => match content.first() {
+            Some(ListItemOrdered { ordinal, .. }) => {
+                target.push_str(&format!("<ol start=\"{}\">\n", ordinal));
+                sections(content, target);
+                target.push_str("</ol>\n");
+            }
+            _ => {
+                target.push_str("<ul>\n");
+                sections(content, target);
+                target.push_str("</ul>\n");
+            }
+        },
+        ListItemOrdered { content, .. } | ListItemUnordered { content, .. } => {
+            target.push_str("<li>\n");
+            sections(content, target);
+            target.push_str("</li>\n");
+        }
+        Paragraph { text, .. } => {
+            target.push_str("<p>");
+            target.push_str(
+                &text
+                    .lines
+                    .iter()
+                    .map(|line| spans(&line.0))
+                    .collect::<Vec<_>>()
+                    .join("<br>\n"),
+            );
+            target.push_str("</p>\n");
+        }
+        Table { header, rows, .. } => {
+            target.push_str("<table>\n");
+            for (cells, tag) in std::iter::once((header, "th"))
+                .chain(rows.iter().map(|cells| (cells, "td")))
+            {
+                target.push_str("<tr>");
+                for cell in cells {
+                    target.push_str(&format!(
+                        "<{tag}>{}</{tag}>",
+                        cell.lines
+                            .iter()
+                            .map(|line| spans(&line.0))
+                            .collect::<Vec<_>>()
+                            .join("<br>"),
+                        tag = tag,
+                    ));
+                }
+                target.push_str("</tr>\n");
+            }
+            target.push_str("</table>\n");
+        }
+        ThematicBreak { .. } => target.push_str("<hr>\n"),
+    }
+}
+
+/// Converts styled spans to HTML.
+///
+/// Only modifiers with a semantic meaning are kept; colours are dropped.
+///
+/// # Arguments
+/// *  `source` - The spans to convert.
+fn spans(source: &[Span<'_>]) -> String {
+    const TAGS: [(Modifier, &str); 3] = [
+        (Modifier::BOLD, "strong"),
+        (Modifier::ITALIC, "em"),
+        (Modifier::CROSSED_OUT, "del"),
+    ];
+    source
+        .iter()
+        .map(|span| {
+            let tags = TAGS
+                .iter()
+                .filter(|(modifier, _)| {
+                    span.style.add_modifier.contains(*modifier)
+                })
+                .map(|(_, tag)| tag)
+                .collect::<Vec<_>>();
+            let mut result = String::new();
+            for tag in tags.iter() {
+                result.push_str(&format!("<{}>", tag));
+            }
+            result.push_str(&escape(&span.content));
+            for tag in tags.iter().rev() {
+                result.push_str(&format!("</{}>", tag));
+            }
+            result
+        })
+        .collect()
+}
+
+/// Converts highlighted code to HTML.
+///
+/// Unlike for other text, the colours are kept.
+///
+/// # Arguments
+/// *  `source` - The spans to convert.
+fn code_spans(source: &[Span<'_>]) -> String {
+    source
+        .iter()
+        .map(|span| match span.style.fg.and_then(color) {
+            Some(color) => format!(
+                "<span style=\"color: {}\">{}</span>",
+                color,
+                spans(std::slice::from_ref(span)),
+            ),
+            None => spans(std::slice::from_ref(span)),
+        })
+        .collect()
+}
+
+/// Converts a colour to CSS.
+///
+/// Only RGB colours are converted, since the other colours depend on the
+/// terminal.
+///
+/// # Arguments
+/// *  `color` - The colour to convert.
+fn color(color: Color) -> Option<String> {
+    match color {
+        Color::Rgb(r, g, b) => Some(format!("#{:02x}{:02x}{:02x}", r, g, b)),
+        _ => None,
+    }
+}
+
+/// Escapes text for inclusion in an HTML document.
+///
+/// # Arguments
+/// *  `text` - The text to escape.
+fn escape(text: &str) -> String {
+    text.chars().fold(String::new(), |mut acc, c| {
+        match c {
+            '&' => acc.push_str("&amp;"),
+            '<' => acc.push_str("&lt;"),
+            '>' => acc.push_str("&gt;"),
+            '"' => acc.push_str("&quot;"),
+            c => acc.push(c),
+        }
+        acc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::testing;
+
+    #[test]
+    fn export_presentation() {
+        let presentation = testing::load("test-resources/export.md");
+        let pages = testing::pages(&presentation);
+        let configuration = Configuration::default();
+        let footnotes =
+            Footnotes::from_presentation(&presentation, &configuration);
+        let pages = pages
+            .iter()
+            .map(|page| Page::new(page, &configuration, &footnotes).0)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            fs::read_to_string("test-resources/export.html").unwrap(),
+            export("A <presentation>", &pages),
+        );
+    }
+
+    #[test]
+    fn escape_special() {
+        assert_eq!("a &lt;b&gt; &amp; &quot;c&quot;", escape("a <b> & \"c\""));
+    }
+}
@@ -0,0 +1,45 @@
+use super::Page;
+
+/// The line separating pages.
+const PAGE_SEPARATOR: &str =
+    "================================================================";
+
+/// Exports the pages of a presentation as plain text.
+///
+/// # Arguments
+/// *  `pages` - The pages.
+pub fn export(pages: &[Page<'_>]) -> String {
+    pages
+        .iter()
+        .map(|page| page.sections.text() + "\n")
+        .collect::<Vec<_>>()
+        .join(&format!("\n{}\n\n", PAGE_SEPARATOR))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::testing;
+    use crate::transform::Footnotes;
+
+    #[test]
+    fn export_presentation() {
+        let presentation = testing::load("test-resources/export.md");
+        let pages = testing::pages(&presentation);
+        let configuration = Configuration::default();
+        let footnotes =
+            Footnotes::from_presentation(&presentation, &configuration);
+        let pages = pages
+            .iter()
+            .map(|page| Page::new(page, &configuration, &footnotes).0)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            fs::read_to_string("test-resources/export.txt").unwrap(),
+            export(&pages),
+        );
+    }
+}
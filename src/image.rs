@@ -0,0 +1,157 @@
+use std::cell::RefCell;
+use std::env;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use tui::layout::Rect;
+
+thread_local! {
+    /// The images placed during the last render, and whether any images were
+    /// shown before that.
+    static PLACEMENTS: RefCell<(Vec<(String, Rect)>, bool)> =
+        const { RefCell::new((Vec::new(), false)) };
+}
+
+/// Whether the terminal supports the Kitty graphics protocol.
+///
+/// The result is determined once from the environment.
+pub fn is_supported() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| supports_graphics(|name| env::var(name).ok()))
+}
+
+/// Determines whether a terminal supports the Kitty graphics protocol given
+/// its environment.
+///
+/// # Arguments
+/// *  `var` - A function returning the value of an environment variable.
+pub fn supports_graphics<F>(var: F) -> bool
+where
+    F: Fn(&str) -> Option<String>,
+{
+    var("KITTY_WINDOW_ID").is_some()
+        || var("TERM").is_some_and(|term| term.contains("kitty"))
+        || var("TERM_PROGRAM").is_some_and(|program| program == "WezTerm")
+}
+
+/// Places an image in an area of the screen.
+///
+/// The image is not shown until [`flush`] is called. Only PNG images are
+/// supported; if the terminal does not support images, or the image is not a
+/// PNG image, this function returns `false` and the caller should render a
+/// fallback.
+///
+/// # Arguments
+/// *  `path` - The path to the image, relative to the presentation.
+/// *  `area` - The area to fill.
+pub fn place(path: &str, area: Rect) -> bool {
+    if is_supported() && path.to_lowercase().ends_with(".png") {
+        PLACEMENTS.with(|placements| {
+            placements.borrow_mut().0.push((path.into(), area))
+        });
+        true
+    } else {
+        false
+    }
+}
+
+/// Discards all images placed since the last flush without showing them.
+pub fn discard() {
+    PLACEMENTS.with(|placements| placements.borrow_mut().0.clear());
+}
+
+/// Shows all images placed since the last flush, and removes all previously
+/// shown images.
+///
+/// # Arguments
+/// *  `target` - The terminal.
+/// *  `directory` - The directory against which relative paths are resolved.
+pub fn flush<W>(target: &mut W, directory: &Path) -> io::Result<()>
+where
+    W: Write,
+{
+    let (placements, shown) = PLACEMENTS.with(|placements| {
+        let mut placements = placements.borrow_mut();
+        let shown = placements.1;
+        placements.1 = !placements.0.is_empty();
+        (placements.0.drain(..).collect::<Vec<_>>(), shown)
+    });
+
+    if shown {
+        write!(target, "\x1b_Ga=d,q=2\x1b\\")?;
+    }
+    for (path, area) in placements {
+        let path = directory.join(path);
+        write!(
+            target,
+            "\x1b7\x1b[{};{}H\x1b_Gf=100,t=f,a=T,c={},r={},q=2;{}\x1b\\\x1b8",
+            area.y + 1,
+            area.x + 1,
+            area.width,
+            area.height,
+            base64(path.to_string_lossy().as_bytes()),
+        )?;
+    }
+    target.flush()
+}
+
+/// Encodes data as base 64.
+///
+/// # Arguments
+/// *  `data` - The data to encode.
+pub(crate) fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    data.chunks(3)
+        .flat_map(|chunk| {
+            let value = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |acc, (i, b)| acc | (*b as u32) << (16 - 8 * i));
+            (0..4).map(move |i| {
+                if i <= chunk.len() {
+                    ALPHABET[(value >> (18 - 6 * i) & 0x3f) as usize] as char
+                } else {
+                    '='
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn supports_graphics_detection() {
+        let environment = |vars: &[(&str, &str)]| {
+            let vars = vars
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>();
+            move |name: &str| vars.get(name).cloned()
+        };
+
+        assert!(supports_graphics(environment(&[("TERM", "xterm-kitty")])));
+        assert!(supports_graphics(environment(&[("KITTY_WINDOW_ID", "1")])));
+        assert!(supports_graphics(environment(&[(
+            "TERM_PROGRAM",
+            "WezTerm"
+        )])));
+        assert!(!supports_graphics(environment(&[("TERM", "xterm")])));
+        assert!(!supports_graphics(environment(&[])));
+    }
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!("", base64(b""));
+        assert_eq!("Zg==", base64(b"f"));
+        assert_eq!("Zm8=", base64(b"fo"));
+        assert_eq!("Zm9v", base64(b"foo"));
+        assert_eq!("L3RtcC9hLnBuZw==", base64(b"/tmp/a.png"));
+    }
+}
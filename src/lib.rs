@@ -0,0 +1,40 @@
+//! Presentations in the terminal, written in markdown.
+//!
+//! The library exposes the structure of presentations, so that other tools
+//! can inspect a presentation without presenting it, and the widgets used to
+//! render pages, so that other terminal applications can embed slides:
+//!
+//! ```no_run
+//! use rupert::configuration::Configuration;
+//!
+//! let configuration = Configuration::default();
+//! let arena = comrak::Arena::new();
+//! let deck = rupert::load_presentation(
+//!     &arena,
+//!     "presentation.md",
+//!     &configuration,
+//! )
+//! .unwrap();
+//! for page in deck.widgets() {
+//!     // Render the page with rupert::render_page, or inspect it with
+//!     // rupert::render_page_to_buffer
+//! }
+//! ```
+//!
+//! The pages borrow the arena holding the parsed document, so the arena must
+//! outlive the deck and its pages.
+
+pub mod clipboard;
+pub mod commands;
+pub mod configuration;
+pub mod deck;
+pub mod export;
+pub mod image;
+pub mod presentation;
+pub mod transform;
+pub mod widget;
+
+#[cfg(test)]
+mod testing;
+
+pub use deck::{load_presentation, render_page, render_page_to_buffer, Deck};
@@ -1,71 +1,351 @@
 use std::env;
+use std::fs;
 use std::path;
 use std::process;
 
-mod configuration;
-mod presentation;
-mod transform;
-mod widget;
-
+mod resume;
 mod ui;
 
+use rupert::deck::{self, Deck, PageRange, Progress};
+use rupert::{
+    clipboard, commands, configuration, export, image, presentation, transform,
+    widget,
+};
+
+/// The size in bytes of presentation documents above which the progress of
+/// loading them is printed.
+const PROGRESS_THRESHOLD: u64 = 256 * 1024;
+
+/// Command line options.
+#[derive(Debug, Default, PartialEq)]
+struct Options {
+    /// The format to export to instead of presenting.
+    export: Option<export::Format>,
+
+    /// The page break condition overriding the configuration.
+    page_break: Option<presentation::PageBreakCondition>,
+
+    /// The range of pages to present, if not all.
+    slides: Option<PageRange>,
+
+    /// Whether to reload the presentation document when it is modified.
+    watch: bool,
+
+    /// Whether to print commands instead of running them.
+    dry_run: bool,
+
+    /// Whether to print the effective configuration instead of presenting.
+    print_config: bool,
+}
+
 fn run<P>(
     root: P,
-    configuration: configuration::Configuration,
+    mut configuration: configuration::Configuration,
+    options: Options,
 ) -> Result<(), String>
 where
     P: AsRef<path::Path>,
 {
-    let arena = comrak::Arena::new();
-    let presentation = presentation::load(
-        &arena,
-        root.as_ref().join(&configuration.source.path),
-    )
-    .map_err(|e| {
-        format!(
-            "Failed to load markdown document {}: {}",
-            configuration.source.path, e
-        )
+    configuration.validate().map_err(|problems| {
+        format!("Invalid configuration: {}", problems.join(", "))
     })?;
+    let path = root.as_ref().join(&configuration.source.path);
+    configuration.syntax_dir = configuration
+        .syntax_dir
+        .map(|directory| root.as_ref().join(directory));
+    configuration.commands.dry_run |= options.dry_run;
+    configuration.monochrome |=
+        env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    if configuration.theme_mode == configuration::ThemeMode::Auto {
+        configuration.theme_mode =
+            configuration::ThemeMode::detect(|name| env::var(name).ok());
+    }
+    configuration.color_depth = configuration.color_depth.or_else(|| {
+        Some(configuration::ColorDepth::detect(|name| {
+            env::var(name).ok()
+        }))
+    });
+
+    if options.print_config {
+        print!(
+            "{}",
+            effective_configuration(
+                &path,
+                &configuration,
+                options.page_break.as_ref(),
+            )?,
+        );
+        return Ok(());
+    }
 
-    let pages = Ok(presentation
-        .pages(configuration.page_break.clone().unwrap_or_default())
-        .collect::<Vec<_>>())
-    .and_then(|pages| {
-        if pages.len() < 1 {
-            Err(format!("Invalid presentation: no pages"))
-        } else {
-            Ok(pages)
+    if let Some(format) = options.export {
+        let arena = comrak::Arena::new();
+        let document = load(&arena, &path, &configuration, &options)?;
+        let footnotes = document.footnotes();
+        let mut pages = Vec::new();
+        for (page, configuration) in document.pages() {
+            let (page, diagnostics) =
+                export::Page::new(page, configuration, &footnotes);
+            for diagnostic in diagnostics {
+                eprintln!("{}: {}", configuration.source.path, diagnostic);
+            }
+            pages.push(page);
         }
-    })?;
+        print!(
+            "{}",
+            export::export(format, &document.configuration().title, &pages)
+        );
+        return Ok(());
+    }
+
+    // The terminal is initialised once the document has been loaded, and
+    // kept while reloading it
+    let mut terminal = None;
+    let positions = configuration
+        .resume
+        .then(|| resume::state_file(|name| env::var(name).ok()))
+        .flatten();
+    // The positions are stored for the whole presentation document, while
+    // only the selected pages are shown
+    let offset = options.slides.as_ref().map_or(0, |slides| slides.first - 1);
+    let mut page = positions
+        .as_deref()
+        .and_then(|positions| resume::Positions::load(positions).get(&path))
+        .map_or(0, |page| page.saturating_sub(offset));
+    // Once reloaded, errors are shown instead of the pages, as when watching
+    let mut reloaded = false;
+    loop {
+        let arena = comrak::Arena::new();
+        let document = load(&arena, &path, &configuration, &options);
+        let loaded =
+            document
+                .as_ref()
+                .map_err(String::clone)
+                .and_then(|document| {
+                    // The progress is printed only before the terminal is
+                    // initialised, since the UI would otherwise be overwritten
+                    let widgets = if terminal.is_none() && is_large(&path) {
+                        document.widgets_with_progress(print_progress)
+                    } else {
+                        document.widgets()
+                    };
+
+                    // When watching, elements that are not supported are shown
+                    // as errors until fixed instead of as placeholders
+                    let widgets = if options.watch {
+                        deck::check(widgets)?
+                    } else {
+                        widgets
+                    };
+                    Ok((document.configuration(), widgets))
+                });
+        let (configuration, widgets) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) if options.watch || reloaded => (
+                &configuration,
+                vec![widget::PageWidget::error(&e, &configuration)],
+            ),
+            Err(e) => return Err(e),
+        };
+
+        if terminal.is_none() {
+            terminal = Some(ui::Terminal::new()?);
+        }
+        match ui::run(
+            terminal.as_mut().unwrap(),
+            configuration,
+            &path,
+            widgets,
+            options.watch,
+            page,
+        )? {
+            ui::Exit::Quit { page } => {
+                if let Some(positions) = positions {
+                    let mut saved = resume::Positions::load(&positions);
+                    saved.set(&path, offset + page);
+                    saved.save(&positions).map_err(|e| {
+                        format!(
+                            "Failed to save the position to {}: {}",
+                            positions.display(),
+                            e,
+                        )
+                    })?;
+                }
+                return Ok(());
+            }
+            ui::Exit::Reload { page: current } => {
+                page = current;
+                reloaded = true;
+            }
+        }
+    }
+}
+
+/// Loads a presentation document, keeping only the range of pages passed on
+/// the command line.
+///
+/// # Arguments
+/// *  `arena` - The arena managing memory for the AST.
+/// *  `path` - The path to the presentation document.
+/// *  `configuration` - The application configuration.
+/// *  `options` - The command line options.
+fn load<'a, 'c>(
+    arena: &'a rupert::deck::Arena<'a>,
+    path: &path::Path,
+    configuration: &'c configuration::Configuration,
+    options: &Options,
+) -> Result<Deck<'a, 'c>, String> {
+    let mut document =
+        Deck::load(arena, path, configuration, options.page_break.as_ref())?;
+    if let Some(slides) = &options.slides {
+        document.select(slides)?;
+    }
+    Ok(document)
+}
+
+/// Prints the progress of loading a presentation to the error output.
+///
+/// The line is cleared once the last page has been converted.
+///
+/// # Arguments
+/// *  `progress` - The progress.
+fn print_progress(progress: Progress) {
+    match progress {
+        Progress::Highlighting(block, blocks) => {
+            eprint!("\r\x1b[2KLoading… (code block {}/{})", block, blocks)
+        }
+        Progress::Converting(page, pages) if page < pages => {
+            eprint!("\r\x1b[2KLoading… (page {}/{})", page, pages)
+        }
+        Progress::Converting(..) => eprint!("\r\x1b[2K"),
+    }
+}
+
+/// Whether a presentation document is large enough for the progress of
+/// loading it to be printed.
+///
+/// # Arguments
+/// *  `path` - The path to the presentation document.
+fn is_large(path: &path::Path) -> bool {
+    fs::metadata(path)
+        .map(|metadata| metadata.len() > PROGRESS_THRESHOLD)
+        .unwrap_or(false)
+}
+
+/// The configuration of a presentation as TOML, after merging its front
+/// matter.
+///
+/// # Arguments
+/// *  `path` - The path to the presentation document.
+/// *  `configuration` - The application configuration.
+/// *  `page_break` - The page break condition overriding the configuration,
+///    if any.
+fn effective_configuration(
+    path: &path::Path,
+    configuration: &configuration::Configuration,
+    page_break: Option<&presentation::PageBreakCondition>,
+) -> Result<String, String> {
+    let arena = comrak::Arena::new();
+    let document = Deck::load(&arena, path, configuration, page_break)?;
+    document
+        .configuration()
+        .to_toml()
+        .map_err(|e| format!("Failed to serialise the configuration: {}", e))
+}
+
+/// Parses command line arguments.
+///
+/// The arguments are expected not to include the executable name.
+///
+/// # Arguments
+/// *  `args` - The command line arguments.
+fn parse_args<I>(args: I) -> Result<(Option<String>, Options), String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    let mut configuration_file = None;
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--export" => {
+                options.export = Some(
+                    args.next()
+                        .ok_or_else(|| "Missing export format".to_string())?
+                        .parse()?,
+                );
+            }
+            "--page-break" => {
+                options.page_break = Some(page_break(
+                    &args
+                        .next()
+                        .ok_or_else(|| "Missing page break".to_string())?,
+                )?);
+            }
+            "--slides" => {
+                options.slides = Some(
+                    args.next()
+                        .ok_or_else(|| "Missing page range".to_string())?
+                        .parse()?,
+                );
+            }
+            "--watch" => options.watch = true,
+            "--dry-run" => options.dry_run = true,
+            "--print-config" => options.print_config = true,
+            _ => configuration_file = Some(arg),
+        }
+    }
 
-    let widgets = pages.iter().map(widget::PageWidget::from).collect();
+    Ok((configuration_file, options))
+}
 
-    ui::run(&configuration, widgets)
+/// Parses and validates a page break condition passed on the command line.
+///
+/// # Arguments
+/// *  `s` - The page break condition.
+fn page_break(s: &str) -> Result<presentation::PageBreakCondition, String> {
+    s.parse::<presentation::PageBreakCondition>()
+        .and_then(|condition| condition.validate().map(|_| condition))
+        .map_err(|e| format!("Failed to parse page break {}: {}", s, e))
 }
 
-/// Initialises the application and returns the root directory and
-/// configuration.
+/// Initialises the application and returns the root directory,
+/// configuration and command line options.
 ///
 /// # Panics
 /// This function will panic if the current executable name cannot b dtermined.
-fn initialize() -> Result<(path::PathBuf, configuration::Configuration), String>
-{
+fn initialize(
+) -> Result<(path::PathBuf, configuration::Configuration, Options), String> {
     let name = env::current_exe()
         .map(|exe| exe.to_string_lossy().into_owned())
         .unwrap();
-    let configuration_file = env::args()
-        .skip(1)
-        .next()
-        .ok_or_else(|| format!("Usage: {} CONFIGURATION_FILE", name))?;
+    let (configuration_file, mut options) = parse_args(env::args().skip(1))?;
+    let configuration_file = configuration_file.ok_or_else(|| {
+        format!(
+            "Usage: {} [--export FORMAT] [--page-break CONDITION] \
+            [--slides FIRST-LAST] [--watch] [--dry-run] [--print-config] \
+            CONFIGURATION_FILE",
+            name,
+        )
+    })?;
+    if options.page_break.is_none() {
+        options.page_break = env::var("RUPERT_PAGE_BREAK")
+            .ok()
+            .map(|s| page_break(&s))
+            .transpose()?;
+    }
+    if !options.dry_run {
+        options.dry_run = env::var("RUPERT_DRY_RUN").is_ok_and(|v| v == "1");
+    }
     configuration::load(&configuration_file)
+        .map(|(root, configuration)| (root, configuration, options))
         .map_err(|e| format!("Failed to load {}: {}", configuration_file, e))
 }
 
 fn main() {
-    match initialize()
-        .and_then(|(root, configuration)| run(root, configuration))
-    {
+    match initialize().and_then(|(root, configuration, options)| {
+        run(root, configuration, options)
+    }) {
         Ok(_) => process::exit(0),
         Err(s) => {
             eprintln!("{}", s);
@@ -73,3 +353,133 @@ fn main() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_args_export() {
+        let args = ["--export", "text", "presentation.toml"];
+
+        assert_eq!(
+            Ok((
+                Some("presentation.toml".to_string()),
+                Options {
+                    export: Some(export::Format::Text),
+                    ..Default::default()
+                },
+            )),
+            parse_args(args.iter().map(|s| s.to_string())),
+        );
+        assert!(parse_args(["--export".to_string()]).is_err());
+    }
+
+    #[test]
+    fn parse_args_watch() {
+        let args = ["presentation.toml", "--watch"];
+
+        assert_eq!(
+            Ok((
+                Some("presentation.toml".to_string()),
+                Options {
+                    watch: true,
+                    ..Default::default()
+                },
+            )),
+            parse_args(args.iter().map(|s| s.to_string())),
+        );
+    }
+
+    #[test]
+    fn parse_args_page_break() {
+        let args = ["--page-break", "heading:2", "presentation.toml"];
+
+        assert_eq!(
+            Ok((
+                Some("presentation.toml".to_string()),
+                Options {
+                    page_break: Some(
+                        presentation::PageBreakCondition::Heading { level: 2 }
+                    ),
+                    ..Default::default()
+                },
+            )),
+            parse_args(args.iter().map(|s| s.to_string())),
+        );
+        assert_eq!(
+            Err(
+                "Failed to parse page break heading:9: invalid heading level \
+                9, expected 1 to 6"
+                    .to_string()
+            ),
+            parse_args(["--page-break".to_string(), "heading:9".to_string()]),
+        );
+        assert!(parse_args([
+            "--page-break".to_string(),
+            "comment: ".to_string()
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parse_args_slides() {
+        let args = ["--slides", "5-9", "presentation.toml"];
+
+        assert_eq!(
+            Ok((
+                Some("presentation.toml".to_string()),
+                Options {
+                    slides: Some(PageRange { first: 5, last: 9 }),
+                    ..Default::default()
+                },
+            )),
+            parse_args(args.iter().map(|s| s.to_string())),
+        );
+        assert!(parse_args(["--slides".to_string()]).is_err());
+        assert!(
+            parse_args(["--slides".to_string(), "9-5".to_string()]).is_err()
+        );
+    }
+
+    #[test]
+    fn print_config() {
+        let configuration = configuration::Configuration {
+            title: "Default".into(),
+            ..Default::default()
+        };
+        let printed = effective_configuration(
+            path::Path::new("test-resources/front-matter.md"),
+            &configuration,
+            None,
+        )
+        .unwrap();
+
+        let printed =
+            toml::from_str::<configuration::Configuration>(&printed).unwrap();
+        assert_eq!("Front matter", printed.title);
+        assert_eq!(configuration.code_theme, printed.code_theme);
+        assert!(
+            parse_args(["--print-config".to_string()])
+                .unwrap()
+                .1
+                .print_config
+        );
+    }
+
+    #[test]
+    fn parse_args_dry_run() {
+        let args = ["--dry-run", "presentation.toml"];
+
+        assert_eq!(
+            Ok((
+                Some("presentation.toml".to_string()),
+                Options {
+                    dry_run: true,
+                    ..Default::default()
+                },
+            )),
+            parse_args(args.iter().map(|s| s.to_string())),
+        );
+    }
+}
@@ -1,21 +1,38 @@
 use std::cell::RefCell;
 use std::fs;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use comrak::arena_tree::Node;
-use comrak::nodes::{Ast, NodeValue};
+use comrak::nodes::{Ast, NodeCodeBlock, NodeValue};
 use comrak::Arena;
 use serde::{Deserialize, Serialize};
 
+/// The delimiters accepted around front matter.
+pub const FRONT_MATTER_DELIMITERS: [&str; 2] = ["%%%", "---"];
+
+/// The info string of code blocks containing page configuration.
+pub const PAGE_CONFIGURATION: &str = "rupert";
+
 /// A presentation.
 pub struct Presentation<'a> {
     /// The root of the AST.
     root: &'a Node<'a, RefCell<Ast>>,
+
+    /// The delimiter of the front matter, if the document has any.
+    front_matter_delimiter: Option<&'static str>,
 }
 
 /// Loads a markdown document.
 ///
+/// A document may begin with front matter, enclosed by lines containing only
+/// one of the delimiters in [`FRONT_MATTER_DELIMITERS`].
+///
+/// Other documents are included by paragraphs containing only an include
+/// directive, such as `{{ include: part.md }}`; the path is relative to the
+/// including document.
+///
 /// # Arguments
 /// *  `arena` - The arena managing memory for the AST.
 /// *  `path` - The path to the document.
@@ -26,19 +43,197 @@ pub fn load<'a, P>(
 where
     P: AsRef<Path>,
 {
-    fs::read_to_string(path).map(|data| Presentation {
-        root: comrak::parse_document(
-            arena,
-            &data,
-            &comrak::ComrakOptions {
-                extension: comrak::ComrakExtensionOptions {
-                    strikethrough: true,
-                    ..Default::default()
-                },
+    let path = path.as_ref();
+    let mut data = fs::read_to_string(path)?;
+    let front_matter_delimiter = front_matter_delimiter(&data);
+
+    // The closing front matter delimiter is only recognised when followed by
+    // a line break, which the last line of a document may lack
+    if front_matter_delimiter.is_some() && !data.ends_with('\n') {
+        data.push('\n');
+    }
+    let root = parse(arena, &data, front_matter_delimiter);
+    include(arena, root, path, &mut vec![path.canonicalize()?])?;
+    Ok(Presentation {
+        root,
+        front_matter_delimiter,
+    })
+}
+
+/// Parses a markdown document.
+///
+/// # Arguments
+/// *  `arena` - The arena managing memory for the AST.
+/// *  `data` - The document.
+/// *  `front_matter_delimiter` - The front matter delimiter used by the
+///    document, if it has front matter.
+fn parse<'a>(
+    arena: &'a Arena<Node<'a, RefCell<Ast>>>,
+    data: &str,
+    front_matter_delimiter: Option<&str>,
+) -> &'a Node<'a, RefCell<Ast>> {
+    comrak::parse_document(
+        arena,
+        data,
+        &comrak::ComrakOptions {
+            extension: comrak::ComrakExtensionOptions {
+                strikethrough: true,
+                table: true,
+                footnotes: true,
+                front_matter_delimiter: front_matter_delimiter
+                    .map(String::from),
                 ..Default::default()
             },
-        ),
-    })
+            ..Default::default()
+        },
+    )
+}
+
+/// Replaces the include directives of a document by the included documents.
+///
+/// Included documents may include other documents, but not any document
+/// including them. Their footnotes are renumbered to follow those already in
+/// the document, and their footnote definitions are moved to its end.
+///
+/// # Arguments
+/// *  `arena` - The arena managing memory for the AST.
+/// *  `root` - The root of the document.
+/// *  `path` - The path to the document.
+/// *  `including` - The canonical paths of the document and all documents
+///    including it.
+fn include<'a>(
+    arena: &'a Arena<Node<'a, RefCell<Ast>>>,
+    root: &'a Node<'a, RefCell<Ast>>,
+    path: &Path,
+    including: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    for node in root.children().collect::<Vec<_>>() {
+        let target = match include_directive(node) {
+            Some(target) => directory.join(target),
+            None => continue,
+        };
+        let error = |e: io::Error| {
+            io::Error::new(e.kind(), format!("{}: {}", target.display(), e))
+        };
+        let canonical = target.canonicalize().map_err(error)?;
+        if including.contains(&canonical) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: the document includes itself", target.display()),
+            ));
+        }
+        let data = fs::read_to_string(&target).map_err(error)?;
+        let included = parse(arena, &data, None);
+        including.push(canonical);
+        include(arena, included, &target, including)?;
+        including.pop();
+
+        // Footnote definitions are kept at the end of the document
+        renumber_footnotes(included, footnote_count(root));
+        for child in included.children().collect::<Vec<_>>() {
+            if is_footnote(child) {
+                root.append(child);
+            } else {
+                node.insert_before(child);
+            }
+        }
+        node.detach();
+    }
+    Ok(())
+}
+
+/// The path of the document included by a node, if it is an include
+/// directive.
+///
+/// # Arguments
+/// *  `node` - The node.
+fn include_directive<'a>(node: &'a Node<'a, RefCell<Ast>>) -> Option<String> {
+    if !matches!(node.data.borrow().value, NodeValue::Paragraph) {
+        return None;
+    }
+    text(node)
+        .trim()
+        .strip_prefix("{{")
+        .and_then(|s| s.strip_suffix("}}"))
+        .and_then(|s| s.trim().strip_prefix("include:"))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// The highest footnote number of a document.
+///
+/// # Arguments
+/// *  `root` - The root of the document.
+fn footnote_count<'a>(root: &'a Node<'a, RefCell<Ast>>) -> usize {
+    root.descendants()
+        .filter_map(|node| match &node.data.borrow().value {
+            NodeValue::FootnoteDefinition(name)
+            | NodeValue::FootnoteReference(name) => {
+                footnote_index(name).map(|index| index + 1)
+            }
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Adds an offset to the footnote numbers of a document.
+///
+/// # Arguments
+/// *  `root` - The root of the document.
+/// *  `offset` - The offset to add.
+fn renumber_footnotes<'a>(root: &'a Node<'a, RefCell<Ast>>, offset: usize) {
+    for node in root.descendants() {
+        match &mut node.data.borrow_mut().value {
+            NodeValue::FootnoteDefinition(name)
+            | NodeValue::FootnoteReference(name) => {
+                if let Some(index) = footnote_index(name) {
+                    *name = (index + 1 + offset).to_string().into_bytes();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Finds the front matter delimiter used by a document.
+///
+/// This is the delimiter found alone on the first line of the document.
+///
+/// # Arguments
+/// *  `data` - The document.
+fn front_matter_delimiter(data: &str) -> Option<&'static str> {
+    let first = data.trim_start_matches('\u{feff}').lines().next()?;
+    FRONT_MATTER_DELIMITERS
+        .iter()
+        .find(|delimiter| first.trim_end() == **delimiter)
+        .copied()
+}
+
+/// Loads a markdown document and lists the titles of its pages.
+///
+/// The title of a page is the text of its first top level heading. The number
+/// of pages is the length of the returned list.
+///
+/// # Arguments
+/// *  `path` - The path to the document.
+/// *  `break_condition` - The break condition for breaking the full document
+///    into pages.
+pub fn titles<P>(
+    path: P,
+    break_condition: PageBreakCondition,
+) -> io::Result<Vec<Option<String>>>
+where
+    P: AsRef<Path>,
+{
+    let arena = Arena::new();
+    let presentation = load(&arena, path)?;
+    let titles = presentation
+        .pages(break_condition)
+        .map(|page| page.title())
+        .collect();
+    Ok(titles)
 }
 
 impl<'a> Presentation<'a> {
@@ -53,6 +248,43 @@ impl<'a> Presentation<'a> {
     ) -> impl Iterator<Item = Page<'a>> {
         PageIterator::new(self, break_condition)
     }
+
+    /// The front matter of this presentation.
+    ///
+    /// The delimiters and surrounding whitespace are not included.
+    pub fn front_matter(&self) -> Option<String> {
+        let delimiter = self.front_matter_delimiter?;
+        self.root
+            .children()
+            .find_map(|node| match &node.data.borrow().value {
+                NodeValue::FrontMatter(literal) => {
+                    String::from_utf8_lossy(literal)
+                        .trim_start_matches('\u{feff}')
+                        .trim()
+                        .strip_prefix(delimiter)
+                        .and_then(|s| s.strip_suffix(delimiter))
+                        .map(|s| s.trim().to_string())
+                }
+                _ => None,
+            })
+    }
+
+    /// The footnote definitions of this presentation.
+    ///
+    /// Definitions are yielded together with their zero based index, in the
+    /// order they are first referenced.
+    pub fn footnotes(
+        &self,
+    ) -> impl Iterator<Item = (usize, &'a Node<'a, RefCell<Ast>>)> {
+        self.root.children().filter_map(|node| {
+            match &node.data.borrow().value {
+                NodeValue::FootnoteDefinition(name) => {
+                    footnote_index(name).map(|index| (index, node))
+                }
+                _ => None,
+            }
+        })
+    }
 }
 
 /// A single page of the presentation.
@@ -69,13 +301,194 @@ impl<'a> From<Vec<&'a Node<'a, RefCell<Ast>>>> for Page<'a> {
 
 impl<'a> Page<'a> {
     /// An iterator over the AST nodes of this page.
-    pub fn nodes(&'a self) -> impl Iterator<Item = &Node<RefCell<Ast>>> {
+    pub fn nodes(&'a self) -> impl Iterator<Item = &'a Node<'a, RefCell<Ast>>> {
         self.nodes.iter().cloned()
     }
+
+    /// The title of this page.
+    ///
+    /// This is the plain text of the first top level heading of the page.
+    pub fn title(&self) -> Option<String> {
+        self.nodes
+            .iter()
+            .find(|node| {
+                matches!(node.data.borrow().value, NodeValue::Heading(_))
+            })
+            .map(|node| text(node))
+    }
+
+    /// The configuration of this page.
+    ///
+    /// This is the content of the first top level code block with the info
+    /// string [`PAGE_CONFIGURATION`].
+    pub fn configuration(&self) -> Option<String> {
+        self.nodes
+            .iter()
+            .find_map(|node| match &node.data.borrow().value {
+                NodeValue::CodeBlock(code) => page_configuration(code),
+                _ => None,
+            })
+    }
+
+    /// Whether this page has no content to show.
+    ///
+    /// Comments, which are speaker notes, page configuration and paragraphs
+    /// containing only whitespace are not considered content.
+    pub fn is_empty(&self) -> bool {
+        self.nodes
+            .iter()
+            .all(|node| match &node.data.borrow().value {
+                NodeValue::FrontMatter(_) => true,
+                NodeValue::HtmlBlock(html) => comment(&html.literal).is_some(),
+                NodeValue::CodeBlock(code) => {
+                    page_configuration(code).is_some()
+                }
+                NodeValue::Paragraph => text(node).trim().is_empty(),
+                _ => false,
+            })
+    }
+
+    /// The speaker notes of this page.
+    ///
+    /// Notes are HTML comments placed among the top level blocks of the page;
+    /// every comment yields one note, in document order. Column markers are
+    /// not notes.
+    pub fn notes(&self) -> Vec<String> {
+        self.nodes
+            .iter()
+            .filter_map(|node| match &node.data.borrow().value {
+                NodeValue::HtmlBlock(html) => comment(&html.literal)
+                    .filter(|text| ColumnMarker::from_comment(text).is_none()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The indices of the footnotes referenced from this page.
+    ///
+    /// Every footnote is listed once, in the order it is first referenced.
+    pub fn footnote_references(&self) -> Vec<usize> {
+        let mut result = Vec::new();
+        for node in self.nodes.iter().flat_map(|node| node.descendants()) {
+            if let NodeValue::FootnoteReference(name) =
+                &node.data.borrow().value
+            {
+                if let Some(index) = footnote_index(name) {
+                    if !result.contains(&index) {
+                        result.push(index);
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Extracts the plain text of a node and its descendants.
+///
+/// # Arguments
+/// *  `node` - The node whose text to extract.
+pub fn text<'a>(node: &'a Node<'a, RefCell<Ast>>) -> String {
+    node.descendants()
+        .filter_map(|node| match &node.data.borrow().value {
+            NodeValue::Text(text) => {
+                Some(String::from_utf8_lossy(text).into_owned())
+            }
+            NodeValue::Code(code) => {
+                Some(String::from_utf8_lossy(&code.literal).into_owned())
+            }
+            NodeValue::LineBreak | NodeValue::SoftBreak => Some(" ".into()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Converts the name of a footnote to its zero based index.
+///
+/// When parsing a document, references and definitions are renamed to their
+/// one based footnote number.
+///
+/// # Arguments
+/// *  `name` - The name of the footnote node.
+pub fn footnote_index(name: &[u8]) -> Option<usize> {
+    std::str::from_utf8(name)
+        .ok()
+        .and_then(|name| name.parse::<usize>().ok())
+        .and_then(|number| number.checked_sub(1))
+}
+
+/// Extracts the text of an HTML comment.
+///
+/// If `literal` is not a comment, `None` is returned.
+///
+/// # Arguments
+/// *  `literal` - The literal content of an HTML node.
+pub fn comment(literal: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(literal)
+        .trim()
+        .strip_prefix("<!--")
+        .and_then(|s| s.strip_suffix("-->"))
+        .map(|s| s.trim().to_string())
+}
+
+/// A marker dividing the content of a page into columns.
+///
+/// Markers are comments: `<!-- columns -->` starts a column layout,
+/// `<!-- column -->` starts the next column and `<!-- /columns -->` ends the
+/// layout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnMarker {
+    /// The start of a column layout, and of its first column.
+    Start,
+
+    /// The start of the next column.
+    Next,
+
+    /// The end of a column layout.
+    End,
+}
+
+impl ColumnMarker {
+    /// Parses the text of a comment as a column marker.
+    ///
+    /// # Arguments
+    /// *  `text` - The text of the comment.
+    pub fn from_comment(text: &str) -> Option<Self> {
+        match text {
+            "columns" => Some(Self::Start),
+            "column" => Some(Self::Next),
+            "/columns" => Some(Self::End),
+            _ => None,
+        }
+    }
+
+    /// The column marker of a node, if it is one.
+    ///
+    /// # Arguments
+    /// *  `node` - The node.
+    pub fn from_node<'a>(node: &'a Node<'a, RefCell<Ast>>) -> Option<Self> {
+        match &node.data.borrow().value {
+            NodeValue::HtmlBlock(html) => comment(&html.literal)
+                .and_then(|text| Self::from_comment(&text)),
+            _ => None,
+        }
+    }
+}
+
+/// Extracts the page configuration of a code block.
+///
+/// If `code` is not a page configuration block, `None` is returned.
+///
+/// # Arguments
+/// *  `code` - The code block.
+pub fn page_configuration(code: &NodeCodeBlock) -> Option<String> {
+    (code.fenced
+        && String::from_utf8_lossy(&code.info).trim() == PAGE_CONFIGURATION)
+        .then(|| String::from_utf8_lossy(&code.literal).into_owned())
 }
 
 /// Conditions for breaking a document into pages.
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum PageBreakCondition {
     /// Break on headings.
@@ -83,9 +496,36 @@ pub enum PageBreakCondition {
         /// The heading level.
         level: u32,
     },
+
+    /// Break on thematic breaks.
+    ThematicBreak,
+
+    /// Break on comments containing only a marker, such as `<!-- slide -->`.
+    Comment {
+        /// The marker.
+        marker: String,
+    },
 }
 
 impl PageBreakCondition {
+    /// The default marker of comment page breaks.
+    pub const DEFAULT_MARKER: &'static str = "slide";
+
+    /// Checks that this condition can match a page break.
+    pub fn validate(&self) -> Result<(), String> {
+        use PageBreakCondition::*;
+        match self {
+            Heading { level } if !(1..=6).contains(level) => Err(format!(
+                "invalid heading level {}, expected 1 to 6",
+                level,
+            )),
+            Comment { marker } if marker.trim().is_empty() => {
+                Err("the marker is empty".into())
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Determines whether a node value signifies a page break.
     ///
     /// # Arguments
@@ -97,6 +537,57 @@ impl PageBreakCondition {
                 NodeValue::Heading(h) => h.level == *level,
                 _ => false,
             },
+            ThematicBreak => matches!(value, NodeValue::ThematicBreak),
+            Comment { marker } => match value {
+                NodeValue::HtmlBlock(html) => {
+                    comment(&html.literal).as_ref() == Some(marker)
+                }
+                _ => false,
+            },
+        }
+    }
+
+    /// Whether the node causing a page break begins the next page.
+    ///
+    /// Otherwise the node is dropped.
+    pub fn keeps_break(&self) -> bool {
+        use PageBreakCondition::*;
+        match self {
+            Heading { .. } => true,
+            ThematicBreak | Comment { .. } => false,
+        }
+    }
+}
+
+impl FromStr for PageBreakCondition {
+    type Err = String;
+
+    /// Parses a page break condition.
+    ///
+    /// The format is the type, optionally followed by a colon and the
+    /// argument, such as `heading:2`, `thematic_break` or `comment:slide`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, argument) = match s.split_once(':') {
+            Some((kind, argument)) => (kind, Some(argument)),
+            None => (s, None),
+        };
+        match kind {
+            "heading" => Ok(PageBreakCondition::Heading {
+                level: argument
+                    .map(|level| level.parse())
+                    .transpose()
+                    .map_err(|_| format!("Invalid heading level: {}", s))?
+                    .unwrap_or(1),
+            }),
+            "thematic_break" if argument.is_none() => {
+                Ok(PageBreakCondition::ThematicBreak)
+            }
+            "comment" => Ok(PageBreakCondition::Comment {
+                marker: argument
+                    .unwrap_or(PageBreakCondition::DEFAULT_MARKER)
+                    .to_string(),
+            }),
+            _ => Err(format!("Unknown page break condition: {}", s)),
         }
     }
 }
@@ -132,13 +623,32 @@ impl<'a> Iterator for PageIterator<'a> {
     type Item = Page<'a>;
 
     fn next(&mut self) -> Option<Page<'a>> {
-        let mut current = self.next?;
+        let mut current = self.next.filter(|node| !is_footnote(node))?;
+        if is_front_matter(current) {
+            current =
+                current.next_sibling().filter(|node| !is_footnote(node))?;
+        }
+        let keeps_break = self.break_condition.keeps_break();
         let mut nodes = Vec::new();
         self.next = loop {
-            nodes.push(current);
+            // Dropped breaks are skipped, so that consecutive breaks do not
+            // cause empty pages
+            if keeps_break
+                || !self.break_condition.is_break(&current.data.borrow().value)
+            {
+                nodes.push(current);
+            }
             if let Some(next) = current.next_sibling() {
-                if self.break_condition.is_break(&next.data.borrow().value) {
-                    break Some(next);
+                if is_footnote(next) {
+                    break None;
+                } else if !nodes.is_empty()
+                    && self.break_condition.is_break(&next.data.borrow().value)
+                {
+                    break if keeps_break {
+                        Some(next)
+                    } else {
+                        next.next_sibling()
+                    };
                 } else {
                     current = next;
                     continue;
@@ -148,35 +658,102 @@ impl<'a> Iterator for PageIterator<'a> {
             }
         };
 
-        Some(nodes.into())
+        if nodes.is_empty() {
+            None
+        } else {
+            Some(nodes.into())
+        }
     }
 }
 
+/// Determines whether a node is a footnote definition.
+///
+/// Footnote definitions are moved to the end of the document when parsing, so
+/// they are not part of any page.
+///
+/// # Arguments
+/// *  `node` - The node to check.
+fn is_footnote(node: &Node<'_, RefCell<Ast>>) -> bool {
+    matches!(node.data.borrow().value, NodeValue::FootnoteDefinition(_))
+}
+
+/// Determines whether a node is front matter.
+///
+/// # Arguments
+/// *  `node` - The node to check.
+fn is_front_matter(node: &Node<'_, RefCell<Ast>>) -> bool {
+    matches!(node.data.borrow().value, NodeValue::FrontMatter(_))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn include() {
+        let arena = Arena::new();
+        let presentation =
+            load(&arena, "test-resources/include/master.md").unwrap();
+
+        assert_eq!(
+            vec![
+                Some("Introduction".to_string()),
+                Some("Part one".to_string()),
+                Some("Part two".to_string()),
+                Some("Part three".to_string()),
+            ],
+            presentation
+                .pages(PageBreakCondition::default())
+                .map(|page| page.title())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            vec![
+                (0, "The source.".to_string()),
+                (1, "Another source.".to_string()),
+            ],
+            presentation
+                .footnotes()
+                .map(|(index, node)| (index, text(node)))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            vec![1],
+            presentation
+                .pages(PageBreakCondition::default())
+                .nth(1)
+                .unwrap()
+                .footnote_references(),
+        );
+
+        let error = load(&arena, "test-resources/include/cycle.md")
+            .err()
+            .unwrap();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+        assert!(load(&arena, "test-resources/include/missing.md").is_err());
+    }
+
     #[test]
     fn load_sucessful() {
-        let mut arena = comrak::Arena::new();
-        let presentation = load(&mut arena, "test-resources/presentation.md");
+        let arena = comrak::Arena::new();
+        let presentation = load(&arena, "test-resources/presentation.md");
 
         assert!(presentation.is_ok());
     }
 
     #[test]
     fn load_fails_for_nonexisting() {
-        let mut arena = comrak::Arena::new();
-        let presentation = load(&mut arena, "test-resources/does-not-exist.md");
+        let arena = comrak::Arena::new();
+        let presentation = load(&arena, "test-resources/does-not-exist.md");
 
         assert!(presentation.is_err());
     }
 
     #[test]
     fn pages() {
-        let mut arena = comrak::Arena::new();
+        let arena = comrak::Arena::new();
         let presentation =
-            load(&mut arena, "test-resources/presentation.md").unwrap();
+            load(&arena, "test-resources/presentation.md").unwrap();
 
         let pages = presentation
             .pages(PageBreakCondition::Heading { level: 1 })
@@ -186,4 +763,276 @@ mod tests {
         assert_eq!(1, pages[0].nodes[0].data.borrow().start_line);
         assert_eq!(6, pages[1].nodes[0].data.borrow().start_line);
     }
+
+    #[test]
+    fn page_break_condition_from_str() {
+        assert_eq!(
+            Ok(PageBreakCondition::Heading { level: 1 }),
+            "heading".parse(),
+        );
+        assert_eq!(
+            Ok(PageBreakCondition::Heading { level: 3 }),
+            "heading:3".parse(),
+        );
+        assert_eq!(
+            Ok(PageBreakCondition::ThematicBreak),
+            "thematic_break".parse(),
+        );
+        assert_eq!(
+            Ok(PageBreakCondition::Comment {
+                marker: "slide".into()
+            }),
+            "comment".parse(),
+        );
+        assert_eq!(
+            Ok(PageBreakCondition::Comment {
+                marker: "next".into()
+            }),
+            "comment:next".parse(),
+        );
+        assert!("heading:x".parse::<PageBreakCondition>().is_err());
+        assert!("thematic_break:2".parse::<PageBreakCondition>().is_err());
+        assert!("paragraph".parse::<PageBreakCondition>().is_err());
+    }
+
+    #[test]
+    fn notes() {
+        let arena = comrak::Arena::new();
+        let presentation = load(&arena, "test-resources/notes.md").unwrap();
+
+        let pages = presentation
+            .pages(PageBreakCondition::Heading { level: 1 })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                "Remember to smile".to_string(),
+                "A note\non two lines".into()
+            ],
+            pages[0].notes(),
+        );
+        assert!(pages[1].notes().is_empty());
+    }
+
+    #[test]
+    fn page_configuration() {
+        let arena = comrak::Arena::new();
+        let presentation =
+            load(&arena, "test-resources/page-configuration.md").unwrap();
+
+        let pages = presentation
+            .pages(PageBreakCondition::Heading { level: 1 })
+            .collect::<Vec<_>>();
+
+        assert_eq!(None, pages[0].configuration());
+        assert_eq!(
+            Some("text_background = \"blue\"\n".into()),
+            pages[1].configuration(),
+        );
+        assert!(Page::from(pages[1].nodes[1..2].to_vec()).is_empty());
+    }
+
+    #[test]
+    fn footnotes() {
+        let arena = comrak::Arena::new();
+        let presentation = load(&arena, "test-resources/footnotes.md").unwrap();
+
+        let pages = presentation
+            .pages(PageBreakCondition::Heading { level: 1 })
+            .collect::<Vec<_>>();
+
+        assert_eq!(2, pages.len());
+        assert_eq!(2, pages[1].nodes.len());
+        assert_eq!(vec![0, 1], pages[0].footnote_references());
+        assert_eq!(vec![0], pages[1].footnote_references());
+        assert_eq!(
+            vec![0, 1],
+            presentation
+                .footnotes()
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn titles() {
+        assert_eq!(
+            vec![Some("Page 1".to_string()), Some("Page 2".into())],
+            super::titles(
+                "test-resources/presentation.md",
+                PageBreakCondition::default(),
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            vec![Some("Level 1".to_string())],
+            super::titles(
+                "test-resources/headings.md",
+                PageBreakCondition::default(),
+            )
+            .unwrap(),
+        );
+        assert!(super::titles(
+            "test-resources/does-not-exist.md",
+            PageBreakCondition::default(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn front_matter() {
+        for path in [
+            "test-resources/front-matter.md",
+            "test-resources/front-matter-dashes.md",
+        ] {
+            let arena = comrak::Arena::new();
+            let presentation = load(&arena, path).unwrap();
+
+            assert_eq!(
+                Some("title = \"Front matter\"".to_string()),
+                presentation.front_matter(),
+            );
+            let pages = presentation
+                .pages(PageBreakCondition::default())
+                .collect::<Vec<_>>();
+            assert_eq!(2, pages.len());
+            assert_eq!(Some("Page 1".into()), pages[0].title());
+        }
+
+        let arena = comrak::Arena::new();
+        let presentation =
+            load(&arena, "test-resources/presentation.md").unwrap();
+        assert_eq!(None, presentation.front_matter());
+    }
+
+    #[test]
+    fn front_matter_line_endings() {
+        for path in [
+            "test-resources/front-matter-crlf.md",
+            "test-resources/front-matter-no-newline.md",
+        ] {
+            let arena = comrak::Arena::new();
+            let presentation = load(&arena, path).unwrap();
+
+            assert_eq!(
+                Some("title = \"Front matter\"".to_string()),
+                presentation.front_matter(),
+            );
+            let pages = presentation
+                .pages(PageBreakCondition::default())
+                .collect::<Vec<_>>();
+            assert_eq!(1, pages.len());
+            assert_eq!(Some("Page 1".into()), pages[0].title());
+        }
+    }
+
+    #[test]
+    fn front_matter_end_of_file() {
+        let arena = comrak::Arena::new();
+        let presentation =
+            load(&arena, "test-resources/front-matter-eof.md").unwrap();
+
+        assert_eq!(
+            Some("title = \"Front matter\"".to_string()),
+            presentation.front_matter(),
+        );
+        assert!(presentation
+            .pages(PageBreakCondition::default())
+            .all(|page| page.is_empty()));
+    }
+
+    #[test]
+    fn is_empty() {
+        let arena = comrak::Arena::new();
+        let presentation =
+            load(&arena, "test-resources/empty-page.md").unwrap();
+
+        let pages = presentation
+            .pages(PageBreakCondition::default())
+            .collect::<Vec<_>>();
+
+        assert_eq!(2, pages.len());
+        assert!(pages[0].is_empty());
+        assert_eq!(None, pages[0].title());
+        assert!(!pages[1].is_empty());
+        assert_eq!(Some("Page 1".into()), pages[1].title());
+    }
+
+    #[test]
+    fn thematic_breaks() {
+        let arena = comrak::Arena::new();
+        let presentation =
+            load(&arena, "test-resources/thematic-breaks.md").unwrap();
+
+        let pages = presentation
+            .pages(PageBreakCondition::ThematicBreak)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![Some("Page 1".to_string()), Some("Page 2".into())],
+            pages.iter().map(Page::title).collect::<Vec<_>>(),
+        );
+        assert!(pages.iter().all(|page| page.nodes().all(|node| !matches!(
+            node.data.borrow().value,
+            NodeValue::ThematicBreak
+        ))));
+    }
+
+    #[test]
+    fn heading_breaks() {
+        let arena = comrak::Arena::new();
+        let presentation = load(&arena, "test-resources/headings.md").unwrap();
+
+        let pages = presentation
+            .pages(PageBreakCondition::Heading { level: 2 })
+            .collect::<Vec<_>>();
+        let headings = pages
+            .iter()
+            .map(|page| {
+                page.nodes()
+                    .filter_map(|node| match &node.data.borrow().value {
+                        NodeValue::Heading(heading) => {
+                            Some((heading.level, text(node)))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                vec![(1, "Level 1".to_string()), (3, "Level 3".into())],
+                vec![(2, "Level 2".into())],
+            ],
+            headings,
+        );
+        assert_eq!(Some("Level 2".into()), pages[1].title());
+        assert!(matches!(
+            pages[1].nodes[0].data.borrow().value,
+            NodeValue::Heading(_)
+        ));
+    }
+
+    #[test]
+    fn comment_breaks() {
+        let arena = comrak::Arena::new();
+        let presentation = load(&arena, "test-resources/slides.md").unwrap();
+
+        let pages = presentation
+            .pages(PageBreakCondition::Comment {
+                marker: "slide".into(),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(3, pages.len());
+        assert_eq!(
+            vec![
+                Vec::<String>::new(),
+                vec!["A speaker note".into()],
+                Vec::new()
+            ],
+            pages.iter().map(Page::notes).collect::<Vec<_>>(),
+        );
+    }
 }
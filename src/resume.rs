@@ -0,0 +1,139 @@
+//! The pages last shown of presentations, restored when presenting them
+//! again.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// The pages last shown of presentations.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Positions {
+    /// The index of the page last shown, before automatic pagination, by
+    /// the canonical path of the presentation document.
+    #[serde(default)]
+    pages: BTreeMap<String, usize>,
+}
+
+impl Positions {
+    /// Loads the positions from a state file.
+    ///
+    /// A missing or invalid state file is treated as empty.
+    ///
+    /// # Arguments
+    /// *  `path` - The path to the state file.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|data| toml::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    /// Writes the positions to a state file, creating its directory if
+    /// necessary.
+    ///
+    /// # Arguments
+    /// *  `path` - The path to the state file.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let data = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, data)
+    }
+
+    /// The page last shown of a presentation, if known.
+    ///
+    /// # Arguments
+    /// *  `presentation` - The path to the presentation document.
+    pub fn get(&self, presentation: &Path) -> Option<usize> {
+        self.pages.get(&key(presentation)).cloned()
+    }
+
+    /// Records the page last shown of a presentation.
+    ///
+    /// # Arguments
+    /// *  `presentation` - The path to the presentation document.
+    /// *  `page` - The index of the page.
+    pub fn set(&mut self, presentation: &Path, page: usize) {
+        self.pages.insert(key(presentation), page);
+    }
+}
+
+/// The path to the state file, given the environment.
+///
+/// The file is placed in `$XDG_CACHE_HOME`, or in `$HOME/.cache`. This
+/// function returns `None` if neither is set.
+///
+/// # Arguments
+/// *  `var` - A function returning the value of an environment variable.
+pub fn state_file<F>(var: F) -> Option<PathBuf>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    var("XDG_CACHE_HOME")
+        .filter(|directory| !directory.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| var("HOME").map(|home| Path::new(&home).join(".cache")))
+        .map(|directory| directory.join("rupert").join("positions.toml"))
+}
+
+/// The key identifying a presentation document.
+///
+/// # Arguments
+/// *  `presentation` - The path to the presentation document.
+fn key(presentation: &Path) -> String {
+    presentation
+        .canonicalize()
+        .unwrap_or_else(|_| presentation.into())
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let path = env::temp_dir()
+            .join(format!("rupert-{}", std::process::id()))
+            .join("positions.toml");
+        let presentation = Path::new("test-resources/headings.md");
+
+        let mut positions = Positions::load(&path);
+        assert_eq!(None, positions.get(presentation));
+        positions.set(presentation, 3);
+        positions.set(Path::new("test-resources/code.md"), 1);
+        positions.save(&path).unwrap();
+
+        let loaded = Positions::load(&path);
+        assert_eq!(positions, loaded);
+        assert_eq!(Some(3), loaded.get(presentation));
+        assert_eq!(
+            Some(3),
+            loaded.get(&env::current_dir().unwrap().join(presentation)),
+        );
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn state_file_location() {
+        assert_eq!(
+            Some(PathBuf::from("/cache/rupert/positions.toml")),
+            state_file(
+                |name| (name == "XDG_CACHE_HOME").then(|| "/cache".into())
+            ),
+        );
+        assert_eq!(
+            Some(PathBuf::from("/home/user/.cache/rupert/positions.toml")),
+            state_file(|name| (name == "HOME").then(|| "/home/user".into())),
+        );
+        assert_eq!(None, state_file(|_| None));
+    }
+}
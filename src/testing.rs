@@ -0,0 +1,53 @@
+//! Helpers shared by the tests of the crate.
+
+use tui::buffer::Buffer;
+
+use crate::presentation::{self, Page, PageBreakCondition, Presentation};
+
+/// Loads a test document.
+///
+/// The arena holding the document is leaked, so that the presentation and
+/// its pages may outlive the test helper.
+///
+/// # Arguments
+/// *  `path` - The path to the document.
+pub fn load(path: &str) -> Presentation<'static> {
+    presentation::load(Box::leak(Box::new(comrak::Arena::new())), path).unwrap()
+}
+
+/// The pages of a presentation, split with the default page break condition.
+///
+/// The pages are leaked as well, since converting a page borrows it for as
+/// long as the arena lives.
+///
+/// # Arguments
+/// *  `presentation` - The presentation.
+pub fn pages(presentation: &Presentation<'static>) -> &'static [Page<'static>] {
+    Box::leak(
+        presentation
+            .pages(PageBreakCondition::default())
+            .collect::<Box<_>>(),
+    )
+}
+
+/// Loads the pages of a test document.
+///
+/// # Arguments
+/// *  `path` - The path to the document.
+pub fn load_pages(path: &str) -> &'static [Page<'static>] {
+    pages(&load(path))
+}
+
+/// The symbols of the rows of a buffer.
+///
+/// # Arguments
+/// *  `buf` - The buffer.
+pub fn rows(buf: &Buffer) -> Vec<String> {
+    (buf.area.top()..buf.area.bottom())
+        .map(|y| {
+            (buf.area.left()..buf.area.right())
+                .map(|x| buf.get(x, y).symbol.as_str())
+                .collect()
+        })
+        .collect()
+}
@@ -1,13 +1,154 @@
-use std::cell::RefCell;
-use std::ops::Deref;
+use std::cell::{Ref, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, Range};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
 
 use comrak::arena_tree::Node;
-use comrak::nodes::{Ast, ListDelimType, ListType, NodeValue};
+use comrak::nodes::{Ast, ListDelimType, ListType, NodeCodeBlock, NodeValue};
+use rayon::prelude::*;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{self, FontStyle, Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
 
+use tui::layout::{Alignment, Constraint};
 use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans, Text};
+use tui::widgets::Wrap;
 
-use crate::presentation::Page;
+use crate::configuration::{
+    Configuration, FootnoteNumbering, HeadingMarker, ThemeMode,
+};
+use crate::presentation::{self, ColumnMarker, Page, Presentation};
+
+pub mod math;
+
+/// The context of a transformation.
+#[derive(Clone)]
+pub struct Context<'a, 'c> {
+    /// The application configuration.
+    pub configuration: &'c Configuration,
+
+    /// The syntax definitions used to highlight code.
+    pub syntax_set: Arc<SyntaxSet>,
+
+    /// The theme used to highlight code.
+    pub theme: Arc<Theme>,
+
+    /// The footnotes referenced from the page, when footnotes are numbered
+    /// per page.
+    pub page_footnotes: Option<Vec<usize>>,
+
+    /// The code blocks highlighted up front, if any.
+    pub highlights: Option<&'c Highlights<'a>>,
+
+    /// The problems found so far.
+    pub diagnostics: RefCell<Vec<Diagnostic>>,
+
+    /// The indices of the footnotes referenced since they were last taken.
+    pub footnote_references: RefCell<Vec<usize>>,
+}
+
+impl<'a, 'c> Context<'a, 'c> {
+    /// Creates a context for a configuration.
+    ///
+    /// The syntax definitions and themes are loaded only once, and shared by
+    /// all contexts. If the configured code theme does not exist, the default
+    /// theme is used, and if the configured syntax definitions cannot be
+    /// loaded, only the default syntax definitions are used; use
+    /// [`syntax_set`] to detect this.
+    ///
+    /// # Arguments
+    /// *  `configuration` - The application configuration.
+    pub fn new(configuration: &'c Configuration) -> Self {
+        static THEMES: OnceLock<HashMap<String, Arc<Theme>>> = OnceLock::new();
+
+        let syntax_set = syntax_set(configuration.syntax_dir.as_deref())
+            .or_else(|_| syntax_set(None))
+            .unwrap();
+        let themes = THEMES.get_or_init(|| {
+            ThemeSet::load_defaults()
+                .themes
+                .into_iter()
+                .map(|(name, theme)| (name, Arc::new(theme)))
+                .collect()
+        });
+        let theme = themes
+            .get(configuration.code_theme())
+            .or_else(|| themes.get(Configuration::DEFAULT_CODE_THEME))
+            .cloned()
+            .unwrap();
+
+        Self {
+            configuration,
+            syntax_set,
+            theme,
+            page_footnotes: None,
+            highlights: None,
+            diagnostics: RefCell::new(Vec::new()),
+            footnote_references: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The index of a footnote as displayed.
+    ///
+    /// When footnotes are numbered per page, this is the position of the
+    /// footnote among those referenced from the page.
+    ///
+    /// # Arguments
+    /// *  `index` - The index of the footnote in the presentation.
+    pub fn footnote_number(&self, index: usize) -> usize {
+        self.page_footnotes
+            .as_ref()
+            .and_then(|footnotes| {
+                footnotes.iter().position(|footnote| *footnote == index)
+            })
+            .unwrap_or(index)
+    }
+
+    /// Records an element that is not supported, and creates a placeholder
+    /// for it.
+    ///
+    /// # Arguments
+    /// *  `source` - The element.
+    /// *  `message` - A description of the problem.
+    /// *  `style` - The current style.
+    pub fn unsupported<'t>(
+        &self,
+        source: &Node<'_, RefCell<Ast>>,
+        message: String,
+        style: Style,
+    ) -> Span<'t> {
+        let placeholder =
+            Span::styled(format!("[{}]", message), style.fg(Color::Red));
+        self.diagnostics.borrow_mut().push(Diagnostic {
+            line: source.data.borrow().start_line as usize,
+            message,
+        });
+        placeholder
+    }
+}
+
+/// A problem found when converting a document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// The line on which the problem was found.
+    pub line: usize,
+
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
 
 /// A collection of sections.
 #[derive(Clone, Debug)]
@@ -17,26 +158,330 @@ pub struct Sections<'a> {
 
     /// The margin between sections.
     pub inner_margin: u16,
+
+    /// The indices of the footnotes referenced from every section, if known.
+    footnote_references: Vec<Vec<usize>>,
+
+    /// The heights of the sections, and the width for which they were
+    /// measured.
+    heights: RefCell<Option<(u16, Vec<u16>)>>,
 }
 
 impl<'a> Sections<'a> {
+    /// The heading of the table of contents.
+    pub const TABLE_OF_CONTENTS: &'static str = "Contents";
+
+    /// Converts a page to sections.
+    ///
+    /// Elements that are not supported are replaced by placeholders, and
+    /// returned as diagnostics together with the sections.
+    ///
+    /// # Arguments
+    /// *  `page` - The page to convert.
+    /// *  `configuration` - The application configuration.
+    pub fn from_page(
+        page: &'a Page<'a>,
+        configuration: &Configuration,
+    ) -> (Self, Vec<Diagnostic>) {
+        Self::from_page_with_highlights(
+            page,
+            configuration,
+            &Highlights::default(),
+        )
+    }
+
+    /// Converts a page to sections, using code blocks highlighted up front.
+    ///
+    /// Code blocks not found among the highlights are highlighted when
+    /// converted.
+    ///
+    /// # Arguments
+    /// *  `page` - The page to convert.
+    /// *  `configuration` - The application configuration.
+    /// *  `highlights` - The highlighted code blocks.
+    pub fn from_page_with_highlights(
+        page: &'a Page<'a>,
+        configuration: &Configuration,
+        highlights: &Highlights<'a>,
+    ) -> (Self, Vec<Diagnostic>) {
+        let mut context = Context::new(configuration);
+        context.highlights = Some(highlights);
+        if configuration.footnote_numbering == FootnoteNumbering::PerPage {
+            context.page_footnotes = Some(page.footnote_references());
+        }
+        let mut sections = Vec::new();
+        let mut footnote_references = Vec::new();
+
+        // The columns of the current column layout, if any, and the footnotes
+        // referenced from them; the last column is the one currently being
+        // filled
+        let mut columns: Option<Vec<Vec<Section>>> = None;
+        let mut column_references = Vec::new();
+        for source in page.nodes() {
+            match (ColumnMarker::from_node(source), columns.as_mut()) {
+                (Some(ColumnMarker::Start), _) => {
+                    if let Some(columns) = columns.take() {
+                        sections.push(Section::columns(columns, configuration));
+                        footnote_references
+                            .push(std::mem::take(&mut column_references));
+                    }
+                    columns = Some(vec![Vec::new()]);
+                }
+                (Some(ColumnMarker::Next), Some(current)) => {
+                    current.push(Vec::new());
+                }
+                (Some(ColumnMarker::End), Some(_)) => {
+                    if let Some(columns) = columns.take() {
+                        sections.push(Section::columns(columns, configuration));
+                        footnote_references
+                            .push(std::mem::take(&mut column_references));
+                    }
+                }
+                (Some(_), None) => {}
+                (None, Some(current)) => {
+                    section(
+                        source,
+                        current.last_mut().unwrap(),
+                        configuration.default_style(),
+                        &context,
+                    );
+                    column_references
+                        .extend(context.footnote_references.take());
+                }
+                (None, None) => {
+                    let count = sections.len();
+                    section(
+                        source,
+                        &mut sections,
+                        configuration.default_style(),
+                        &context,
+                    );
+
+                    // A node converted to several sections may reference its
+                    // footnotes from any of them
+                    let references = context.footnote_references.take();
+                    footnote_references
+                        .resize(sections.len().max(count), references.clone());
+                }
+            }
+        }
+        if let Some(columns) = columns.take() {
+            sections.push(Section::columns(columns, configuration));
+            footnote_references.push(column_references);
+        }
+
+        let mut sections = Sections::from(sections);
+        sections.inner_margin = configuration.section_spacing;
+        sections.footnote_references = footnote_references;
+        (sections, context.diagnostics.into_inner())
+    }
+
+    /// The indices of the footnotes referenced from these sections, in the
+    /// order they are first referenced.
+    pub fn footnote_references(&self) -> Vec<usize> {
+        let mut result = Vec::new();
+        for index in self.footnote_references.iter().flatten() {
+            if !result.contains(index) {
+                result.push(*index);
+            }
+        }
+        result
+    }
+
+    /// A range of these sections, with the same margin.
+    ///
+    /// # Arguments
+    /// *  `range` - The range of sections.
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        let mut result = Sections::from(self.sections[range.clone()].to_vec());
+        result.inner_margin = self.inner_margin;
+        result.footnote_references = self
+            .footnote_references
+            .get(range)
+            .map(<[_]>::to_vec)
+            .unwrap_or_default();
+        result
+    }
+
+    /// Creates the content of the metadata page.
+    ///
+    /// The title is shown as a heading, followed by the subtitle, author and
+    /// date of the presentation, all centered; values that are not set are
+    /// left out.
+    ///
+    /// # Arguments
+    /// *  `configuration` - The application configuration.
+    pub fn metadata(configuration: &Configuration) -> Self {
+        let style = configuration.default_style();
+        let title = Some(&configuration.title)
+            .filter(|title| !title.is_empty())
+            .map(|title| Section::Heading {
+                text: Spans::from(Span::styled(
+                    title.clone(),
+                    style.patch(configuration.headings.h1.style()),
+                )),
+                level: 1,
+                padding: (0, 0),
+                hash: false,
+                alignment: Alignment::Center,
+                break_wide: configuration.wraps_wide_characters(),
+            });
+        let lines = [
+            (
+                &configuration.subtitle,
+                style.add_modifier(Modifier::ITALIC),
+            ),
+            (&configuration.author, style),
+            (&configuration.date, style),
+        ]
+        .into_iter()
+        .filter_map(|(text, style)| {
+            text.as_ref().map(|text| Section::Line {
+                text: Spans::from(Span::styled(text.clone(), style)),
+                alignment: Alignment::Center,
+                break_wide: configuration.wraps_wide_characters(),
+            })
+        });
+        let mut sections =
+            Sections::from(title.into_iter().chain(lines).collect::<Vec<_>>());
+        sections.inner_margin = configuration.section_spacing;
+        sections
+    }
+
+    /// Creates a table of contents.
+    ///
+    /// Every page with a title is listed with its page number, as shown by
+    /// the page counter.
+    ///
+    /// # Arguments
+    /// *  `titles` - The titles of all pages.
+    /// *  `leading` - The number of pages generated before the pages, such as
+    ///    the table of contents itself.
+    /// *  `configuration` - The application configuration.
+    pub fn table_of_contents(
+        titles: &[Option<String>],
+        leading: usize,
+        configuration: &Configuration,
+    ) -> Self {
+        let style = configuration.default_style();
+        let heading = Section::heading(
+            vec![Span::styled(
+                Self::TABLE_OF_CONTENTS,
+                style.patch(configuration.headings.h1.style()),
+            )],
+            1,
+            style,
+            configuration,
+        );
+        let mut items = Sections::from(
+            titles
+                .iter()
+                .enumerate()
+                .filter_map(|(i, title)| {
+                    title.as_ref().map(|title| Section::ListItemOrdered {
+                        content: vec![Section::paragraph(
+                            Text::styled(title.clone(), style),
+                            configuration,
+                        )]
+                        .into(),
+                        ordinal: leading + i + 1,
+                        delimiter: '.',
+                        indent: configuration.indent_width,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        );
+        items.inner_margin = 0;
+        let mut sections =
+            Sections::from(vec![heading, Section::List { content: items }]);
+        sections.inner_margin = configuration.section_spacing;
+        sections
+    }
+
+    /// The heights of the sections for a width.
+    ///
+    /// The heights are measured once, and cached until they are requested
+    /// for a different width.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the rendering area.
+    /// *  `measure` - Measures the heights when they are not cached.
+    pub fn heights<F>(&self, width: u16, measure: F) -> Ref<'_, [u16]>
+    where
+        F: FnOnce() -> Vec<u16>,
+    {
+        let cached = matches!(
+            &*self.heights.borrow(),
+            Some((cached_width, _)) if *cached_width == width,
+        );
+        if !cached {
+            *self.heights.borrow_mut() = Some((width, measure()));
+        }
+        Ref::map(self.heights.borrow(), |heights| {
+            heights
+                .as_ref()
+                .map(|(_, heights)| heights.as_slice())
+                .unwrap()
+        })
+    }
+
+    /// The source code of all code blocks, in the order they appear.
+    ///
+    /// Code blocks nested in block quotes, lists and columns are included.
+    pub fn code(&self) -> Vec<&str> {
+        self.sections
+            .iter()
+            .flat_map(|section| match section {
+                Section::Code { code, .. } => vec![code.as_str()],
+                Section::BlockQuote { content, .. }
+                | Section::List { content }
+                | Section::ListItemOrdered { content, .. }
+                | Section::ListItemUnordered { content, .. } => content.code(),
+                Section::Columns { columns } => {
+                    columns.iter().flat_map(|column| column.code()).collect()
+                }
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    /// The plain text of these sections.
+    ///
+    /// Sections are separated by the inner margin, and all styles are
+    /// dropped.
+    pub fn text(&self) -> String {
+        self.iter()
+            .map(Section::text)
+            .collect::<Vec<_>>()
+            .join(&"\n".repeat(1 + self.inner_margin as usize))
+    }
+
+    /// The number of words in these sections.
+    ///
+    /// # Arguments
+    /// *  `code` - Whether to count the words of code blocks.
+    pub fn word_count(&self, code: bool) -> usize {
+        self.iter().map(|section| section.word_count(code)).sum()
+    }
+
     /// Reorders all ordered list items in a list of sections.
     ///
+    /// Only the items directly in these sections are renumbered; nested lists
+    /// are numbered from their own start when they are converted.
+    ///
     /// # Arguments
     /// *  `start_at` - The starting index.
     fn list_item_reorder(&mut self, start_at: usize) {
         self.sections
             .iter_mut()
-            .filter(|section| match &section {
-                Section::ListItemOrdered { .. } => true,
-                _ => false,
+            .filter(|section| {
+                matches!(section, Section::ListItemOrdered { .. })
             })
             .enumerate()
-            .for_each(|(i, mut section)| match &mut section {
-                Section::ListItemOrdered {
-                    ref mut ordinal, ..
-                } => *ordinal = start_at + i,
-                _ => {}
+            .for_each(|(i, section)| {
+                if let Section::ListItemOrdered { ordinal, .. } = section {
+                    *ordinal = start_at + i;
+                }
             });
     }
 }
@@ -55,20 +500,88 @@ impl<'a> From<Vec<Section<'a>>> for Sections<'a> {
             Self {
                 sections: source,
                 inner_margin: 1,
+                footnote_references: Vec::new(),
+                heights: RefCell::new(None),
             }
         }
     }
 }
 
-impl<'a> From<&'a Page<'a>> for Sections<'a> {
-    fn from(source: &'a Page<'a>) -> Self {
-        {
-            let mut sections = Vec::new();
-            for source in source.nodes() {
-                section(source, &mut sections, Style::default());
-            }
-            sections.into()
+/// The footnotes of a presentation.
+///
+/// Every definition is converted to sections once, and shared by all pages
+/// referencing it.
+#[derive(Default)]
+pub struct Footnotes<'a> {
+    /// The converted definitions, by index.
+    definitions: HashMap<usize, Rc<Sections<'a>>>,
+
+    /// The problems found in the definitions, by index.
+    diagnostics: HashMap<usize, Vec<Diagnostic>>,
+}
+
+impl<'a> Footnotes<'a> {
+    /// Converts the footnote definitions of a presentation.
+    ///
+    /// # Arguments
+    /// *  `presentation` - The presentation.
+    /// *  `configuration` - The application configuration.
+    pub fn from_presentation(
+        presentation: &Presentation<'a>,
+        configuration: &Configuration,
+    ) -> Self {
+        let mut result = Self::default();
+        for (index, source) in presentation.footnotes() {
+            let context = Context::new(configuration);
+            let mut content = Vec::new();
+            sections(
+                source,
+                &mut content,
+                configuration.default_style(),
+                &context,
+            );
+            let mut content = Sections::from(content);
+            content.inner_margin = configuration.section_spacing;
+            result.definitions.insert(index, Rc::new(content));
+            result
+                .diagnostics
+                .insert(index, context.diagnostics.into_inner());
         }
+        result
+    }
+
+    /// The definition of a footnote.
+    ///
+    /// # Arguments
+    /// *  `index` - The zero based index of the footnote.
+    pub fn get(&self, index: usize) -> Option<Rc<Sections<'a>>> {
+        self.definitions.get(&index).cloned()
+    }
+
+    /// The problems found in the definition of a footnote.
+    ///
+    /// # Arguments
+    /// *  `index` - The zero based index of the footnote.
+    pub fn diagnostics(&self, index: usize) -> &[Diagnostic] {
+        self.diagnostics
+            .get(&index)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Converts the index of a footnote to its number in superscript.
+    ///
+    /// # Arguments
+    /// *  `index` - The zero based index of the footnote.
+    pub fn index_to_superscript(index: usize) -> String {
+        const DIGITS: [char; 10] =
+            ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+        (index + 1)
+            .to_string()
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .map(|digit| DIGITS[digit as usize])
+            .collect()
     }
 }
 
@@ -79,12 +592,40 @@ pub enum Section<'a> {
     BlockQuote {
         /// The content of the quote.
         content: Sections<'a>,
+
+        /// The width of one level of indentation.
+        indent: u16,
+
+        /// The text shown on the line above the quote, or an empty string
+        /// to leave the line out.
+        marker: String,
     },
 
     /// A code block.
     Code {
         /// The text of the section.
         text: Text<'a>,
+
+        /// The source code, as written in the document.
+        code: String,
+
+        /// The language of the code, as given by the info string.
+        language: Option<String>,
+
+        /// Whether to show the language as a caption.
+        caption: bool,
+
+        /// Whether to draw a border around the code.
+        border: bool,
+
+        /// The number of cells the code is indented by in the plain text.
+        indent: u16,
+    },
+
+    /// Sections laid out side by side.
+    Columns {
+        /// The content of the columns, from left to right.
+        columns: Vec<Sections<'a>>,
     },
 
     /// A heading section.
@@ -94,6 +635,27 @@ pub enum Section<'a> {
 
         /// The heading level.
         level: u8,
+
+        /// The top and bottom padding.
+        padding: (u16, u16),
+
+        /// Whether to show one `#` per level before the text.
+        hash: bool,
+
+        /// The horizontal alignment of the heading.
+        alignment: Alignment,
+
+        /// Whether lines may be wrapped between any two wide characters.
+        break_wide: bool,
+    },
+
+    /// An image.
+    Image {
+        /// The path to the image, as written in the document.
+        path: String,
+
+        /// The alternative text of the image.
+        alt: String,
     },
 
     /// A collection of list items.
@@ -112,6 +674,9 @@ pub enum Section<'a> {
 
         /// The delimiter.
         delimiter: char,
+
+        /// The width of the marker column.
+        indent: u16,
     },
 
     /// A list item in an unordered list.
@@ -121,21 +686,312 @@ pub enum Section<'a> {
 
         /// The bullet marker.
         bullet: char,
+
+        /// The width of the marker column.
+        indent: u16,
+    },
+
+    /// A single line of text, wrapped if it is too long.
+    Line {
+        /// The text of the section.
+        text: Spans<'a>,
+
+        /// The horizontal alignment of the text.
+        alignment: Alignment,
+
+        /// Whether lines may be wrapped between any two wide characters.
+        break_wide: bool,
     },
 
     /// A paragraph.
     Paragraph {
         /// The text of the section.
         text: Text<'a>,
+
+        /// How long lines are wrapped, or `None` to clip them.
+        wrap: Option<Wrap>,
+
+        /// The text ending clipped lines.
+        ellipsis: String,
+
+        /// Whether lines may be wrapped between any two wide characters.
+        break_wide: bool,
+    },
+
+    /// A table.
+    Table {
+        /// The cells of the header row; the number of cells is the number of
+        /// columns.
+        header: Vec<Text<'a>>,
+
+        /// The cells of the other rows.
+        rows: Vec<Vec<Text<'a>>>,
+
+        /// How the table is drawn.
+        options: TableOptions,
     },
 
     /// A thematic break
-    ThematicBreak,
+    ThematicBreak {
+        /// The text drawn as the rule.
+        symbol: String,
+
+        /// Whether to repeat the text to span the full width.
+        full_width: bool,
+
+        /// The style of the rule.
+        style: Style,
+    },
+}
+
+/// How a table is drawn.
+#[derive(Clone, Debug)]
+pub struct TableOptions {
+    /// The style of every other row, if rows are striped.
+    pub stripe: Option<Style>,
+
+    /// Whether to draw a border around the table.
+    pub border: bool,
+
+    /// The widths of the columns.
+    pub widths: Vec<Constraint>,
+
+    /// The text ending cells cut off to fit their column.
+    pub ellipsis: String,
 }
 
 impl<'a> Section<'a> {
-    /// The number of cells each level of indentaion provides.
-    pub const INDENT: u16 = 4;
+    /// The number of cells between columns.
+    pub const COLUMN_GAP: u16 = 2;
+
+    /// Creates a heading section.
+    ///
+    /// # Arguments
+    /// *  `text` - The text of the heading.
+    /// *  `level` - The heading level.
+    /// *  `style` - The current style.
+    /// *  `configuration` - The application configuration.
+    fn heading(
+        text: Vec<Span<'a>>,
+        level: u8,
+        style: Style,
+        configuration: &Configuration,
+    ) -> Self {
+        let heading = configuration.headings.level(level);
+        let mut text = Spans::from(text);
+        if configuration.heading_marker != HeadingMarker::None
+            && !heading.prefix.is_empty()
+        {
+            text.0
+                .insert(0, Span::styled(heading.prefix.clone(), style));
+        }
+        Section::Heading {
+            text,
+            level,
+            padding: (heading.padding_top, heading.padding_bottom),
+            hash: configuration.heading_marker == HeadingMarker::Hash,
+            alignment: heading.alignment.into(),
+            break_wide: configuration.wraps_wide_characters(),
+        }
+    }
+
+    /// Creates a paragraph section.
+    ///
+    /// # Arguments
+    /// *  `text` - The text of the paragraph.
+    /// *  `configuration` - The application configuration.
+    fn paragraph(text: Text<'a>, configuration: &Configuration) -> Self {
+        Section::Paragraph {
+            text,
+            wrap: configuration.paragraph_wrap.then_some(Wrap {
+                trim: configuration.paragraph_trim,
+            }),
+            ellipsis: configuration.ellipsis.clone(),
+            break_wide: configuration.wraps_wide_characters(),
+        }
+    }
+
+    /// Creates a column layout.
+    ///
+    /// # Arguments
+    /// *  `columns` - The sections of every column.
+    /// *  `configuration` - The application configuration.
+    fn columns(
+        columns: Vec<Vec<Section<'a>>>,
+        configuration: &Configuration,
+    ) -> Self {
+        Section::Columns {
+            columns: columns
+                .into_iter()
+                .map(|column| {
+                    let mut column = Sections::from(column);
+                    column.inner_margin = configuration.section_spacing;
+                    column
+                })
+                .collect(),
+        }
+    }
+
+    /// The number of words in this section.
+    ///
+    /// List markers, heading markers and the alternative text of images are
+    /// not counted.
+    ///
+    /// # Arguments
+    /// *  `code` - Whether to count the words of code blocks.
+    pub fn word_count(&self, code: bool) -> usize {
+        use Section::*;
+        match self {
+            BlockQuote { content, .. }
+            | List { content }
+            | ListItemOrdered { content, .. }
+            | ListItemUnordered { content, .. } => content.word_count(code),
+            Code { code: source, .. } if code => {
+                source.split_whitespace().count()
+            }
+            Columns { columns } => {
+                columns.iter().map(|column| column.word_count(code)).sum()
+            }
+            Heading { text, .. } | Line { text, .. } => text
+                .0
+                .iter()
+                .map(|span| span.content.split_whitespace().count())
+                .sum(),
+            Paragraph { .. } | Table { .. } => {
+                self.text().split_whitespace().count()
+            }
+            Code { .. } | Image { .. } | ThematicBreak { .. } => 0,
+        }
+    }
+
+    /// The plain text of this section.
+    ///
+    /// Nested content is indented, and list items are prefixed by their
+    /// markers.
+    pub fn text(&self) -> String {
+        use Section::*;
+        match self {
+            BlockQuote { content, .. } => indent(&content.text(), "> ", "> "),
+            Code {
+                code,
+                indent: width,
+                ..
+            } => indent(
+                code.strip_suffix('\n').unwrap_or(code),
+                &" ".repeat(*width as usize),
+                &" ".repeat(*width as usize),
+            ),
+            Columns { columns } => columns
+                .iter()
+                .map(|column| column.text())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+            Heading { text, level, .. } => format!(
+                "{} {}",
+                "#".repeat(*level as usize),
+                text.0.iter().map(|span| &*span.content).collect::<String>(),
+            ),
+            Image { alt, .. } => format!("[{}]", alt),
+            Line { text, .. } => {
+                text.0.iter().map(|span| &*span.content).collect()
+            }
+            List { content } => content.text(),
+            ListItemOrdered {
+                content,
+                ordinal,
+                delimiter,
+                indent: width,
+            } => indent(
+                &content.text(),
+                &marker(&format!("{}{}", ordinal, delimiter), *width),
+                &marker("", *width),
+            ),
+            ListItemUnordered {
+                content,
+                bullet,
+                indent: width,
+            } => indent(
+                &content.text(),
+                &marker(&bullet.to_string(), *width),
+                &marker("", *width),
+            ),
+            Paragraph { text, .. } => text
+                .lines
+                .iter()
+                .map(|line| line.0.iter().map(|span| &*span.content))
+                .map(|spans| spans.collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Table { header, rows, .. } => std::iter::once(header)
+                .chain(rows)
+                .map(|cells| {
+                    cells
+                        .iter()
+                        .map(|cell| {
+                            cell.lines
+                                .iter()
+                                .flat_map(|line| line.0.iter())
+                                .map(|span| &*span.content)
+                                .collect::<String>()
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" | ")
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ThematicBreak { .. } => "---".into(),
+        }
+    }
+}
+
+/// The estimated time to read a number of words.
+///
+/// # Arguments
+/// *  `words` - The number of words.
+/// *  `words_per_minute` - The reading speed.
+pub fn reading_time(words: usize, words_per_minute: u32) -> Duration {
+    Duration::from_secs(
+        (words as u64 * 60).div_ceil(u64::from(words_per_minute.max(1))),
+    )
+}
+
+/// Pads a list item marker to the indentation width.
+///
+/// A space always follows a marker, even if it is wider than the
+/// indentation.
+///
+/// # Arguments
+/// *  `marker` - The marker.
+/// *  `width` - The indentation width.
+fn marker(marker: &str, width: u16) -> String {
+    if marker.is_empty() {
+        " ".repeat(width as usize)
+    } else {
+        format!("{:width$}", format!("{} ", marker), width = width as usize)
+    }
+}
+
+/// Prefixes all lines of a text.
+///
+/// Empty lines are not prefixed by trailing whitespace.
+///
+/// # Arguments
+/// *  `text` - The text to indent.
+/// *  `first` - The prefix of the first line.
+/// *  `rest` - The prefix of all other lines.
+fn indent(text: &str, first: &str, rest: &str) -> String {
+    text.split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            let prefix = if i == 0 { first } else { rest };
+            if line.is_empty() {
+                prefix.trim_end().to_string()
+            } else {
+                format!("{}{}", prefix, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 /// Converts a collection of markdown AST nodes to sections.
@@ -143,61 +999,98 @@ impl<'a> Section<'a> {
 /// # Arguments
 /// *  `nodes` - The nodes to style.
 /// *  `style` - The current style.
+/// *  `context` - The transformation context.
 fn sections<'a>(
     source: &'a Node<'a, RefCell<Ast>>,
     target: &mut Vec<Section<'a>>,
     style: Style,
+    context: &Context<'a, '_>,
 ) {
     for source in source.children() {
-        section(source, target, style);
+        section(source, target, style, context);
     }
 }
 
 /// Handles a single block element.
 ///
+/// Elements that are not supported are replaced by placeholders.
+///
 /// # Arguments
 /// *  `source` - The element to handle.
 /// *  `target` - A target `Vec` for generated spans.
 /// *  `style` - The current style.
+/// *  `context` - The transformation context.
 fn section<'a>(
     source: &'a Node<'a, RefCell<Ast>>,
     target: &mut Vec<Section<'a>>,
     style: Style,
+    context: &Context<'a, '_>,
 ) {
+    let configuration = context.configuration;
     let node = &source.data.borrow().value;
     match node {
         NodeValue::BlockQuote => {
             let mut content = Vec::new();
-            sections(source, &mut content, style.add_modifier(Modifier::DIM));
+            sections(
+                source,
+                &mut content,
+                style.add_modifier(Modifier::DIM),
+                context,
+            );
             let content = content.into();
-            target.push(Section::BlockQuote { content });
+            target.push(Section::BlockQuote {
+                content,
+                indent: configuration.indent_width,
+                marker: configuration.blockquote_marker.clone(),
+            });
         }
 
+        NodeValue::CodeBlock(code)
+            if presentation::page_configuration(code).is_some() => {}
+
         NodeValue::CodeBlock(code) => {
-            // TODO: Apply highlight based on code.info
-            let text = Text {
-                lines: String::from_utf8_lossy(&code.literal)
-                    .into_owned()
-                    .split('\n')
-                    .map(|s| s.to_string().into())
-                    .collect::<Vec<_>>(),
-            };
-            target.push(Section::Code { text });
+            let info = String::from_utf8_lossy(&code.info);
+            let language = info
+                .split_whitespace()
+                .next()
+                .filter(|language| !PLAIN_TEXT_TOKENS.contains(language))
+                .map(String::from);
+            let token = code_token(code, configuration);
+            let code = String::from_utf8_lossy(&code.literal).into_owned();
+            let text = context
+                .highlights
+                .and_then(|highlights| highlights.get(source))
+                .unwrap_or_else(|| {
+                    highlight(
+                        &code,
+                        &token,
+                        &context.syntax_set,
+                        &context.theme,
+                    )
+                });
+            target.push(Section::Code {
+                text,
+                code,
+                language,
+                caption: configuration.code_caption,
+                border: configuration.code_border,
+                indent: configuration.indent_width,
+            });
         }
 
         NodeValue::Heading(heading) => {
-            let text = Spans::from(root_inlines(
-                source.children(),
-                style.add_modifier(Modifier::UNDERLINED),
-            ))
-            .into();
             let level = heading.level as u8;
-            target.push(Section::Heading { text, level });
+            let text = root_inlines(
+                source.children(),
+                style.patch(configuration.headings.level(level).style()),
+                context,
+            );
+            target.push(Section::heading(text, level, style, configuration));
         }
 
         NodeValue::Item(item) => {
             let mut content = Vec::new();
-            sections(source, &mut content, style);
+            sections(source, &mut content, style, context);
             let content = Sections::from(content);
             target.push(match item.list_type {
                 ListType::Ordered => Section::ListItemOrdered {
@@ -207,86 +1100,494 @@ fn section<'a>(
                         ListDelimType::Period => '.',
                         ListDelimType::Paren => ')',
                     },
+                    indent: configuration.indent_width,
                 },
                 ListType::Bullet => Section::ListItemUnordered {
                     content,
                     bullet: item.bullet_char.into(),
+                    indent: configuration.indent_width,
                 },
             })
         }
 
         NodeValue::List(list) => {
             let mut content = Vec::new();
-            sections(source, &mut content, style);
+            sections(source, &mut content, style, context);
             let mut content = Sections::from(content);
             content.inner_margin = 0;
             content.list_item_reorder(list.start);
             target.push(Section::List { content });
         }
 
+        NodeValue::Paragraph if is_lone_image(source) => {
+            let image = source.first_child().unwrap();
+            if let NodeValue::Image(link) = &image.data.borrow().value {
+                let path = String::from_utf8_lossy(&link.url).into_owned();
+                let alt = root_inlines(image.children(), style, context)
+                    .into_iter()
+                    .map(|span| span.content)
+                    .collect();
+                target.push(Section::Image { path, alt });
+            }
+        }
+
         NodeValue::Paragraph => {
             let text =
-                Spans::from(root_inlines(source.children(), style)).into();
-            target.push(Section::Paragraph { text });
+                lines(root_inlines(source.children(), style, context)).into();
+            target.push(Section::paragraph(text, configuration));
         }
         NodeValue::ThematicBreak => {
-            target.push(Section::ThematicBreak);
+            let thematic_break = &configuration.thematic_break;
+            target.push(Section::ThematicBreak {
+                symbol: thematic_break.symbol.clone(),
+                full_width: thematic_break.full_width,
+                style: style.fg(thematic_break.color),
+            });
         }
 
         // TODO: Enable description lists and handle them
         NodeValue::DescriptionDetails
         | NodeValue::DescriptionItem(_)
         | NodeValue::DescriptionTerm => {
-            unimplemented!(
-                "Description lists are not supported, but found on line {}",
-                source.data.borrow().start_line,
-            )
+            let placeholder = context.unsupported(
+                source,
+                "Description lists are not supported".into(),
+                style,
+            );
+            target.push(Section::paragraph(placeholder.into(), configuration));
         }
 
-        // TODO: Enable footnote references and handle them
-        NodeValue::FootnoteDefinition(_) => {
-            unimplemented!(
-                "Footnote definitions are not supported, but found on line {}",
-                source.data.borrow().start_line,
-            )
-        }
+        // Footnote definitions are listed separately by the page
+        NodeValue::FootnoteDefinition(_) => {}
 
-        // TODO: Enable tables and handle them
-        NodeValue::TableCell | NodeValue::TableRow(_) | NodeValue::Table(_) => {
-            unimplemented!(
-                "Tables are not supported, but found on line {}",
-                source.data.borrow().start_line,
-            )
+        // The first row of a table is its header
+        NodeValue::Table(_) => {
+            let mut rows = source.children().map(|row| {
+                row.children()
+                    .map(|cell| {
+                        Spans::from(root_inlines(
+                            cell.children(),
+                            style,
+                            context,
+                        ))
+                        .into()
+                    })
+                    .collect::<Vec<_>>()
+            });
+            let header = rows.next().unwrap_or_default();
+            let rows = rows.collect();
+            let stripe = configuration.table_zebra.then(|| {
+                Style::default().bg(match configuration.theme_mode {
+                    ThemeMode::Light => Color::Indexed(254),
+                    ThemeMode::Auto | ThemeMode::Dark => Color::Indexed(236),
+                })
+            });
+            let columns = header.len();
+            let widths = if configuration.table_widths.len() == columns {
+                configuration
+                    .table_widths
+                    .iter()
+                    .copied()
+                    .map(Constraint::from)
+                    .collect()
+            } else {
+                vec![Constraint::Ratio(1, columns as u32); columns]
+            };
+            target.push(Section::Table {
+                header,
+                rows,
+                options: TableOptions {
+                    stripe,
+                    border: configuration.table_border,
+                    widths,
+                    ellipsis: configuration.ellipsis.clone(),
+                },
+            });
         }
 
+        // Comments are speaker notes, and are not part of the page
+        NodeValue::HtmlBlock(html)
+            if presentation::comment(&html.literal).is_some() => {}
+
         // These are not supported
         NodeValue::HtmlBlock(_) => {
-            unimplemented!(
-                "The element {:?} on line {} is not supported.",
-                node,
-                source.data.borrow().start_line
-            )
+            let placeholder = context.unsupported(
+                source,
+                "HTML is not supported".into(),
+                style,
+            );
+            target.push(Section::paragraph(placeholder.into(), configuration));
         }
 
-        _ => unimplemented!(
-            "{:?} was unexpected on line {}",
-            node,
-            source.data.borrow().start_line,
-        ),
+        _ => {
+            let placeholder = context.unsupported(
+                source,
+                format!("{:?} was unexpected", node),
+                style,
+            );
+            target.push(Section::paragraph(placeholder.into(), configuration));
+        }
     }
 }
 
+/// Code blocks highlighted up front.
+///
+/// Highlighting code blocks is independent of the rest of a page, so the code
+/// blocks of all pages are highlighted in parallel before the pages are
+/// converted. The code blocks are identified by their nodes, which live as
+/// long as the arena of the presentation.
+#[derive(Default)]
+pub struct Highlights<'a> {
+    /// The highlighted code blocks, keyed by the address of their nodes.
+    texts: HashMap<usize, Text<'static>>,
+
+    /// The nodes are only valid while the arena of the presentation lives.
+    arena: PhantomData<&'a ()>,
+}
+
+impl<'a> Highlights<'a> {
+    /// Highlights the code blocks of pages in parallel.
+    ///
+    /// # Arguments
+    /// *  `pages` - The pages with their configuration.
+    pub fn from_pages<'c, I>(pages: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a Page<'a>, &'c Configuration)>,
+    {
+        Self::from_pages_with_progress(pages, |_, _| {})
+    }
+
+    /// Highlights the code blocks of pages in parallel, reporting the
+    /// progress.
+    ///
+    /// # Arguments
+    /// *  `pages` - The pages with their configuration.
+    /// *  `progress` - A function called on the calling thread after every
+    ///    code block has been highlighted, with the number of code blocks
+    ///    highlighted and the total number of code blocks.
+    pub fn from_pages_with_progress<'c, I, F>(pages: I, mut progress: F) -> Self
+    where
+        I: IntoIterator<Item = (&'a Page<'a>, &'c Configuration)>,
+        F: FnMut(usize, usize),
+    {
+        // The nodes cannot be shared between threads, so the code and the
+        // highlighting settings are collected first
+        let blocks = pages
+            .into_iter()
+            .flat_map(|(page, configuration)| {
+                let context = Context::new(configuration);
+                page.nodes()
+                    .flat_map(|node| node.descendants())
+                    .filter_map(move |node| match &node.data.borrow().value {
+                        NodeValue::CodeBlock(code)
+                            if presentation::page_configuration(code)
+                                .is_none() =>
+                        {
+                            Some((
+                                Self::key(node),
+                                String::from_utf8_lossy(&code.literal)
+                                    .into_owned(),
+                                code_token(code, configuration),
+                                context.syntax_set.clone(),
+                                context.theme.clone(),
+                            ))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // The worker threads report every highlighted block, and the progress
+        // is reported from this thread until they are all done
+        let (sender, receiver) = mpsc::channel();
+        let texts = thread::scope(|scope| {
+            let texts = scope.spawn(|| {
+                blocks
+                    .par_iter()
+                    .map_with(
+                        sender,
+                        |sender, (key, code, token, syntax_set, theme)| {
+                            let text =
+                                highlight(code, token, syntax_set, theme);
+                            sender.send(()).ok();
+                            (*key, text)
+                        },
+                    )
+                    .collect::<HashMap<_, _>>()
+            });
+            for (highlighted, _) in receiver.iter().enumerate() {
+                progress(highlighted + 1, blocks.len());
+            }
+            texts.join().unwrap()
+        });
+        Self {
+            texts,
+            arena: PhantomData,
+        }
+    }
+
+    /// The highlighted text of a code block, if it has been highlighted.
+    ///
+    /// # Arguments
+    /// *  `node` - The node of the code block.
+    pub fn get<'t>(
+        &self,
+        node: &'a Node<'a, RefCell<Ast>>,
+    ) -> Option<Text<'t>> {
+        self.texts.get(&Self::key(node)).cloned()
+    }
+
+    /// The number of highlighted code blocks.
+    pub fn len(&self) -> usize {
+        self.texts.len()
+    }
+
+    /// Whether no code blocks have been highlighted.
+    pub fn is_empty(&self) -> bool {
+        self.texts.is_empty()
+    }
+
+    /// The key identifying a code block.
+    ///
+    /// # Arguments
+    /// *  `node` - The node of the code block.
+    fn key(node: &Node<'_, RefCell<Ast>>) -> usize {
+        node as *const _ as usize
+    }
+}
+
+/// The token identifying the language of a code block.
+///
+/// # Arguments
+/// *  `code` - The code block.
+/// *  `configuration` - The application configuration.
+fn code_token(code: &NodeCodeBlock, configuration: &Configuration) -> String {
+    String::from_utf8_lossy(&code.info)
+        .split_whitespace()
+        .next()
+        .or(configuration.default_code_language.as_deref())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// Highlights code.
+///
+/// If no syntax matches the token, the code is treated as plain text.
+///
+/// # Arguments
+/// *  `code` - The code to highlight.
+/// *  `token` - The token identifying the language of the code.
+/// *  `syntax_set` - The syntax definitions.
+/// *  `theme` - The theme used to highlight code.
+fn highlight<'a>(
+    code: &str,
+    token: &str,
+    syntax_set: &SyntaxSet,
+    theme: &Theme,
+) -> Text<'a> {
+    let syntax = find_syntax(syntax_set, token);
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    Text {
+        lines: code
+            .split('\n')
+            .map(|line| {
+                highlighter
+                    .highlight(&format!("{}\n", line), syntax_set)
+                    .into_iter()
+                    .map(|(style, s)| {
+                        Span::styled(
+                            s.trim_end_matches('\n').to_string(),
+                            highlight_style(style),
+                        )
+                    })
+                    .filter(|span| !span.content.is_empty())
+                    .collect::<Vec<_>>()
+                    .into()
+            })
+            .collect(),
+    }
+}
+
+/// Finds the syntax definition for the language of a code block.
+///
+/// The token is matched against the file extensions and names of the syntax
+/// definitions, and may also be a file name, such as `main.rs`. If no syntax
+/// definition matches, the plain text definition is used.
+///
+/// # Arguments
+/// *  `syntax_set` - The syntax definitions.
+/// *  `token` - The language of the code block.
+fn find_syntax<'s>(
+    syntax_set: &'s SyntaxSet,
+    token: &str,
+) -> &'s SyntaxReference {
+    syntax_set
+        .find_syntax_by_token(token)
+        .or_else(|| syntax_set.find_syntax_by_extension(&token.to_lowercase()))
+        .or_else(|| syntax_set.find_syntax_by_name(token))
+        .or_else(|| {
+            Path::new(token)
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .and_then(|extension| {
+                    syntax_set.find_syntax_by_extension(extension)
+                })
+        })
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text())
+}
+
+/// The info string tokens marking code as plain text; these are not shown as
+/// languages.
+const PLAIN_TEXT_TOKENS: [&str; 2] = ["nohighlight", "text"];
+
+/// Loads the syntax definitions used to highlight code.
+///
+/// The default syntax definitions are extended with those found in a
+/// directory. Syntax definitions are loaded only once for every directory.
+///
+/// # Arguments
+/// *  `directory` - A directory containing additional syntax definitions.
+pub fn syntax_set(directory: Option<&Path>) -> Result<Arc<SyntaxSet>, String> {
+    static SYNTAX_SETS: OnceLock<
+        Mutex<HashMap<Option<PathBuf>, Arc<SyntaxSet>>>,
+    > = OnceLock::new();
+
+    let mut syntax_sets = SYNTAX_SETS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap();
+    let key = directory.map(Path::to_path_buf);
+    if let Some(syntax_set) = syntax_sets.get(&key) {
+        return Ok(syntax_set.clone());
+    }
+
+    let syntax_set = match directory {
+        Some(directory) => {
+            let mut builder =
+                SyntaxSet::load_defaults_newlines().into_builder();
+            builder.add_from_folder(directory, true).map_err(|e| {
+                format!(
+                    "Failed to load syntax definitions from {}: {}",
+                    directory.display(),
+                    e,
+                )
+            })?;
+            builder.build()
+        }
+        None => SyntaxSet::load_defaults_newlines(),
+    };
+    let syntax_set = Arc::new(syntax_set);
+    syntax_sets.insert(key, syntax_set.clone());
+    Ok(syntax_set)
+}
+
+/// Converts a highlighting style to a terminal style.
+///
+/// # Arguments
+/// *  `style` - The style to convert.
+fn highlight_style(style: highlighting::Style) -> Style {
+    [
+        (FontStyle::BOLD, Modifier::BOLD),
+        (FontStyle::ITALIC, Modifier::ITALIC),
+        (FontStyle::UNDERLINE, Modifier::UNDERLINED),
+    ]
+    .into_iter()
+    .filter(|(font_style, _)| style.font_style.contains(*font_style))
+    .fold(
+        Style::default().fg(color(style.foreground)),
+        |result, (_, modifier)| result.add_modifier(modifier),
+    )
+}
+
+/// Converts a highlighting colour to a terminal colour.
+///
+/// # Arguments
+/// *  `color` - The colour to convert.
+fn color(color: highlighting::Color) -> Color {
+    Color::Rgb(color.r, color.g, color.b)
+}
+
+/// Splits text into runs of text marked as `==highlighted==` and unmarked
+/// text.
+///
+/// Like in other markdown implementations, the marked text may not begin or
+/// end with whitespace; markers that do not delimit highlighted text are kept
+/// as is.
+///
+/// # Arguments
+/// *  `text` - The text to split.
+fn highlights(text: &str) -> Vec<(&str, bool)> {
+    const MARKER: &str = "==";
+    let mut result = Vec::new();
+    let (mut start, mut position) = (0, 0);
+    while let Some(open) = text[position..].find(MARKER) {
+        let open = position + open;
+        let content = open + MARKER.len();
+        let close = text[content..].find(MARKER).map(|close| content + close);
+        match close {
+            Some(close)
+                if close > content
+                    && !text[content..close]
+                        .starts_with(char::is_whitespace)
+                    && !text[content..close].ends_with(char::is_whitespace) =>
+            {
+                result.push((&text[start..open], false));
+                result.push((&text[content..close], true));
+                start = close + MARKER.len();
+                position = start;
+            }
+            _ => position = content,
+        }
+    }
+    result.push((&text[start..], false));
+    result.retain(|(text, _)| !text.is_empty());
+    result
+}
+
+/// Determines whether a paragraph contains only a single image.
+///
+/// # Arguments
+/// *  `source` - The paragraph to check.
+fn is_lone_image<'a>(source: &'a Node<'a, RefCell<Ast>>) -> bool {
+    source.children().count() == 1
+        && source.first_child().is_some_and(|child| {
+            matches!(child.data.borrow().value, NodeValue::Image(_))
+        })
+}
+
+/// The span marking a hard line break.
+const LINE_BREAK: &str = "\n";
+
+/// Splits spans into lines at hard line breaks.
+///
+/// # Arguments
+/// *  `spans` - The spans to split.
+fn lines(spans: Vec<Span<'_>>) -> Vec<Spans<'_>> {
+    let mut result = vec![Vec::new()];
+    for span in spans {
+        if span.content == LINE_BREAK {
+            result.push(Vec::new());
+        } else {
+            result.last_mut().unwrap().push(span);
+        }
+    }
+    result.into_iter().map(Spans::from).collect()
+}
+
 /// Handles all children of a node as inline elements.
 ///
 /// # Arguments
 /// *  `source` - The element to handle.
 /// *  `style` - The current style.
+/// *  `context` - The transformation context.
 fn root_inlines<'a>(
     nodes: impl Iterator<Item = &'a Node<'a, RefCell<Ast>>>,
     style: Style,
+    context: &Context,
 ) -> Vec<Span<'a>> {
     nodes.fold(Vec::new(), |mut target, source| {
-        inline(source, &mut target, style);
+        inline(source, &mut target, style, context);
         target
     })
 }
@@ -297,108 +1598,1023 @@ fn root_inlines<'a>(
 /// *  `source` - The element to handle.
 /// *  `target` - A target `Vec` for generated spans.
 /// *  `style` - The current style.
+/// *  `context` - The transformation context.
 fn inlines<'a>(
     source: &'a Node<'a, RefCell<Ast>>,
     target: &mut Vec<Span<'a>>,
     style: Style,
+    context: &Context,
 ) {
     for source in source.children() {
-        inline(source, target, style)
+        inline(source, target, style, context);
     }
 }
 
 /// Handles a single inline element.
 ///
+/// Elements that are not supported are replaced by placeholders.
+///
 /// # Arguments
 /// *  `source` - The element to handle.
 /// *  `target` - A target `Vec` for generated spans.
 /// *  `style` - The current style.
+/// *  `context` - The transformation context.
 fn inline<'a>(
     source: &'a Node<'a, RefCell<Ast>>,
     target: &mut Vec<Span<'a>>,
     style: Style,
+    context: &Context,
 ) {
     use NodeValue::*;
     let node = &source.data.borrow().value;
     match node {
-        Code(code) => target.push(Span::raw(
+        Code(code) => target.push(Span::styled(
             String::from_utf8_lossy(&code.literal).into_owned(),
+            style.patch(Style::from(&context.configuration.inline_code)),
         )),
 
         Emph => {
-            inlines(source, target, style.add_modifier(Modifier::ITALIC));
-        }
-
-        LineBreak => {
-            target.push(Span::raw("\n"));
+            inlines(
+                source,
+                target,
+                style.add_modifier(Modifier::ITALIC),
+                context,
+            );
         }
 
-        Link(link) => {
+        // Images in running text are replaced by their alternative text
+        Image(_) => {
             inlines(
                 source,
                 target,
-                style.add_modifier(Modifier::UNDERLINED).fg(Color::Blue),
+                style.add_modifier(Modifier::ITALIC),
+                context,
             );
+        }
+
+        LineBreak => {
+            target.push(Span::raw(LINE_BREAK));
+        }
+
+        Link(link) => {
+            // The URL is styled as the link text, with its own style on top
+            let style =
+                style.patch(Style::from(&context.configuration.link_style));
+            inlines(source, target, style, context);
             target.push(Span::styled(
                 format!(" <{}>", String::from_utf8_lossy(&link.url)),
-                style,
+                style.patch(Style::from(&context.configuration.link_url_style)),
             ));
         }
 
-        SoftBreak => target.push(Span::raw(" ")),
+        SoftBreak => target.push(Span::styled(" ", style)),
 
         Strong => {
-            inlines(source, target, style.add_modifier(Modifier::BOLD));
+            inlines(
+                source,
+                target,
+                style.add_modifier(Modifier::BOLD),
+                context,
+            );
         }
 
         Strikethrough => {
-            inlines(source, target, style.add_modifier(Modifier::CROSSED_OUT));
+            inlines(
+                source,
+                target,
+                style.add_modifier(Modifier::CROSSED_OUT),
+                context,
+            );
         }
 
         Text(text) => {
+            let highlight =
+                style.patch(Style::from(&context.configuration.highlight));
+            for (text, highlighted) in
+                highlights(&String::from_utf8_lossy(text))
+            {
+                let style = if highlighted { highlight } else { style };
+                if context.configuration.math {
+                    target.extend(math::split(text).into_iter().map(
+                        |(text, math)| {
+                            Span::styled(
+                                if math {
+                                    math::to_unicode(text)
+                                } else {
+                                    text.to_string()
+                                },
+                                style,
+                            )
+                        },
+                    ));
+                } else {
+                    target.push(Span::styled(text.to_string(), style));
+                }
+            }
+        }
+
+        FootnoteReference(name) => {
+            if let Some(index) = presentation::footnote_index(name) {
+                context.footnote_references.borrow_mut().push(index);
+            }
             target.push(Span::styled(
-                String::from_utf8_lossy(text).into_owned(),
+                match presentation::footnote_index(name) {
+                    Some(index) => Footnotes::index_to_superscript(
+                        context.footnote_number(index),
+                    ),
+                    None => format!("[^{}]", String::from_utf8_lossy(name)),
+                },
                 style,
             ));
         }
 
-        // TODO: Enable footnote references and handle them
-        FootnoteReference(_) => {
-            unimplemented!(
-                "Footnote references are not supported, but found on line {}",
-                source.data.borrow().start_line,
-            )
-        }
-
         // TODO: Enable superscript and handle it
         Superscript => {
-            unimplemented!(
-                "Superscript is are not supported, but found on line {}",
-                source.data.borrow().start_line,
-            )
+            target.push(context.unsupported(
+                source,
+                "Superscript is not supported".into(),
+                style,
+            ));
         }
 
         // TODO: Enable task item lists and handle them
         TaskItem(_) => {
-            unimplemented!(
-                "Task item lists are not supported, but found on line {}",
-                source.data.borrow().start_line,
-            )
+            target.push(context.unsupported(
+                source,
+                "Task item lists are not supported".into(),
+                style,
+            ));
         }
 
+        // Comments are not part of the text
+        HtmlInline(html) if presentation::comment(html).is_some() => {}
+
         // These are not supported
-        HtmlInline(_) | Image(_) => {
-            unimplemented!(
-                "The element {:?} on line {} is not supported.",
-                node,
-                source.data.borrow().start_line
-            )
-        }
-
-        _ => unimplemented!(
-            "{:?} was unexpected on line {}",
-            node,
-            source.data.borrow().start_line,
-        ),
+        HtmlInline(_) => {
+            target.push(context.unsupported(
+                source,
+                "HTML is not supported".into(),
+                style,
+            ));
+        }
+
+        _ => {
+            target.push(context.unsupported(
+                source,
+                format!("{:?} was unexpected", node),
+                style,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::{StyleConfig, ThemeMode};
+    use crate::presentation::PageBreakCondition;
+    use crate::testing;
+
+    #[test]
+    fn section_spacing() {
+        let pages = testing::load_pages("test-resources/tall.md");
+
+        let mut configuration = Configuration::default();
+        assert_eq!(
+            7,
+            Sections::from_page(&pages[0], &configuration).0.height(40),
+        );
+        configuration.section_spacing = 0;
+        assert_eq!(
+            4,
+            Sections::from_page(&pages[0], &configuration).0.height(40),
+        );
+        configuration.section_spacing = 2;
+        assert_eq!(
+            10,
+            Sections::from_page(&pages[0], &configuration).0.height(40),
+        );
+    }
+
+    #[test]
+    fn context_shared() {
+        let configuration = Configuration::default();
+        let context = Context::new(&configuration);
+        let clone = context.clone();
+        let other = Context::new(&configuration);
+
+        assert!(Arc::ptr_eq(&context.syntax_set, &clone.syntax_set));
+        assert!(Arc::ptr_eq(&context.theme, &clone.theme));
+        assert!(Arc::ptr_eq(&context.syntax_set, &other.syntax_set));
+        assert!(Arc::ptr_eq(&context.theme, &other.theme));
+
+        let light = Configuration {
+            theme_mode: ThemeMode::Light,
+            ..Default::default()
+        };
+        assert!(!Arc::ptr_eq(&context.theme, &Context::new(&light).theme));
+    }
+
+    #[test]
+    fn code_highlighted() {
+        let configuration = Configuration::default();
+        let context = Context::new(&configuration);
+
+        let text = highlight(
+            "fn main() {}",
+            "rust",
+            &context.syntax_set,
+            &context.theme,
+        );
+        assert_eq!(1, text.lines.len());
+        assert!(text.lines[0].0.len() > 1);
+        assert_eq!(
+            "fn main() {}",
+            text.lines[0]
+                .0
+                .iter()
+                .map(|span| span.content.clone())
+                .collect::<String>(),
+        );
+        assert!(matches!(text.lines[0].0[0].style.fg, Some(Color::Rgb(..))));
+
+        let text =
+            highlight("a\nb\n", "unknown", &context.syntax_set, &context.theme);
+        assert_eq!(3, text.lines.len());
+    }
+
+    #[test]
+    fn heading_padding() {
+        let pages = testing::load_pages("test-resources/headings.md");
+
+        let mut configuration = Configuration::default();
+        configuration.headings.h1.padding_top = 2;
+        configuration.headings.h1.padding_bottom = 1;
+        let sections = Sections::from_page(&pages[0], &configuration).0;
+        let padding = sections
+            .iter()
+            .map(Section::padding)
+            .take(3)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![(2, 1), (1, 0), (1, 0)], padding);
+    }
+
+    #[test]
+    fn heading_marker() {
+        let pages = testing::load_pages("test-resources/headings.md");
+        let heading = |configuration: &Configuration| match Sections::from_page(
+            &pages[0],
+            configuration,
+        )
+        .0[0]
+            .clone()
+        {
+            Section::Heading { text, hash, .. } => (
+                text.0
+                    .iter()
+                    .map(|span| span.content.clone())
+                    .collect::<String>(),
+                hash,
+            ),
+            _ => panic!("expected a heading"),
+        };
+
+        let mut configuration = Configuration::default();
+        configuration.headings.h1.prefix = "§ ".into();
+        assert_eq!(("§ Level 1".into(), true), heading(&configuration));
+        configuration.heading_marker = HeadingMarker::PrefixOnly;
+        assert_eq!(("§ Level 1".into(), false), heading(&configuration));
+        configuration.heading_marker = HeadingMarker::None;
+        assert_eq!(("Level 1".into(), false), heading(&configuration));
+    }
+
+    #[test]
+    fn footnotes() {
+        let presentation = testing::load("test-resources/footnotes.md");
+        let pages = testing::pages(&presentation);
+
+        let configuration = Configuration::default();
+        let footnotes =
+            Footnotes::from_presentation(&presentation, &configuration);
+        assert!(footnotes.get(0).is_some());
+        assert!(footnotes.get(1).is_some());
+        assert!(footnotes.get(2).is_none());
+        assert!(Rc::ptr_eq(
+            &footnotes.get(0).unwrap(),
+            &footnotes.get(0).unwrap(),
+        ));
+
+        let sections = Sections::from_page(&pages[0], &configuration).0;
+        match &sections[1] {
+            Section::Paragraph { text, .. } => assert_eq!(
+                "A claim¹ and a remark².",
+                text.lines[0]
+                    .0
+                    .iter()
+                    .map(|span| span.content.clone())
+                    .collect::<String>(),
+            ),
+            _ => panic!("expected a paragraph"),
+        }
+        assert_eq!("¹⁰", Footnotes::index_to_superscript(9));
+    }
+
+    #[test]
+    fn index_to_superscript() {
+        assert_eq!(
+            vec!["¹", "⁹", "¹⁰", "¹¹", "¹²", "¹⁰⁰", "¹⁰¹"],
+            [0, 8, 9, 10, 11, 99, 100]
+                .into_iter()
+                .map(Footnotes::index_to_superscript)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn table_of_contents() {
+        let titles = [Some("First".to_string()), None, Some("Third".into())];
+        let sections =
+            Sections::table_of_contents(&titles, 2, &Configuration::default());
+
+        assert!(matches!(sections[0], Section::Heading { level: 1, .. }));
+        match &sections[1] {
+            Section::List { content } => assert_eq!(
+                vec![(3, "First".to_string()), (5, "Third".into())],
+                content
+                    .iter()
+                    .map(|item| match item {
+                        Section::ListItemOrdered {
+                            content, ordinal, ..
+                        } => match &content[0] {
+                            Section::Paragraph { text, .. } => (
+                                *ordinal,
+                                text.lines[0].0[0].content.to_string(),
+                            ),
+                            _ => panic!("expected a paragraph"),
+                        },
+                        _ => panic!("expected an ordered list item"),
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            _ => panic!("expected a list"),
+        }
+    }
+
+    #[test]
+    fn unsupported() {
+        let pages = testing::load_pages("test-resources/unsupported.md");
+
+        let (sections, diagnostics) =
+            Sections::from_page(&pages[0], &Configuration::default());
+        assert_eq!(
+            vec![Diagnostic {
+                line: 3,
+                message: "HTML is not supported".into(),
+            }],
+            diagnostics,
+        );
+        match &sections[1] {
+            Section::Paragraph { text, .. } => assert_eq!(
+                "[HTML is not supported]",
+                text.lines[0].0[0].content,
+            ),
+            _ => panic!("expected a paragraph"),
+        }
+    }
+
+    #[test]
+    fn unsupported_nodes() {
+        let configuration = Configuration::default();
+        let diagnostics = |value: NodeValue, block: bool| {
+            let arena = comrak::Arena::new();
+            let mut ast = Ast::new(value);
+            ast.start_line = 7;
+            let source = arena.alloc(Node::new(RefCell::new(ast)));
+            let context = Context::new(&configuration);
+            if block {
+                section(source, &mut Vec::new(), Style::default(), &context);
+            } else {
+                inline(source, &mut Vec::new(), Style::default(), &context);
+            }
+            context.diagnostics.into_inner()
+        };
+
+        for (value, block) in [
+            (NodeValue::DescriptionItem(Default::default()), true),
+            (NodeValue::DescriptionTerm, true),
+            (NodeValue::DescriptionDetails, true),
+            (NodeValue::TableRow(false), true),
+            (NodeValue::TableCell, true),
+            (NodeValue::HtmlBlock(Default::default()), true),
+            (NodeValue::Text(Vec::new()), true),
+            (NodeValue::Superscript, false),
+            (NodeValue::TaskItem(false), false),
+            (NodeValue::HtmlInline(b"<br>".to_vec()), false),
+            (NodeValue::Paragraph, false),
+        ] {
+            let description = format!("{:?}", value);
+            let diagnostics = diagnostics(value, block);
+            assert_eq!(1, diagnostics.len(), "{}", description);
+            assert_eq!(7, diagnostics[0].line, "{}", description);
+        }
+    }
+
+    #[test]
+    fn highlights() {
+        assert_eq!(
+            vec![("a ", false), ("b", true), (" c", false)],
+            super::highlights("a ==b== c"),
+        );
+        assert_eq!(
+            vec![("a == b == c", false)],
+            super::highlights("a == b == c")
+        );
+        assert_eq!(
+            vec![("a ==== ", false), ("b", true)],
+            super::highlights("a ==== ==b=="),
+        );
+        assert_eq!(vec![("==b", false)], super::highlights("==b"));
+    }
+
+    #[test]
+    fn highlight_style() {
+        let pages = testing::load_pages("test-resources/highlights.md");
+
+        let (sections, _) =
+            Sections::from_page(&pages[0], &Configuration::default());
+        match &sections[1] {
+            Section::Paragraph { text, .. } => {
+                let spans = &text.lines[0].0;
+                assert_eq!(
+                    vec![
+                        "Some ",
+                        "marked text",
+                        ", but a == b == c is not marked."
+                    ],
+                    spans
+                        .iter()
+                        .map(|span| span.content.as_ref())
+                        .collect::<Vec<_>>(),
+                );
+                assert!(!spans[0]
+                    .style
+                    .add_modifier
+                    .contains(Modifier::REVERSED));
+                assert!(spans[1]
+                    .style
+                    .add_modifier
+                    .contains(Modifier::REVERSED));
+            }
+            _ => panic!("expected a paragraph"),
+        }
+    }
+
+    #[test]
+    fn math() {
+        let pages = testing::load_pages("test-resources/math.md");
+        let text = |configuration: &Configuration| {
+            let (sections, _) = Sections::from_page(&pages[0], configuration);
+            match &sections[1] {
+                Section::Paragraph { text, .. } => text.lines[0]
+                    .0
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>(),
+                _ => panic!("expected a paragraph"),
+            }
+        };
+
+        assert_eq!(
+            "Since α² ≤ ½, it costs $5 or $10.",
+            text(&Configuration {
+                math: true,
+                ..Default::default()
+            }),
+        );
+        assert_eq!(
+            "Since $\\alpha^2 \\le \\frac12$, it costs $5 or $10.",
+            text(&Configuration::default()),
+        );
+    }
+
+    #[test]
+    fn table_cell_formatting() {
+        let pages = testing::load_pages("test-resources/table-formatting.md");
+        let configuration = Configuration::default();
+
+        let (sections, diagnostics) =
+            Sections::from_page(&pages[0], &configuration);
+        assert!(diagnostics.is_empty());
+        match &sections[1] {
+            Section::Table { rows, .. } => {
+                let cell = &rows[0][0];
+                assert_eq!(1, cell.lines.len());
+                let spans = &cell.lines[0].0;
+                assert_eq!(
+                    vec!["bold", " ", "code", " note", "¹"],
+                    spans
+                        .iter()
+                        .map(|span| span.content.as_ref())
+                        .collect::<Vec<_>>(),
+                );
+                assert!(spans[0].style.add_modifier.contains(Modifier::BOLD));
+                assert!(spans[2].style.add_modifier.contains(Modifier::DIM));
+                let cell = &rows[0][1];
+                assert_eq!(1, cell.lines.len());
+                assert!(cell.lines[0].0[0]
+                    .style
+                    .add_modifier
+                    .contains(Modifier::ITALIC));
+            }
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn nested_ordered_lists() {
+        let pages =
+            testing::load_pages("test-resources/nested-ordered-lists.md");
+        let configuration = Configuration::default();
+        let ordinals = |sections: &Sections| {
+            sections
+                .iter()
+                .filter_map(|section| match section {
+                    Section::ListItemOrdered { ordinal, .. } => Some(*ordinal),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let (sections, _) = Sections::from_page(&pages[0], &configuration);
+        match &sections[1] {
+            Section::List { content } => {
+                assert_eq!(vec![3, 4, 5], ordinals(content));
+                match &content[1] {
+                    Section::ListItemOrdered { content, .. } => {
+                        match content.last() {
+                            Some(Section::List { content }) => {
+                                assert_eq!(vec![7, 8], ordinals(content));
+                            }
+                            _ => panic!("expected a nested list"),
+                        }
+                    }
+                    _ => panic!("expected an ordered list item"),
+                }
+            }
+            _ => panic!("expected a list"),
+        }
+    }
+
+    #[test]
+    fn inline_code_style() {
+        let pages = testing::load_pages("test-resources/links.md");
+        let configuration = Configuration {
+            inline_code: StyleConfig {
+                fg: Some(Color::Yellow),
+                bg: Some(Color::DarkGray),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (sections, _) = Sections::from_page(&pages[0], &configuration);
+        match &sections[2] {
+            Section::Paragraph { text, .. } => {
+                let spans = &text.lines[0].0;
+                assert_eq!("code", spans[0].content);
+                assert_eq!(Some(Color::Yellow), spans[0].style.fg);
+                assert_eq!(Some(Color::DarkGray), spans[0].style.bg);
+                assert!(spans[0]
+                    .style
+                    .add_modifier
+                    .contains(Modifier::CROSSED_OUT));
+                assert_ne!(spans[1].style, spans[0].style);
+            }
+            _ => panic!("expected a paragraph"),
+        }
+    }
+
+    #[test]
+    fn link_style() {
+        let pages = testing::load_pages("test-resources/links.md");
+        let configuration = Configuration {
+            link_style: StyleConfig {
+                fg: Some(Color::Green),
+                italic: true,
+                ..Default::default()
+            },
+            link_url_style: StyleConfig {
+                dim: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (sections, _) = Sections::from_page(&pages[0], &configuration);
+        match &sections[1] {
+            Section::Paragraph { text, .. } => {
+                let spans = &text.lines[0].0;
+                assert_eq!(Some(Color::Green), spans[0].style.fg);
+                assert_eq!(
+                    Modifier::CROSSED_OUT | Modifier::BOLD | Modifier::ITALIC,
+                    spans[0].style.add_modifier,
+                );
+                assert_eq!(Some(Color::Green), spans[1].style.fg);
+                assert_eq!(
+                    Modifier::CROSSED_OUT
+                        | Modifier::BOLD
+                        | Modifier::ITALIC
+                        | Modifier::DIM,
+                    spans[1].style.add_modifier,
+                );
+            }
+            _ => panic!("expected a paragraph"),
+        }
+    }
+
+    #[test]
+    fn break_wide() {
+        let pages = testing::load_pages("test-resources/cjk.md");
+        let configuration = Configuration {
+            language: Some("ja".into()),
+            ..Default::default()
+        };
+
+        // Wrapping between wide characters does not change the text
+        let sections = Sections::from_page(&pages[0], &configuration).0;
+        assert!(!sections.text().contains('\u{200b}'));
+        assert!(sections.iter().all(|section| match section {
+            Section::Heading { break_wide, .. }
+            | Section::Paragraph { break_wide, .. } => *break_wide,
+            _ => true,
+        }));
+    }
+
+    #[test]
+    fn nested_link() {
+        let pages = testing::load_pages("test-resources/links.md");
+
+        let (sections, _) =
+            Sections::from_page(&pages[0], &Configuration::default());
+        match &sections[1] {
+            Section::Paragraph { text, .. } => {
+                let spans = &text.lines[0].0;
+                assert_eq!(
+                    vec!["text", " <https://example.com>"],
+                    spans
+                        .iter()
+                        .map(|span| span.content.as_ref())
+                        .collect::<Vec<_>>(),
+                );
+                for span in spans {
+                    assert_eq!(
+                        Modifier::CROSSED_OUT
+                            | Modifier::BOLD
+                            | Modifier::UNDERLINED,
+                        span.style.add_modifier,
+                    );
+                    assert_eq!(Some(Color::Blue), span.style.fg);
+                }
+            }
+            _ => panic!("expected a paragraph"),
+        }
+        match &sections[2] {
+            Section::Paragraph { text, .. } => {
+                assert!(text.lines[0].0.iter().all(|span| span
+                    .style
+                    .add_modifier
+                    .contains(Modifier::CROSSED_OUT)));
+            }
+            _ => panic!("expected a paragraph"),
+        }
+    }
+
+    #[test]
+    fn line_breaks() {
+        let pages = testing::load_pages("test-resources/line-breaks.md");
+
+        let (sections, _) =
+            Sections::from_page(&pages[0], &Configuration::default());
+        match &sections[1] {
+            Section::Paragraph { text, .. } => assert_eq!(
+                vec!["The first line,", "and the second line."],
+                text.lines
+                    .iter()
+                    .map(|line| line
+                        .0
+                        .iter()
+                        .map(|span| span.content.clone())
+                        .collect::<String>())
+                    .collect::<Vec<_>>(),
+            ),
+            _ => panic!("expected a paragraph"),
+        }
+        assert_eq!(2, sections[1].height(40));
+    }
+
+    #[test]
+    fn code_caption() {
+        let pages = testing::load_pages("test-resources/code.md");
+
+        let (sections, _) =
+            Sections::from_page(&pages[0], &Configuration::default());
+        assert_eq!(
+            vec![Some("rust"), None, None, None],
+            sections
+                .iter()
+                .filter_map(|section| match section {
+                    Section::Code { language, .. } => Some(language.as_deref()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn text() {
+        let pages = testing::load_pages("test-resources/text.md");
+        let (sections, _) =
+            Sections::from_page(&pages[0], &Configuration::default());
+
+        assert_eq!(
+            "# A styled heading\n\
+            \n\
+            *   One\n\
+            *   Two\n\
+            \n    1.  Nested\n\
+            \n    let x = 1;\n\
+            \n\
+            Name | Value\n\
+            x | 1",
+            sections.text(),
+        );
+    }
+
+    #[test]
+    fn word_count() {
+        let pages = testing::load_pages("test-resources/export.md");
+        let pages = pages
+            .iter()
+            .map(|page| Sections::from_page(page, &Configuration::default()).0)
+            .collect::<Vec<_>>();
+
+        assert_eq!(18, pages[0].word_count(false));
+        assert_eq!(18, pages[0].word_count(true));
+        assert_eq!(2, pages[1].word_count(false));
+        assert_eq!(10, pages[1].word_count(true));
+    }
+
+    #[test]
+    fn reading_time() {
+        assert_eq!(Duration::ZERO, super::reading_time(0, 200));
+        assert_eq!(Duration::from_secs(15), super::reading_time(50, 200));
+        assert_eq!(Duration::from_secs(60), super::reading_time(200, 200));
+        assert_eq!(Duration::from_secs(1), super::reading_time(1, 200));
+    }
+
+    #[test]
+    fn code_highlights() {
+        let pages = testing::load_pages("test-resources/code.md");
+        let configuration = Configuration {
+            default_code_language: Some("rust".into()),
+            ..Default::default()
+        };
+
+        // Code blocks highlighted in parallel are identical to those
+        // highlighted while converting, including nested code blocks
+        let highlights = Highlights::from_pages(
+            pages.iter().map(|page| (page, &configuration)),
+        );
+        assert_eq!(6, highlights.len());
+        for page in pages {
+            assert_eq!(
+                format!("{:?}", Sections::from_page(page, &configuration).0),
+                format!(
+                    "{:?}",
+                    Sections::from_page_with_highlights(
+                        page,
+                        &configuration,
+                        &highlights,
+                    )
+                    .0,
+                ),
+            );
+        }
+
+        // Page configuration blocks are not highlighted
+        let pages = testing::load_pages("test-resources/page-configuration.md");
+        assert!(Highlights::from_pages(
+            pages.iter().map(|page| (page, &configuration)),
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn code_source() {
+        let pages = testing::load_pages("test-resources/code.md");
+        let code = |page| {
+            Sections::from_page(&pages[page], &Configuration::default())
+                .0
+                .code()
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec![
+                "fn main() {}\n",
+                "Plain text\n",
+                "No language\n",
+                "fn main() {}\n",
+            ],
+            code(0),
+        );
+        assert_eq!(vec!["echo quoted\n", "echo listed\n"], code(1));
+    }
+
+    #[test]
+    fn default_code_language() {
+        let pages = testing::load_pages("test-resources/code.md");
+        let code = |configuration: &Configuration| {
+            Sections::from_page(&pages[0], configuration)
+                .0
+                .iter()
+                .filter_map(|section| match section {
+                    Section::Code { text, .. } => Some(text.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let plain = code(&Configuration::default());
+        assert_ne!(plain[0], plain[3]);
+
+        let highlighted = code(&Configuration {
+            default_code_language: Some("rust".into()),
+            ..Default::default()
+        });
+        assert_eq!(highlighted[0], highlighted[3]);
+        assert_eq!(highlighted[1], plain[1]);
+    }
+
+    #[test]
+    fn find_syntax() {
+        let syntax_set = syntax_set(None).unwrap();
+        for (token, name) in [
+            ("rust", "Rust"),
+            ("RS", "Rust"),
+            ("Rust", "Rust"),
+            ("main.rs", "Rust"),
+            ("Makefile", "Makefile"),
+            ("build.sh", "Bourne Again Shell (bash)"),
+            ("unknown", "Plain Text"),
+        ] {
+            assert_eq!(
+                name,
+                super::find_syntax(&syntax_set, token).name,
+                "{}",
+                token,
+            );
+        }
+    }
+
+    #[test]
+    fn syntax_dir() {
+        assert!(syntax_set(Some(Path::new("test-resources/missing"))).is_err());
+
+        let pages = testing::load_pages("test-resources/syntax.md");
+        let configuration = Configuration {
+            syntax_dir: Some("test-resources/syntaxes".into()),
+            ..Default::default()
+        };
+        assert!(syntax_set(configuration.syntax_dir.as_deref()).is_ok());
+
+        let (sections, _) = Sections::from_page(&pages[0], &configuration);
+        match &sections[1] {
+            Section::Code { text, .. } => {
+                let spans = &text.lines[0].0;
+                assert_eq!(
+                    vec!["quiet ", "LOUD"],
+                    spans
+                        .iter()
+                        .map(|span| span.content.clone())
+                        .collect::<Vec<_>>(),
+                );
+                assert_ne!(spans[0].style, spans[1].style);
+            }
+            _ => panic!("expected code"),
+        }
+    }
+
+    #[test]
+    fn columns() {
+        let pages = testing::load_pages("test-resources/columns.md");
+
+        let (sections, _) =
+            Sections::from_page(&pages[0], &Configuration::default());
+        assert_eq!(3, sections.len());
+        match &sections[1] {
+            Section::Columns { columns } => assert_eq!(
+                vec!["Left", "Right"],
+                columns
+                    .iter()
+                    .map(|column| column.text())
+                    .collect::<Vec<_>>(),
+            ),
+            _ => panic!("expected columns"),
+        }
+        assert_eq!(vec!["A note"], pages[0].notes());
+    }
+
+    #[test]
+    fn inline_comment() {
+        let arena = comrak::Arena::new();
+        let presentation =
+            presentation::load(&arena, "test-resources/slides.md").unwrap();
+        let pages = presentation
+            .pages(PageBreakCondition::Comment {
+                marker: "slide".into(),
+            })
+            .collect::<Vec<_>>();
+
+        let sections =
+            Sections::from_page(&pages[1], &Configuration::default()).0;
+        match &sections[0] {
+            Section::Paragraph { text, .. } => assert_eq!(
+                "The second slide, with an  comment.",
+                text.lines[0]
+                    .0
+                    .iter()
+                    .map(|span| span.content.clone())
+                    .collect::<String>(),
+            ),
+            _ => panic!("expected a paragraph"),
+        }
+    }
+
+    #[test]
+    fn default_style() {
+        let pages = testing::load_pages("test-resources/presentation.md");
+
+        let configuration = Configuration {
+            text_color: Color::Green,
+            text_background: Color::Black,
+            ..Default::default()
+        };
+        let sections = Sections::from_page(&pages[0], &configuration).0;
+        match &sections[1] {
+            Section::Paragraph { text, .. } => assert_eq!(
+                Style::default().fg(Color::Green).bg(Color::Black),
+                text.lines[0].0[0].style,
+            ),
+            _ => panic!("expected a paragraph"),
+        }
+        match &sections[0] {
+            Section::Heading { text, .. } => assert_eq!(
+                Style::default()
+                    .fg(Color::Green)
+                    .bg(Color::Black)
+                    .add_modifier(Modifier::UNDERLINED),
+                text.0[0].style,
+            ),
+            _ => panic!("expected a heading"),
+        }
+    }
+
+    #[test]
+    fn heading_style() {
+        let pages = testing::load_pages("test-resources/headings.md");
+
+        let mut configuration = Configuration::default();
+        configuration.headings.h1.style = toml::from_str(
+            "fg = \"Red\"\n\
+            bold = true",
+        )
+        .unwrap();
+        let sections = Sections::from_page(&pages[0], &configuration).0;
+        let styles = sections
+            .iter()
+            .take(2)
+            .map(|section| match section {
+                Section::Heading { text, .. } => text.0[0].style,
+                _ => panic!("expected a heading"),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Style::default()
+                    .fg(Color::Red)
+                    .bg(Color::Reset)
+                    .add_modifier(Modifier::BOLD),
+                Style::default()
+                    .fg(Color::Reset)
+                    .bg(Color::Reset)
+                    .add_modifier(Modifier::UNDERLINED),
+            ],
+            styles,
+        );
     }
 }
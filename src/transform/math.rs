@@ -0,0 +1,424 @@
+//! Approximation of TeX math using Unicode characters.
+
+/// The symbols produced by commands without arguments.
+const SYMBOLS: [(&str, &str); 77] = [
+    ("alpha", "α"),
+    ("beta", "β"),
+    ("gamma", "γ"),
+    ("delta", "δ"),
+    ("epsilon", "ε"),
+    ("varepsilon", "ε"),
+    ("zeta", "ζ"),
+    ("eta", "η"),
+    ("theta", "θ"),
+    ("vartheta", "ϑ"),
+    ("iota", "ι"),
+    ("kappa", "κ"),
+    ("lambda", "λ"),
+    ("mu", "μ"),
+    ("nu", "ν"),
+    ("xi", "ξ"),
+    ("pi", "π"),
+    ("rho", "ρ"),
+    ("sigma", "σ"),
+    ("tau", "τ"),
+    ("upsilon", "υ"),
+    ("phi", "φ"),
+    ("varphi", "φ"),
+    ("chi", "χ"),
+    ("psi", "ψ"),
+    ("omega", "ω"),
+    ("Gamma", "Γ"),
+    ("Delta", "Δ"),
+    ("Theta", "Θ"),
+    ("Lambda", "Λ"),
+    ("Xi", "Ξ"),
+    ("Pi", "Π"),
+    ("Sigma", "Σ"),
+    ("Upsilon", "Υ"),
+    ("Phi", "Φ"),
+    ("Psi", "Ψ"),
+    ("Omega", "Ω"),
+    ("sum", "∑"),
+    ("prod", "∏"),
+    ("int", "∫"),
+    ("oint", "∮"),
+    ("partial", "∂"),
+    ("nabla", "∇"),
+    ("infty", "∞"),
+    ("pm", "±"),
+    ("mp", "∓"),
+    ("times", "×"),
+    ("div", "÷"),
+    ("cdot", "·"),
+    ("circ", "∘"),
+    ("le", "≤"),
+    ("leq", "≤"),
+    ("ge", "≥"),
+    ("geq", "≥"),
+    ("ne", "≠"),
+    ("neq", "≠"),
+    ("approx", "≈"),
+    ("equiv", "≡"),
+    ("sim", "∼"),
+    ("propto", "∝"),
+    ("to", "→"),
+    ("rightarrow", "→"),
+    ("leftarrow", "←"),
+    ("Rightarrow", "⇒"),
+    ("Leftrightarrow", "⇔"),
+    ("mapsto", "↦"),
+    ("in", "∈"),
+    ("notin", "∉"),
+    ("subset", "⊂"),
+    ("subseteq", "⊆"),
+    ("cup", "∪"),
+    ("cap", "∩"),
+    ("emptyset", "∅"),
+    ("forall", "∀"),
+    ("exists", "∃"),
+    ("neg", "¬"),
+    ("ldots", "…"),
+];
+
+/// The superscript forms of characters.
+const SUPERSCRIPTS: [(char, char); 39] = [
+    ('0', '⁰'),
+    ('1', '¹'),
+    ('2', '²'),
+    ('3', '³'),
+    ('4', '⁴'),
+    ('5', '⁵'),
+    ('6', '⁶'),
+    ('7', '⁷'),
+    ('8', '⁸'),
+    ('9', '⁹'),
+    ('+', '⁺'),
+    ('-', '⁻'),
+    ('=', '⁼'),
+    ('(', '⁽'),
+    (')', '⁾'),
+    ('a', 'ᵃ'),
+    ('b', 'ᵇ'),
+    ('c', 'ᶜ'),
+    ('d', 'ᵈ'),
+    ('e', 'ᵉ'),
+    ('f', 'ᶠ'),
+    ('g', 'ᵍ'),
+    ('h', 'ʰ'),
+    ('i', 'ⁱ'),
+    ('j', 'ʲ'),
+    ('k', 'ᵏ'),
+    ('l', 'ˡ'),
+    ('m', 'ᵐ'),
+    ('n', 'ⁿ'),
+    ('o', 'ᵒ'),
+    ('p', 'ᵖ'),
+    ('r', 'ʳ'),
+    ('s', 'ˢ'),
+    ('t', 'ᵗ'),
+    ('u', 'ᵘ'),
+    ('v', 'ᵛ'),
+    ('w', 'ʷ'),
+    ('x', 'ˣ'),
+    ('y', 'ʸ'),
+];
+
+/// The subscript forms of characters.
+const SUBSCRIPTS: [(char, char); 32] = [
+    ('0', '₀'),
+    ('1', '₁'),
+    ('2', '₂'),
+    ('3', '₃'),
+    ('4', '₄'),
+    ('5', '₅'),
+    ('6', '₆'),
+    ('7', '₇'),
+    ('8', '₈'),
+    ('9', '₉'),
+    ('+', '₊'),
+    ('-', '₋'),
+    ('=', '₌'),
+    ('(', '₍'),
+    (')', '₎'),
+    ('a', 'ₐ'),
+    ('e', 'ₑ'),
+    ('h', 'ₕ'),
+    ('i', 'ᵢ'),
+    ('j', 'ⱼ'),
+    ('k', 'ₖ'),
+    ('l', 'ₗ'),
+    ('m', 'ₘ'),
+    ('n', 'ₙ'),
+    ('o', 'ₒ'),
+    ('p', 'ₚ'),
+    ('r', 'ᵣ'),
+    ('s', 'ₛ'),
+    ('t', 'ₜ'),
+    ('u', 'ᵤ'),
+    ('v', 'ᵥ'),
+    ('x', 'ₓ'),
+];
+
+/// The fractions with a character of their own.
+const FRACTIONS: [(&str, &str, &str); 11] = [
+    ("1", "2", "½"),
+    ("1", "3", "⅓"),
+    ("2", "3", "⅔"),
+    ("1", "4", "¼"),
+    ("3", "4", "¾"),
+    ("1", "5", "⅕"),
+    ("1", "6", "⅙"),
+    ("1", "8", "⅛"),
+    ("3", "8", "⅜"),
+    ("5", "8", "⅝"),
+    ("7", "8", "⅞"),
+];
+
+/// The marker delimiting inline math.
+const MARKER: char = '$';
+
+/// Splits text into plain text and inline math.
+///
+/// The second value of every item is whether the text is math, in which case
+/// the markers have been removed. As with the highlight markers, the math
+/// must not start or end with whitespace, and a closing marker followed by a
+/// digit does not end the math, so that amounts such as `$5 or $10` are left
+/// as they are.
+///
+/// # Arguments
+/// *  `text` - The text to split.
+pub fn split(text: &str) -> Vec<(&str, bool)> {
+    let mut result = Vec::new();
+    let (mut start, mut position) = (0, 0);
+    while let Some(open) = text[position..].find(MARKER) {
+        let open = position + open;
+        let content = open + MARKER.len_utf8();
+        let close = text[content..]
+            .match_indices(MARKER)
+            .map(|(close, _)| content + close)
+            .find(|&close| {
+                !text[close + MARKER.len_utf8()..]
+                    .starts_with(|c: char| c.is_ascii_digit())
+            });
+        match close {
+            Some(close)
+                if close > content
+                    && !text[content..close]
+                        .starts_with(char::is_whitespace)
+                    && !text[content..close].ends_with(char::is_whitespace) =>
+            {
+                result.push((&text[start..open], false));
+                result.push((&text[content..close], true));
+                start = close + MARKER.len_utf8();
+                position = start;
+            }
+            _ => position = content,
+        }
+    }
+    result.push((&text[start..], false));
+    result.retain(|(text, _)| !text.is_empty());
+    result
+}
+
+/// Converts TeX math to an approximation using Unicode characters.
+///
+/// Greek letters, common operators and relations, superscripts, subscripts,
+/// fractions and square roots are converted; unknown commands, and scripts
+/// containing characters without a superscript or subscript form, are kept
+/// as they are written.
+///
+/// # Arguments
+/// *  `tex` - The TeX source, without the surrounding markers.
+pub fn to_unicode(tex: &str) -> String {
+    let source = tex.chars().collect::<Vec<_>>();
+    let mut position = 0;
+    group(&source, &mut position, false)
+}
+
+/// Converts tokens until the end of the source or of the current group.
+///
+/// The closing brace of the group is consumed.
+///
+/// # Arguments
+/// *  `source` - The TeX source.
+/// *  `position` - The position of the next token.
+/// *  `nested` - Whether the tokens are in a group, rather than at the top
+///    level where a closing brace is kept as it is.
+fn group(source: &[char], position: &mut usize, nested: bool) -> String {
+    let mut result = String::new();
+    while let Some(&c) = source.get(*position) {
+        *position += 1;
+        match c {
+            '}' if nested => break,
+            '{' => result.push_str(&group(source, position, true)),
+            '\\' => result.push_str(&command(source, position)),
+            '^' => result.push_str(&script(
+                '^',
+                &argument(source, position),
+                &SUPERSCRIPTS,
+            )),
+            '_' => result.push_str(&script(
+                '_',
+                &argument(source, position),
+                &SUBSCRIPTS,
+            )),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Converts the argument of a command or script, which is either a group or
+/// a single token.
+///
+/// # Arguments
+/// *  `source` - The TeX source.
+/// *  `position` - The position of the argument.
+fn argument(source: &[char], position: &mut usize) -> String {
+    let c = match source.get(*position) {
+        Some(&c) => c,
+        None => return String::new(),
+    };
+    *position += 1;
+    match c {
+        '{' => group(source, position, true),
+        '\\' => command(source, position),
+        c => c.to_string(),
+    }
+}
+
+/// Converts a command, whose leading backslash has been consumed.
+///
+/// # Arguments
+/// *  `source` - The TeX source.
+/// *  `position` - The position following the backslash.
+fn command(source: &[char], position: &mut usize) -> String {
+    let start = *position;
+    while source
+        .get(*position)
+        .is_some_and(|c| c.is_ascii_alphabetic())
+    {
+        *position += 1;
+    }
+    let name = source[start..*position].iter().collect::<String>();
+    match name.as_str() {
+        // A single character command, such as \{ or a space
+        "" => match source.get(*position) {
+            Some(&c) => {
+                *position += 1;
+                match c {
+                    ',' | ':' | ';' | ' ' => " ".into(),
+                    '!' => String::new(),
+                    c => c.to_string(),
+                }
+            }
+            None => "\\".into(),
+        },
+        "frac" => {
+            let numerator = argument(source, position);
+            let denominator = argument(source, position);
+            fraction(&numerator, &denominator)
+        }
+        "sqrt" => format!("√{}", parenthesized(&argument(source, position))),
+        "mathrm" | "mathit" | "mathbf" | "text" | "operatorname" => {
+            argument(source, position)
+        }
+        name => match SYMBOLS.iter().find(|(symbol, _)| *symbol == name) {
+            Some((_, symbol)) => symbol.to_string(),
+            None if source.get(*position) == Some(&'{') => {
+                format!("\\{}{{{}}}", name, argument(source, position))
+            }
+            None => format!("\\{}", name),
+        },
+    }
+}
+
+/// Converts a superscript or subscript.
+///
+/// If any character lacks a script form, the text is kept after the marker.
+///
+/// # Arguments
+/// *  `marker` - The marker introducing the script.
+/// *  `text` - The converted text of the script.
+/// *  `forms` - The script forms of characters.
+fn script(marker: char, text: &str, forms: &[(char, char)]) -> String {
+    let text = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    text.chars()
+        .map(|c| {
+            forms
+                .iter()
+                .find(|(plain, _)| *plain == c)
+                .map(|(_, form)| *form)
+        })
+        .collect::<Option<String>>()
+        .filter(|converted| !converted.is_empty())
+        .unwrap_or_else(|| format!("{}{}", marker, parenthesized(&text)))
+}
+
+/// Converts a fraction.
+///
+/// # Arguments
+/// *  `numerator` - The converted numerator.
+/// *  `denominator` - The converted denominator.
+fn fraction(numerator: &str, denominator: &str) -> String {
+    FRACTIONS
+        .iter()
+        .find(|(n, d, _)| *n == numerator && *d == denominator)
+        .map(|(_, _, fraction)| fraction.to_string())
+        .unwrap_or_else(|| {
+            format!(
+                "{}/{}",
+                parenthesized(numerator),
+                parenthesized(denominator),
+            )
+        })
+}
+
+/// Surrounds text longer than one character with parentheses.
+///
+/// # Arguments
+/// *  `text` - The text.
+fn parenthesized(text: &str) -> String {
+    if text.chars().count() > 1 {
+        format!("({})", text)
+    } else {
+        text.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn split() {
+        assert_eq!(
+            vec![("a ", false), ("x^2", true), (" b", false)],
+            super::split("a $x^2$ b"),
+        );
+        assert_eq!(
+            vec![("costs $5 or $10", false)],
+            super::split("costs $5 or $10"),
+        );
+        assert_eq!(vec![("$ a $", false)], super::split("$ a $"));
+        assert_eq!(vec![("a", true), ("$", false)], super::split("$a$$"));
+    }
+
+    #[test]
+    fn to_unicode() {
+        assert_eq!("α²", super::to_unicode("\\alpha^2"));
+        assert_eq!("xᵢ₊₁ = xᵢ²", super::to_unicode("x_{i+1} = x_i^2"));
+        assert_eq!("∑ᵢ aᵢ ≤ ∞", super::to_unicode("\\sum_i a_i \\le \\infty"));
+        assert_eq!(
+            "½ + (a+b)/c",
+            super::to_unicode("\\frac12 + \\frac{a+b}{c}")
+        );
+        assert_eq!("√(x+1)", super::to_unicode("\\sqrt{x+1}"));
+        assert_eq!("e^(iπ)", super::to_unicode("e^{i\\pi}"));
+        assert_eq!("x_q", super::to_unicode("x_q"));
+        assert_eq!("\\unknown{x}", super::to_unicode("\\unknown{x}"));
+        assert_eq!("a}b", super::to_unicode("a}b"));
+    }
+}
@@ -1,86 +1,615 @@
-use std::io;
+use std::fs;
+use std::io::{self, Write};
+use std::mem;
+use std::panic;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
+use crossterm::cursor;
 use crossterm::event::{self, Event, KeyCode};
 use crossterm::execute;
 use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
-use tui::backend::CrosstermBackend;
+use tui::backend::{Backend, CrosstermBackend};
 
-use tui::layout::{Alignment, Constraint, Direction, Layout};
-use tui::style::{Color, Style};
-use tui::widgets::{Block, BorderType, Borders, Gauge};
+use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, BorderType, Borders, Clear, Gauge, Paragraph, Wrap};
 use tui::Frame;
+use unicode_width::UnicodeWidthStr;
 
-use crate::configuration::Configuration;
-use crate::widget::PageWidget;
+use crate::clipboard;
+use crate::commands::Background;
+use crate::configuration::{
+    interpolate, ColorDepth, Configuration, CounterPosition, PageCounter,
+    PageLayout, Transition,
+};
+use crate::image;
+use crate::transform;
+use crate::widget::{
+    BackgroundWidget, MonochromeWidget, PageWidget, PaletteWidget,
+    TransitionWidget,
+};
+
+/// The interval at which a watched presentation document is checked for
+/// modifications.
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The interval at which commands running in the background are checked
+/// for completion.
+const COMMAND_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The reason the UI main loop returned.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Exit {
+    /// The user exited.
+    Quit {
+        /// The index of the page shown, before automatic pagination.
+        page: usize,
+    },
+
+    /// The watched presentation document was modified, or the user asked to
+    /// reload it.
+    Reload {
+        /// The index of the page shown, before automatic pagination.
+        page: usize,
+    },
+}
 
 /// Runs the UI main loop.
 ///
-/// This function will not return until the user exits.
+/// This function will not return until the user exits or reloads the
+/// presentation document, or, when watching the document, until it is
+/// modified.
 ///
 /// # Arguments
+/// *  `terminal` - The terminal.
 /// *  `configuraiton` - The application configuration.
+/// *  `path` - The path to the presentation document.
 /// *  `pages` - The pages of the presentation.
+/// *  `watch` - Whether to watch the presentation document.
+/// *  `page` - The index of the page to show first.
 pub fn run(
+    terminal: &mut Terminal,
     configuration: &Configuration,
+    path: &Path,
     pages: Vec<PageWidget>,
-) -> Result<(), String> {
-    let mut terminal = Terminal::new()?;
-    let mut page = 0usize;
+    watch: bool,
+    page: usize,
+) -> Result<Exit, String> {
+    let directory = path.parent().unwrap_or(path);
+    let watched = watch.then(|| modified(path));
+    let mut state = State {
+        confirm_quit: configuration.confirm_quit,
+        ..Default::default()
+    };
+    let mut area = Rect::default();
+    let mut origins = Vec::new();
+    let mut widgets = Vec::new();
+    let mut headings = Vec::new();
+    let interval = configuration.autoplay_ms.map(Duration::from_millis);
+    let mut shown = Instant::now();
+    let mut dispatched = None;
+    let mut background = Background::default();
 
     #[allow(unused_must_use)]
     loop {
+        let size = terminal
+            .0
+            .size()
+            .map_err(|e| format!("Failed to read terminal size: {}", e))?;
+        let content = limit_width(
+            content_rect(size, state.presenter, !state.chrome_hidden),
+            configuration.max_content_width,
+        );
+        // Results of commands started for other pages are stale
+        for (_, result) in background
+            .reap()
+            .into_iter()
+            .filter(|&(page, _)| page == state.page)
+        {
+            if let Some(message) = command_message(result) {
+                state.message = Some(message);
+            }
+        }
+
+        if content != area {
+            let origin = origin(&origins, state.page, page, pages.len());
+            area = content;
+            (origins, widgets) = paginate(configuration, &pages, area);
+            headings = outline(&widgets);
+            state.page = origins.iter().position(|&o| o == origin).unwrap_or(0);
+            state.overview = None;
+        }
+
         terminal
             .0
-            .draw(|frame| render(frame, configuration, &pages, page))
+            .draw(|frame| {
+                render(frame, configuration, &widgets, &headings, &state, None)
+            })
             .map(|_| ())
+            .and_then(|_| image::flush(terminal.0.backend_mut(), directory))
             .or_else(|_| terminal.0.clear())
             .map_err(|e| format!("Failed to render TUI: {}", e));
-        if let Event::Key(key) =
-            event::read().map_err(|e| format!("Failed to read event: {}", e))?
-        {
-            match key.code {
-                KeyCode::Left | KeyCode::Backspace => {
-                    if page > 0 {
-                        page -= 1;
+
+        // The output of commands, and any errors, are shown in the footer
+        // once the commands have finished, and command lines in dry-run mode
+        // are printed once the terminal has been restored
+        if dispatched != Some(state.page) {
+            match configuration.commands.dispatch(
+                &mut background,
+                path,
+                state.page,
+                widgets.len(),
+            ) {
+                Ok(Some(command_line)) => terminal.defer(command_line),
+                Ok(None) => {}
+                Err(e) => state.message = Some(e),
+            }
+            dispatched = Some(state.page);
+        }
+
+        // Wait for an event, unless it is time to advance automatically, to
+        // check the watched document or to reap background commands
+        let timeout = interval
+            .filter(|_| !state.paused)
+            .map(|interval| interval.saturating_sub(shown.elapsed()))
+            .into_iter()
+            .chain(watch.then_some(WATCH_INTERVAL))
+            .chain((!background.is_empty()).then_some(COMMAND_INTERVAL))
+            .min();
+        let event = match timeout {
+            Some(timeout) => event::poll(timeout)
+                .and_then(|ready| ready.then(event::read).transpose()),
+            None => event::read().map(Some),
+        }
+        .map_err(|e| format!("Failed to read event: {}", e))?;
+
+        let previous = (state.page, state.paused);
+        match event {
+            Some(Event::Key(key)) => {
+                if key.code == KeyCode::Char('y') && state.overview.is_none() {
+                    if let Some(code) = widgets[state.page].code().first() {
+                        clipboard::copy(terminal.0.backend_mut(), code);
                     }
                 }
-                KeyCode::Right | KeyCode::Enter => {
-                    if page < pages.len() - 1 {
-                        page += 1;
-                    }
+                if key.code == KeyCode::Char('r')
+                    && state.overview.is_none()
+                    && state.outline.is_none()
+                {
+                    return Ok(Exit::Reload {
+                        page: origins.get(state.page).cloned().unwrap_or(0),
+                    });
+                }
+                let running = if state.outline.is_some() {
+                    state.handle_outline(key.code, &headings)
+                } else if key.code == KeyCode::Char('o')
+                    && state.overview.is_none()
+                {
+                    state.open_outline(&headings);
+                    true
+                } else {
+                    state.handle(key.code, widgets.len())
+                };
+                if !running {
+                    return Ok(Exit::Quit {
+                        page: origins.get(state.page).cloned().unwrap_or(0),
+                    });
+                }
+                state.scroll = state.scroll.min(
+                    widgets[state.page].max_scroll(area.width, area.height),
+                );
+            }
+            Some(_) => {}
+            None => {
+                if watched.is_some_and(|watched| watched != modified(path)) {
+                    return Ok(Exit::Reload {
+                        page: origins.get(state.page).cloned().unwrap_or(0),
+                    });
+                }
+                if should_advance(shown.elapsed(), interval, state.paused) {
+                    state.advance(widgets.len());
                 }
-                KeyCode::Char('q') => break,
-                _ => continue,
             }
         }
+        if (state.page, state.paused) != previous {
+            shown = Instant::now();
+        }
+        if state.page != previous.0 {
+            state.message = None;
+        }
+        if state.page != previous.0
+            && configuration.transition != Transition::None
+        {
+            transition(
+                terminal,
+                configuration,
+                &widgets,
+                &headings,
+                &state,
+                previous.0,
+            )?;
+        }
+    }
+}
+
+/// The message to show in the footer for the result of a command.
+///
+/// This is the last line of the output of a command, or the error if it
+/// failed; commands without output give no message.
+///
+/// # Arguments
+/// *  `result` - The result of the command.
+fn command_message(result: Result<String, String>) -> Option<String> {
+    match result {
+        Ok(output) => output.lines().last().map(String::from),
+        Err(e) => Some(e),
+    }
+}
+
+/// The index of the page, before automatic pagination, to show after
+/// paginating for a new area.
+///
+/// This is the page shown before, or the page to show first if none has been
+/// shown yet, clamped to the number of pages, since a reloaded document may
+/// have fewer pages.
+///
+/// # Arguments
+/// *  `origins` - The index of the page, before automatic pagination, of
+///    every page shown before.
+/// *  `shown` - The index of the page shown before.
+/// *  `page` - The index of the page to show first.
+/// *  `count` - The number of pages.
+fn origin(origins: &[usize], shown: usize, page: usize, count: usize) -> usize {
+    origins
+        .get(shown)
+        .cloned()
+        .unwrap_or(page)
+        .min(count.saturating_sub(1))
+}
+
+/// The modification time of a file.
+///
+/// # Arguments
+/// *  `path` - The path to the file.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Shows the transition to the current page.
+///
+/// The transition is interrupted as soon as an event is available, which is
+/// then handled by the main loop.
+///
+/// # Arguments
+/// *  `terminal` - The terminal.
+/// *  `configuration` - The application configuration.
+/// *  `widgets` - The page widgets.
+/// *  `outline` - The entries of the outline of the page widgets.
+/// *  `state` - The state after changing page.
+/// *  `previous` - The index of the previous page.
+fn transition(
+    terminal: &mut Terminal,
+    configuration: &Configuration,
+    widgets: &[PageWidget<'_>],
+    outline: &[OutlineEntry],
+    state: &State,
+    previous: usize,
+) -> Result<(), String> {
+    for frame in 1..TransitionWidget::FRAMES {
+        if event::poll(TransitionWidget::FRAME_DURATION)
+            .map_err(|e| format!("Failed to read event: {}", e))?
+        {
+            break;
+        }
+        terminal
+            .0
+            .draw(|f| {
+                render(
+                    f,
+                    configuration,
+                    widgets,
+                    outline,
+                    state,
+                    Some((previous, frame)),
+                )
+            })
+            .map_err(|e| format!("Failed to render TUI: {}", e))?;
+
+        // Images are shown only once the transition is complete
+        image::discard();
     }
 
     Ok(())
 }
 
-fn render(
-    frame: &mut Frame<CrosstermBackend<io::Stdout>>,
+/// Splits pages into the pages actually displayed.
+///
+/// If automatic pagination is enabled, pages too tall for the area are split
+/// into several pages. This function returns the index of the source page for
+/// every displayed page, and the displayed pages.
+///
+/// # Arguments
+/// *  `configuration` - The application configuration.
+/// *  `pages` - The pages of the presentation.
+/// *  `area` - The area available for page content.
+fn paginate<'a>(
     configuration: &Configuration,
-    widgets: &Vec<PageWidget<'_>>,
+    pages: &[PageWidget<'a>],
+    area: Rect,
+) -> (Vec<usize>, Vec<PageWidget<'a>>) {
+    pages
+        .iter()
+        .enumerate()
+        .flat_map(|(i, page)| {
+            if configuration.auto_paginate {
+                page.paginate(area.width, area.height)
+            } else {
+                vec![page.clone()]
+            }
+            .into_iter()
+            .map(move |page| (i, page))
+        })
+        .unzip()
+}
+
+/// The state of the UI.
+#[derive(Default)]
+struct State {
+    /// The index of the current page.
     page: usize,
-) {
+
+    /// The vertical scroll offset of the current page.
+    scroll: u16,
+
+    /// Whether the presenter view is shown.
+    presenter: bool,
+
+    /// The selected page, if the overview is shown.
+    overview: Option<usize>,
+
+    /// Whether automatic advancing is paused.
+    paused: bool,
+
+    /// Whether the presentation window, page counter and progress gauge
+    /// are hidden.
+    chrome_hidden: bool,
+
+    /// Whether quitting requires pressing `q` twice.
+    confirm_quit: bool,
+
+    /// Whether `q` was pressed once, and pressing it again quits.
+    quit_pending: bool,
+
+    /// The selected entry, if the outline is shown.
+    outline: Option<usize>,
+
+    /// The message shown in the footer, from a command run for the current
+    /// page.
+    message: Option<String>,
+}
+
+impl State {
+    /// Updates the state after a key press.
+    ///
+    /// This method returns `false` if the application should exit.
+    ///
+    /// # Arguments
+    /// *  `code` - The key pressed.
+    /// *  `count` - The number of pages.
+    fn handle(&mut self, code: KeyCode, count: usize) -> bool {
+        if let Some(running) = self.handle_quit(code) {
+            return running;
+        }
+
+        let page = self.page;
+        if let Some(selected) = self.overview.as_mut() {
+            let columns = grid_columns(count);
+            match code {
+                KeyCode::Left => *selected = selected.saturating_sub(1),
+                KeyCode::Right if *selected + 1 < count => *selected += 1,
+                KeyCode::Up => *selected = selected.saturating_sub(columns),
+                KeyCode::Down if *selected + columns < count => {
+                    *selected += columns;
+                }
+                KeyCode::Enter => {
+                    self.page = *selected;
+                    self.overview = None;
+                }
+                KeyCode::Tab | KeyCode::Esc => self.overview = None,
+                _ => {}
+            }
+        } else {
+            match code {
+                KeyCode::Left | KeyCode::Backspace => {
+                    self.page = self.page.saturating_sub(1);
+                }
+                KeyCode::Right | KeyCode::Enter if self.page + 1 < count => {
+                    self.page += 1;
+                }
+                KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+                KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+                KeyCode::Tab => self.overview = Some(self.page),
+                KeyCode::Char('p') => self.presenter = !self.presenter,
+                KeyCode::Char(' ') => self.paused = !self.paused,
+                KeyCode::Char('c') => self.chrome_hidden = !self.chrome_hidden,
+                _ => {}
+            }
+        }
+        if self.page != page {
+            self.scroll = 0;
+        }
+
+        true
+    }
+
+    /// Updates the state after a key press while the outline is shown.
+    ///
+    /// This method returns `false` if the application should exit.
+    ///
+    /// # Arguments
+    /// *  `code` - The key pressed.
+    /// *  `outline` - The entries of the outline.
+    fn handle_outline(
+        &mut self,
+        code: KeyCode,
+        outline: &[OutlineEntry],
+    ) -> bool {
+        if let Some(running) = self.handle_quit(code) {
+            return running;
+        }
+
+        if let Some(selected) = self.outline.as_mut() {
+            match code {
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down if *selected + 1 < outline.len() => {
+                    *selected += 1;
+                }
+                KeyCode::Enter => {
+                    if let Some(entry) = outline.get(*selected) {
+                        if entry.page != self.page {
+                            self.page = entry.page;
+                            self.scroll = 0;
+                        }
+                    }
+                    self.outline = None;
+                }
+                KeyCode::Char('o') | KeyCode::Esc => self.outline = None,
+                _ => {}
+            }
+        }
+
+        true
+    }
+
+    /// Shows the outline, selecting the last heading on or before the
+    /// current page.
+    ///
+    /// The outline is not shown if there are no headings.
+    ///
+    /// # Arguments
+    /// *  `outline` - The entries of the outline.
+    fn open_outline(&mut self, outline: &[OutlineEntry]) {
+        self.quit_pending = false;
+        if !outline.is_empty() {
+            self.outline = Some(
+                outline
+                    .iter()
+                    .rposition(|entry| entry.page <= self.page)
+                    .unwrap_or(0),
+            );
+        }
+    }
+
+    /// Handles the key used to quit.
+    ///
+    /// Any other key cancels a pending quit. This method returns `None` if
+    /// the key was not the key used to quit, and otherwise whether the
+    /// application should keep running.
+    ///
+    /// # Arguments
+    /// *  `code` - The key pressed.
+    fn handle_quit(&mut self, code: KeyCode) -> Option<bool> {
+        let quit_pending = mem::take(&mut self.quit_pending);
+        if code == KeyCode::Char('q') {
+            self.quit_pending = self.confirm_quit && !quit_pending;
+            Some(self.quit_pending)
+        } else {
+            None
+        }
+    }
+
+    /// Advances to the next page, or to the first page after the last page.
+    ///
+    /// # Arguments
+    /// *  `count` - The number of pages.
+    fn advance(&mut self, count: usize) {
+        self.page = (self.page + 1) % count.max(1);
+        self.scroll = 0;
+    }
+}
+
+/// Determines whether to advance to the next page automatically.
+///
+/// # Arguments
+/// *  `elapsed` - The time the current page has been shown.
+/// *  `interval` - The time to show every page, if advancing automatically.
+/// *  `paused` - Whether automatic advancing is paused.
+fn should_advance(
+    elapsed: Duration,
+    interval: Option<Duration>,
+    paused: bool,
+) -> bool {
+    !paused && interval.is_some_and(|interval| elapsed >= interval)
+}
+
+/// The smallest area in which page content is rendered.
+const MIN_CONTENT_SIZE: (u16, u16) = (16, 2);
+
+/// The message shown when the terminal is too small.
+const TOO_SMALL: &str = "Terminal too small";
+
+/// The message shown in the footer after pressing `q` once, when quitting
+/// must be confirmed.
+const QUIT_PROMPT: &str = "Press q again to quit";
+
+/// The title of the outline.
+const OUTLINE_TITLE: &str = "Outline";
+
+/// Determines whether a screen is too small to show pages.
+///
+/// # Arguments
+/// *  `size` - The size of the screen.
+fn is_too_small(size: Rect) -> bool {
+    let content = content_rect(size, false, true);
+    content.width < MIN_CONTENT_SIZE.0 || content.height < MIN_CONTENT_SIZE.1
+}
+
+/// Renders the UI.
+///
+/// # Arguments
+/// *  `frame` - The frame to which to render.
+/// *  `configuration` - The application configuration.
+/// *  `widgets` - The page widgets.
+/// *  `outline` - The entries of the outline of the page widgets.
+/// *  `state` - The state of the UI.
+/// *  `transition` - The previous page and the current frame, if a
+///    transition is shown.
+fn render<B>(
+    frame: &mut Frame<B>,
+    configuration: &Configuration,
+    widgets: &[PageWidget<'_>],
+    outline: &[OutlineEntry],
+    state: &State,
+    transition: Option<(usize, u16)>,
+) where
+    B: Backend,
+{
     let size = frame.size();
+    if is_too_small(size) {
+        frame.render_widget(
+            Paragraph::new(TOO_SMALL)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            Rect {
+                y: size.y + size.height / 2,
+                height: size.height - size.height / 2,
+                ..size
+            },
+        );
+        return;
+    }
 
-    let show_progress = widgets.len() > 1;
-    let progress_height = if show_progress { 1 } else { 0 };
+    let title = is_title(widgets, state);
+    let show_progress = widgets.len() > 1 && !title && !state.chrome_hidden;
 
-    // The layout for the presentation and the progress gauge
-    let main_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(0)
-        .constraints(
-            [
-                Constraint::Length(size.height - progress_height),
-                Constraint::Length(progress_height),
-            ]
-            .as_ref(),
-        )
-        .split(size);
+    let (presentation_rect, presenter_rect, progress_rect) =
+        layout(size, show_progress, state.presenter);
 
     // The window containing the presentation and the rectangle for content
     let presentation_window = Block::default()
@@ -88,14 +617,88 @@ fn render(
         .title(configuration.title.as_str())
         .title_alignment(Alignment::Center)
         .border_type(BorderType::Rounded);
-    let content_rect = presentation_window.inner(main_layout[0]);
+    if let Some(presenter_rect) = presenter_rect {
+        render_presenter(frame, widgets, state.page, presenter_rect);
+    }
+    let content_rect = if state.chrome_hidden {
+        presentation_rect
+    } else {
+        presentation_window.inner(presentation_rect)
+    };
 
-    frame.render_widget(presentation_window, main_layout[0]);
-    frame.render_widget(&widgets[page], content_rect);
+    if !state.chrome_hidden {
+        frame.render_widget(presentation_window, presentation_rect);
+    }
+    let current = state.overview.unwrap_or(state.page);
+    let counter = page_counter(
+        &configuration.page_counter,
+        current,
+        widgets.len(),
+        widgets[current].word_count(),
+        configuration.words_per_minute,
+    );
+    let footer = if state.quit_pending {
+        Some((QUIT_PROMPT.to_string(), Alignment::Center))
+    } else if let Some(message) =
+        state.message.as_ref().filter(|_| !state.chrome_hidden)
+    {
+        Some((message.clone(), Alignment::Left))
+    } else {
+        counter.filter(|_| !title && !state.chrome_hidden)
+    };
+    if let Some((footer, alignment)) = footer {
+        frame.render_widget(
+            Paragraph::new(footer).alignment(alignment),
+            Rect {
+                x: presentation_rect.x + 2,
+                y: presentation_rect.bottom() - 1,
+                width: presentation_rect.width.saturating_sub(4),
+                height: 1,
+            },
+        );
+    }
+    let style = match state.overview {
+        Some(_) => configuration.default_style(),
+        None => widgets[state.page].style(),
+    };
+    frame.render_widget(Block::default().style(style), content_rect);
+    if state.overview.is_none() {
+        frame.render_widget(
+            BackgroundWidget(&configuration.background),
+            content_rect,
+        );
+    }
+    let mut page_rect =
+        limit_width(content_rect, configuration.max_content_width);
+    if title {
+        page_rect = center_vertically(
+            page_rect,
+            widgets[state.page].height(page_rect.width),
+        );
+    }
+    if let Some(selected) = state.overview {
+        render_overview(frame, widgets, selected, content_rect);
+    } else if let Some((previous, step)) = transition {
+        frame.render_widget(
+            TransitionWidget::new(
+                &widgets[previous],
+                &widgets[state.page],
+                configuration.transition,
+                previous < state.page,
+                step,
+            ),
+            page_rect,
+        );
+    } else {
+        frame.render_widget(
+            widgets[state.page].scrolled(state.scroll),
+            page_rect,
+        );
+    }
 
     if show_progress {
         let progress = if widgets.len() > 1 {
-            page as f64 / (widgets.len() - 1) as f64
+            state.page as f64 / (widgets.len() - 1) as f64
         } else {
             0.0
         };
@@ -104,16 +707,440 @@ fn render(
             .label("")
             .use_unicode(true)
             .gauge_style(Style::default().fg(Color::Gray).bg(Color::DarkGray));
-        frame.render_widget(progress, main_layout[1]);
+        frame.render_widget(progress, progress_rect);
+    }
+
+    if let Some(selected) = state.outline {
+        render_outline(frame, outline, selected, content_rect);
+    }
+
+    let color_depth = configuration.color_depth.unwrap_or_default();
+    if configuration.monochrome {
+        frame.render_widget(MonochromeWidget, size);
+    } else if color_depth != ColorDepth::TrueColor {
+        frame.render_widget(PaletteWidget(color_depth), size);
+    }
+}
+
+/// The text and alignment of the page counter.
+///
+/// This function returns `None` if the counter is hidden.
+///
+/// # Arguments
+/// *  `page_counter` - The configuration of the page counter.
+/// *  `page` - The index of the current page.
+/// *  `count` - The number of pages.
+/// *  `words` - The number of words on the current page.
+/// *  `words_per_minute` - The reading speed.
+fn page_counter(
+    page_counter: &PageCounter,
+    page: usize,
+    count: usize,
+    words: usize,
+    words_per_minute: u32,
+) -> Option<(String, Alignment)> {
+    let alignment = match page_counter.position {
+        CounterPosition::Left => Alignment::Left,
+        CounterPosition::Center => Alignment::Center,
+        CounterPosition::Right => Alignment::Right,
+        CounterPosition::Hidden => return None,
+    };
+    Some((
+        interpolate(&page_counter.format, |name| match name {
+            "page.current" => Some((page + 1).to_string()),
+            "page.total" => Some(count.to_string()),
+            "page.words" => Some(words.to_string()),
+            "page.reading_time" => {
+                let time = transform::reading_time(words, words_per_minute);
+                Some(format!(
+                    "{}:{:02}",
+                    time.as_secs() / 60,
+                    time.as_secs() % 60,
+                ))
+            }
+            _ => None,
+        }),
+        alignment,
+    ))
+}
+
+/// Determines whether the current page is shown using the title layout.
+///
+/// # Arguments
+/// *  `widgets` - The page widgets.
+/// *  `state` - The state of the UI.
+fn is_title(widgets: &[PageWidget<'_>], state: &State) -> bool {
+    state.overview.is_none()
+        && widgets
+            .get(state.page)
+            .is_some_and(|page| page.page_layout() == PageLayout::Title)
+}
+
+/// Centers content vertically in an area.
+///
+/// Content higher than the area is placed at the top.
+///
+/// # Arguments
+/// *  `area` - The area.
+/// *  `height` - The height of the content.
+fn center_vertically(area: Rect, height: u16) -> Rect {
+    let offset = area.height.saturating_sub(height) / 2;
+    Rect {
+        y: area.y + offset,
+        height: area.height - offset,
+        ..area
+    }
+}
+
+/// Calculates the layout of the screen.
+///
+/// This function returns the area of the presentation window, the area of
+/// the presenter column, if shown, and the area of the progress gauge.
+///
+/// # Arguments
+/// *  `size` - The size of the screen.
+/// *  `show_progress` - Whether the progress gauge is shown.
+/// *  `presenter` - Whether the presenter view is shown.
+fn layout(
+    size: Rect,
+    show_progress: bool,
+    presenter: bool,
+) -> (Rect, Option<Rect>, Rect) {
+    let progress_height = if show_progress { 1 } else { 0 };
+
+    // The layout for the presentation and the progress gauge
+    let main_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(0)
+        .constraints(
+            [
+                Constraint::Length(size.height.saturating_sub(progress_height)),
+                Constraint::Length(progress_height),
+            ]
+            .as_ref(),
+        )
+        .split(size);
+
+    if presenter {
+        let presenter_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [Constraint::Ratio(2, 3), Constraint::Ratio(1, 3)].as_ref(),
+            )
+            .split(main_layout[0]);
+        (
+            presenter_layout[0],
+            Some(presenter_layout[1]),
+            main_layout[1],
+        )
+    } else {
+        (main_layout[0], None, main_layout[1])
+    }
+}
+
+/// Calculates the area available for page content.
+///
+/// The progress gauge is assumed to be shown along with the presentation
+/// window.
+///
+/// # Arguments
+/// *  `size` - The size of the screen.
+/// *  `presenter` - Whether the presenter view is shown.
+/// *  `chrome` - Whether the presentation window is shown.
+fn content_rect(size: Rect, presenter: bool, chrome: bool) -> Rect {
+    if chrome {
+        Block::default()
+            .borders(Borders::ALL)
+            .inner(layout(size, true, presenter).0)
+    } else {
+        layout(size, false, presenter).0
+    }
+}
+
+/// Limits the width of an area, keeping it centered.
+///
+/// # Arguments
+/// *  `area` - The area to limit.
+/// *  `max_width` - The maximum width, if any.
+fn limit_width(area: Rect, max_width: Option<u16>) -> Rect {
+    match max_width {
+        Some(max_width) if max_width < area.width => Rect {
+            x: area.x + (area.width - max_width) / 2,
+            width: max_width,
+            ..area
+        },
+        _ => area,
+    }
+}
+
+/// Renders the presenter column.
+///
+/// The column contains the speaker notes for the current page, and a preview
+/// of the next page.
+///
+/// # Arguments
+/// *  `frame` - The frame to which to render.
+/// *  `widgets` - The page widgets.
+/// *  `page` - The index of the current page.
+/// *  `area` - The area of the presenter column.
+fn render_presenter<B>(
+    frame: &mut Frame<B>,
+    widgets: &[PageWidget<'_>],
+    page: usize,
+    area: Rect,
+) where
+    B: Backend,
+{
+    let presenter_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)].as_ref(),
+        )
+        .split(area);
+
+    // Problems with the page are listed after the notes
+    let diagnostics = widgets[page].diagnostics();
+    let notes_window = Block::default()
+        .borders(Borders::ALL)
+        .title(notes_title(diagnostics.len()))
+        .border_type(BorderType::Rounded);
+    let notes = Paragraph::new(
+        widgets[page]
+            .notes()
+            .iter()
+            .cloned()
+            .chain(diagnostics.iter().map(ToString::to_string))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    )
+    .wrap(Wrap { trim: true });
+    frame.render_widget(notes, notes_window.inner(presenter_layout[0]));
+    frame.render_widget(notes_window, presenter_layout[0]);
+
+    let next_window = Block::default()
+        .borders(Borders::ALL)
+        .title("Next")
+        .border_type(BorderType::Rounded);
+    if let Some(next) = widgets.get(page + 1) {
+        frame.render_widget(next, next_window.inner(presenter_layout[1]));
+    }
+    frame.render_widget(next_window, presenter_layout[1]);
+}
+
+/// The title of the notes in the presenter view.
+///
+/// # Arguments
+/// *  `diagnostics` - The number of problems found on the page.
+fn notes_title(diagnostics: usize) -> String {
+    match diagnostics {
+        0 => "Notes".into(),
+        1 => "Notes (1 problem)".into(),
+        n => format!("Notes ({} problems)", n),
     }
 }
 
-struct Terminal(pub tui::Terminal<CrosstermBackend<io::Stdout>>);
+/// Renders the overview of all pages.
+///
+/// # Arguments
+/// *  `frame` - The frame to which to render.
+/// *  `widgets` - The page widgets.
+/// *  `selected` - The index of the selected page.
+/// *  `area` - The area of the overview.
+fn render_overview<B>(
+    frame: &mut Frame<B>,
+    widgets: &[PageWidget<'_>],
+    selected: usize,
+    area: Rect,
+) where
+    B: Backend,
+{
+    for (i, (widget, cell)) in
+        widgets.iter().zip(grid(widgets.len(), area)).enumerate()
+    {
+        let window = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("{}", i + 1))
+            .border_type(BorderType::Rounded)
+            .border_style(if i == selected {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+        frame.render_widget(widget, window.inner(cell));
+        frame.render_widget(window, cell);
+    }
+}
+
+/// A heading listed in the outline.
+#[derive(Clone, Debug, PartialEq)]
+struct OutlineEntry {
+    /// The index of the page containing the heading.
+    page: usize,
+
+    /// The heading level.
+    level: u8,
+
+    /// The text of the heading.
+    text: String,
+}
+
+/// Lists the headings of all pages, in order.
+///
+/// # Arguments
+/// *  `widgets` - The page widgets.
+fn outline(widgets: &[PageWidget<'_>]) -> Vec<OutlineEntry> {
+    widgets
+        .iter()
+        .enumerate()
+        .flat_map(|(page, widget)| {
+            widget
+                .headings()
+                .into_iter()
+                .map(move |(level, text)| OutlineEntry { page, level, text })
+        })
+        .collect()
+}
+
+/// Renders the outline over the page.
+///
+/// # Arguments
+/// *  `frame` - The frame.
+/// *  `outline` - The entries of the outline.
+/// *  `selected` - The index of the selected entry.
+/// *  `area` - The area over which the outline is centered.
+fn render_outline<B>(
+    frame: &mut Frame<B>,
+    outline: &[OutlineEntry],
+    selected: usize,
+    area: Rect,
+) where
+    B: Backend,
+{
+    // Headings are indented by level, and followed by their page number
+    let lines = outline
+        .iter()
+        .map(|entry| {
+            (
+                format!(
+                    "{}{}",
+                    "  ".repeat(entry.level.saturating_sub(1) as usize),
+                    entry.text,
+                ),
+                (entry.page + 1).to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let width = lines
+        .iter()
+        .map(|(text, page)| text.width() + page.len() + 2)
+        .max()
+        .unwrap_or(0)
+        .max(OUTLINE_TITLE.len()) as u16
+        + 2;
+    let window = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(lines.len() as u16 + 2) / 2,
+        width: width.min(area.width),
+        height: (lines.len() as u16 + 2).min(area.height),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(OUTLINE_TITLE)
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(window);
+    let offset =
+        selected.saturating_sub(inner.height.saturating_sub(1) as usize);
+
+    frame.render_widget(Clear, window);
+    frame.render_widget(block, window);
+    frame.render_widget(
+        Paragraph::new(
+            lines
+                .into_iter()
+                .enumerate()
+                .map(|(i, (text, page))| {
+                    let padding = (inner.width as usize)
+                        .saturating_sub(text.width() + page.len());
+                    Spans::from(Span::styled(
+                        format!("{}{}{}", text, " ".repeat(padding), page),
+                        if i == selected {
+                            Style::default().add_modifier(Modifier::REVERSED)
+                        } else {
+                            Style::default()
+                        },
+                    ))
+                })
+                .collect::<Vec<_>>(),
+        )
+        .scroll((offset as u16, 0)),
+        inner,
+    );
+}
+
+/// The number of columns used for an overview grid.
+///
+/// # Arguments
+/// *  `count` - The number of cells in the grid.
+fn grid_columns(count: usize) -> usize {
+    (1..=count).find(|c| c * c >= count).unwrap_or(1)
+}
+
+/// Lays out cells in a grid filling an area.
+///
+/// The grid is filled row by row, and has as many columns as rows, or one
+/// more column.
+///
+/// # Arguments
+/// *  `count` - The number of cells.
+/// *  `area` - The area to fill.
+fn grid(count: usize, area: Rect) -> Vec<Rect> {
+    let columns = grid_columns(count);
+    let rows = count.div_ceil(columns);
+    let width = area.width / columns as u16;
+    let height = area.height / rows.max(1) as u16;
+    (0..count)
+        .map(|i| Rect {
+            x: area.x + (i % columns) as u16 * width,
+            y: area.y + (i / columns) as u16 * height,
+            width,
+            height,
+        })
+        .collect()
+}
+
+/// Whether the terminal is in raw mode and shows the alternate screen.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// A panic hook.
+type PanicHook = Box<dyn Fn(&panic::PanicHookInfo<'_>) + Send + Sync>;
+
+/// The terminal, in raw mode and showing the alternate screen.
+///
+/// The terminal is restored when this is dropped, or when panicking. Lines
+/// deferred with [`Terminal::defer`] are then written to the error output.
+pub struct Terminal(
+    pub tui::Terminal<CrosstermBackend<io::Stdout>>,
+    Arc<PanicHook>,
+    Vec<String>,
+);
 
 impl Terminal {
     pub fn new() -> Result<Self, String> {
+        // Restore the terminal before reporting panics, and then defer to
+        // the previous hook
+        let previous = Arc::new(panic::take_hook());
+        let hook = previous.clone();
+        panic::set_hook(Box::new(move |info| {
+            let _ = restore(&mut io::stdout());
+            hook(info);
+        }));
+
         crossterm::terminal::enable_raw_mode()
             .map_err(|e| format!("Failed to initialise terminal: {}", e))?;
+        ACTIVE.store(true, Ordering::SeqCst);
 
         let mut stdout = std::io::stdout();
         execute!(stdout, EnterAlternateScreen)
@@ -123,14 +1150,566 @@ impl Terminal {
 
         tui::Terminal::new(backend)
             .map_err(|e| format!("Failed to initialise terminal: {}", e))
-            .map(Self)
+            .map(|terminal| Self(terminal, previous, Vec::new()))
+    }
+
+    /// Writes a line to the error output once the terminal is restored.
+    ///
+    /// Writing to the error output while presenting would corrupt the
+    /// screen.
+    ///
+    /// # Arguments
+    /// *  `line` - The line to write.
+    pub fn defer(&mut self, line: String) {
+        self.2.push(line);
     }
 }
 
 impl Drop for Terminal {
     fn drop(&mut self) {
-        crossterm::terminal::disable_raw_mode().unwrap();
-        execute!(self.0.backend_mut(), LeaveAlternateScreen).unwrap();
+        restore(self.0.backend_mut()).unwrap();
         self.0.show_cursor().unwrap();
+        for line in self.2.drain(..) {
+            eprintln!("{}", line);
+        }
+
+        // The panic hook cannot be changed while panicking
+        if !thread::panicking() {
+            let previous = self.1.clone();
+            panic::set_hook(Box::new(move |info| previous(info)));
+        }
+    }
+}
+
+/// Disables raw mode and leaves the alternate screen.
+///
+/// Only the first call after initialising the terminal has any effect, so
+/// this is safe to call both from the panic hook and when dropping the
+/// terminal. This function returns whether the terminal was restored.
+///
+/// # Arguments
+/// *  `target` - The terminal.
+fn restore<W>(target: &mut W) -> io::Result<bool>
+where
+    W: Write,
+{
+    if ACTIVE.swap(false, Ordering::SeqCst) {
+        crossterm::terminal::disable_raw_mode()?;
+        execute!(target, LeaveAlternateScreen, cursor::Show)?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tui::backend::TestBackend;
+    use tui::buffer::Buffer;
+
+    use super::*;
+    use crate::presentation::{self, Page, PageBreakCondition};
+    use crate::transform::Footnotes;
+
+    /// Loads the pages of a test document.
+    ///
+    /// The arena holding the document and the pages are leaked, so that
+    /// they may outlive this helper.
+    ///
+    /// # Arguments
+    /// *  `path` - The path to the document.
+    fn load_pages(path: &str) -> &'static [Page<'static>] {
+        let arena = Box::leak(Box::new(comrak::Arena::new()));
+        Box::leak(
+            presentation::load(arena, path)
+                .unwrap()
+                .pages(PageBreakCondition::default())
+                .collect::<Box<_>>(),
+        )
+    }
+
+    /// The symbols of the rows of a buffer.
+    ///
+    /// # Arguments
+    /// *  `buffer` - The buffer.
+    fn rows(buffer: &Buffer) -> Vec<String> {
+        (buffer.area.top()..buffer.area.bottom())
+            .map(|y| {
+                (buffer.area.left()..buffer.area.right())
+                    .map(|x| buffer.get(x, y).symbol.as_str())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn grid_layout() {
+        let area = Rect::new(1, 2, 90, 30);
+
+        assert_eq!(vec![Rect::new(1, 2, 90, 30)], grid(1, area));
+        assert_eq!(
+            vec![Rect::new(1, 2, 45, 30), Rect::new(46, 2, 45, 30),],
+            grid(2, area),
+        );
+        assert_eq!(
+            vec![
+                Rect::new(1, 2, 30, 10),
+                Rect::new(31, 2, 30, 10),
+                Rect::new(61, 2, 30, 10),
+                Rect::new(1, 12, 30, 10),
+                Rect::new(31, 12, 30, 10),
+                Rect::new(61, 12, 30, 10),
+                Rect::new(1, 22, 30, 10),
+            ],
+            grid(7, area),
+        );
+    }
+
+    #[test]
+    fn overview_navigation() {
+        let mut state = State::default();
+
+        assert!(state.handle(KeyCode::Tab, 7));
+        assert_eq!(Some(0), state.overview);
+        state.handle(KeyCode::Down, 7);
+        state.handle(KeyCode::Right, 7);
+        assert_eq!(Some(4), state.overview);
+        state.handle(KeyCode::Down, 7);
+        assert_eq!(Some(4), state.overview);
+        state.handle(KeyCode::Enter, 7);
+        assert_eq!(None, state.overview);
+        assert_eq!(4, state.page);
+    }
+
+    #[test]
+    fn scroll_reset() {
+        let mut state = State::default();
+
+        state.handle(KeyCode::Up, 2);
+        assert_eq!(0, state.scroll);
+        state.handle(KeyCode::Down, 2);
+        state.handle(KeyCode::Down, 2);
+        assert_eq!(2, state.scroll);
+        state.handle(KeyCode::Right, 2);
+        assert_eq!(0, state.scroll);
+    }
+
+    #[test]
+    fn reload_clamp() {
+        assert_eq!(2, origin(&[], 0, 2, 5));
+        assert_eq!(4, origin(&[], 0, 7, 5));
+        assert_eq!(0, origin(&[], 0, 7, 0));
+        assert_eq!(1, origin(&[0, 0, 1, 2], 2, 7, 5));
+        assert_eq!(1, origin(&[0, 0, 1, 3], 3, 7, 2));
+    }
+
+    #[test]
+    fn autoplay() {
+        let second = Duration::from_secs(1);
+
+        assert!(!should_advance(second, None, false));
+        assert!(!should_advance(second / 2, Some(second), false));
+        assert!(should_advance(second, Some(second), false));
+        assert!(should_advance(second * 2, Some(second), false));
+        assert!(!should_advance(second * 2, Some(second), true));
+
+        let mut state = State::default();
+        state.handle(KeyCode::Char(' '), 2);
+        assert!(state.paused);
+        state.handle(KeyCode::Char(' '), 2);
+        assert!(!state.paused);
+        state.advance(2);
+        assert_eq!(1, state.page);
+        state.advance(2);
+        assert_eq!(0, state.page);
+    }
+
+    #[test]
+    fn restore_once() {
+        ACTIVE.store(true, Ordering::SeqCst);
+
+        let mut first = Vec::new();
+        assert!(restore(&mut first).unwrap());
+        assert!(!first.is_empty());
+
+        let mut second = Vec::new();
+        assert!(!restore(&mut second).unwrap());
+        assert!(second.is_empty());
+    }
+
+    #[test]
+    fn too_small() {
+        let configuration = Configuration::default();
+        let state = State::default();
+        let text = |width, height| {
+            let mut terminal =
+                tui::Terminal::new(TestBackend::new(width, height)).unwrap();
+            terminal
+                .draw(|frame| {
+                    render(frame, &configuration, &[], &[], &state, None)
+                })
+                .unwrap();
+            rows(terminal.backend().buffer()).concat()
+        };
+
+        assert_eq!("  Te", text(2, 2));
+        assert!(text(40, 3).contains(TOO_SMALL));
+        assert!(!is_too_small(Rect::new(0, 0, 18, 5)));
+        assert!(is_too_small(Rect::new(0, 0, 17, 5)));
+        assert!(is_too_small(Rect::new(0, 0, 18, 4)));
+    }
+
+    #[test]
+    fn max_content_width() {
+        assert_eq!(
+            Rect::new(21, 1, 60, 10),
+            limit_width(Rect::new(1, 1, 100, 10), Some(60)),
+        );
+        assert_eq!(
+            Rect::new(1, 1, 50, 10),
+            limit_width(Rect::new(1, 1, 50, 10), Some(60)),
+        );
+        assert_eq!(
+            Rect::new(1, 1, 100, 10),
+            limit_width(Rect::new(1, 1, 100, 10), None),
+        );
+
+        let configuration = Configuration {
+            max_content_width: Some(20),
+            ..Default::default()
+        };
+        let widgets = [PageWidget::error("centered", &configuration)];
+        let mut terminal = tui::Terminal::new(TestBackend::new(40, 6)).unwrap();
+        terminal
+            .draw(|frame| {
+                render(
+                    frame,
+                    &configuration,
+                    &widgets,
+                    &[],
+                    &State::default(),
+                    None,
+                )
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        assert_eq!("c", buffer.get(10, 1).symbol);
+        assert_eq!(" ", buffer.get(9, 1).symbol);
+    }
+
+    #[test]
+    fn title_layout() {
+        let pages = load_pages("test-resources/slides.md");
+        let configuration = Configuration::default();
+        let footnotes = Footnotes::default();
+        let title = Configuration {
+            layout: PageLayout::Title,
+            ..Default::default()
+        };
+        let widgets = [
+            PageWidget::new(&pages[0], &title, &footnotes),
+            PageWidget::new(&pages[0], &configuration, &footnotes),
+        ];
+
+        let mut state = State::default();
+        assert!(is_title(&widgets, &state));
+        state.overview = Some(0);
+        assert!(!is_title(&widgets, &state));
+        state.overview = None;
+        state.page = 1;
+        assert!(!is_title(&widgets, &state));
+
+        let render = |page: usize| {
+            let state = State {
+                page,
+                ..Default::default()
+            };
+            let mut terminal =
+                tui::Terminal::new(TestBackend::new(30, 12)).unwrap();
+            terminal
+                .draw(|frame| {
+                    render(frame, &configuration, &widgets, &[], &state, None)
+                })
+                .unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            let row = |y: u16| {
+                (1..29)
+                    .map(|x| buffer.get(x, y).symbol.clone())
+                    .collect::<String>()
+            };
+            (row(1), row(11))
+        };
+
+        // The title page is centered, and the progress gauge is hidden
+        let (first, bottom) = render(0);
+        assert_eq!(" ".repeat(28), first);
+        assert_eq!("─".repeat(28), bottom);
+
+        let (first, bottom) = render(1);
+        assert_ne!(" ".repeat(28), first);
+        assert_ne!("─".repeat(28), bottom);
+    }
+
+    #[test]
+    fn page_counter() {
+        let mut configuration = Configuration::default();
+        assert_eq!(
+            Some(("2 / 3".to_string(), Alignment::Right)),
+            super::page_counter(&configuration.page_counter, 1, 3, 0, 200),
+        );
+
+        configuration.page_counter = PageCounter {
+            format: "Slide ${page.current} of ${page.total}${unknown}".into(),
+            position: CounterPosition::Left,
+        };
+        assert_eq!(
+            Some(("Slide 3 of 12${unknown}".to_string(), Alignment::Left)),
+            super::page_counter(&configuration.page_counter, 2, 12, 0, 200),
+        );
+
+        configuration.page_counter.format =
+            "${page.words} words, ${page.reading_time}".into();
+        assert_eq!(
+            Some(("250 words, 1:15".to_string(), Alignment::Left)),
+            super::page_counter(&configuration.page_counter, 2, 12, 250, 200),
+        );
+
+        configuration.page_counter.position = CounterPosition::Hidden;
+        assert_eq!(
+            None,
+            super::page_counter(&configuration.page_counter, 2, 12, 0, 200),
+        );
+
+        let render = |position: CounterPosition| {
+            let configuration = Configuration {
+                page_counter: PageCounter {
+                    position,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let widgets = [
+                PageWidget::error("one", &configuration),
+                PageWidget::error("two", &configuration),
+            ];
+            let mut terminal =
+                tui::Terminal::new(TestBackend::new(20, 6)).unwrap();
+            terminal
+                .draw(|frame| {
+                    render(
+                        frame,
+                        &configuration,
+                        &widgets,
+                        &[],
+                        &State::default(),
+                        None,
+                    )
+                })
+                .unwrap();
+            rows(terminal.backend().buffer()).swap_remove(4)
+        };
+        assert_eq!("╰─1 / 2────────────╯", render(CounterPosition::Left));
+        assert_eq!("╰───────1 / 2──────╯", render(CounterPosition::Center));
+        assert_eq!("╰────────────1 / 2─╯", render(CounterPosition::Right));
+        assert_eq!("╰──────────────────╯", render(CounterPosition::Hidden));
+    }
+
+    #[test]
+    fn chrome_hidden() {
+        let configuration = Configuration::default();
+        let widgets = [
+            PageWidget::error("one", &configuration),
+            PageWidget::error("two", &configuration),
+        ];
+        let mut state = State::default();
+        let render = |state: &State| {
+            let mut terminal =
+                tui::Terminal::new(TestBackend::new(20, 6)).unwrap();
+            terminal
+                .draw(|frame| {
+                    render(frame, &configuration, &widgets, &[], state, None)
+                })
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+
+        let buffer = render(&state);
+        assert_eq!("╭", buffer.get(0, 0).symbol);
+        assert_eq!("o", buffer.get(1, 1).symbol);
+
+        assert!(state.handle(KeyCode::Char('c'), widgets.len()));
+        let buffer = render(&state);
+        assert_eq!("o", buffer.get(0, 0).symbol);
+        assert!((0..20).all(|x| buffer.get(x, 5).symbol == " "));
+
+        assert!(state.handle(KeyCode::Char('c'), widgets.len()));
+        assert!(!state.chrome_hidden);
+    }
+
+    #[test]
+    fn monochrome() {
+        let pages = load_pages("test-resources/code.md");
+        let render = |monochrome: bool| {
+            let configuration = Configuration {
+                monochrome,
+                ..Default::default()
+            };
+            let widgets = [PageWidget::new(
+                &pages[0],
+                &configuration,
+                &Footnotes::default(),
+            )];
+            let mut terminal =
+                tui::Terminal::new(TestBackend::new(30, 16)).unwrap();
+            terminal
+                .draw(|frame| {
+                    render(
+                        frame,
+                        &configuration,
+                        &widgets,
+                        &[],
+                        &State::default(),
+                        None,
+                    )
+                })
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+        let is_rgb = |color: Color| matches!(color, Color::Rgb(..));
+
+        let buffer = render(false);
+        assert!(buffer.content.iter().any(|cell| is_rgb(cell.fg)));
+        let buffer = render(true);
+        assert!(buffer
+            .content
+            .iter()
+            .all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset));
+        assert!(buffer
+            .content
+            .iter()
+            .any(|cell| cell.modifier.contains(Modifier::UNDERLINED)));
+    }
+
+    #[test]
+    fn outline() {
+        let configuration = Configuration::default();
+        let pages = load_pages("test-resources/outline.md");
+        let footnotes = Footnotes::default();
+        let widgets = pages
+            .iter()
+            .map(|page| PageWidget::new(page, &configuration, &footnotes))
+            .collect::<Vec<_>>();
+        let entry = |page, level, text: &str| OutlineEntry {
+            page,
+            level,
+            text: text.into(),
+        };
+
+        let outline = super::outline(&widgets);
+        assert_eq!(
+            vec![
+                entry(0, 1, "Introduction"),
+                entry(0, 2, "Motivation"),
+                entry(1, 1, "Details"),
+                entry(1, 3, "Deep"),
+            ],
+            outline,
+        );
+
+        let mut state = State {
+            page: 1,
+            ..Default::default()
+        };
+        state.open_outline(&outline);
+        assert_eq!(Some(3), state.outline);
+        assert!(state.handle_outline(KeyCode::Up, &outline));
+        assert!(state.handle_outline(KeyCode::Up, &outline));
+        assert!(state.handle_outline(KeyCode::Up, &outline));
+        assert!(state.handle_outline(KeyCode::Up, &outline));
+        assert_eq!(Some(0), state.outline);
+        assert!(state.handle_outline(KeyCode::Down, &outline));
+        assert!(state.handle_outline(KeyCode::Enter, &outline));
+        assert_eq!(None, state.outline);
+        assert_eq!(0, state.page);
+
+        state.open_outline(&outline);
+        assert!(state.handle_outline(KeyCode::Esc, &outline));
+        assert_eq!(None, state.outline);
+        assert_eq!(0, state.page);
+
+        state.open_outline(&[]);
+        assert_eq!(None, state.outline);
+    }
+
+    #[test]
+    fn confirm_quit() {
+        let mut state = State::default();
+        assert!(!state.handle(KeyCode::Char('q'), 2));
+
+        let mut state = State {
+            confirm_quit: true,
+            ..Default::default()
+        };
+        assert!(state.handle(KeyCode::Char('q'), 2));
+        assert!(state.quit_pending);
+        assert!(state.handle(KeyCode::Right, 2));
+        assert!(!state.quit_pending);
+        assert_eq!(1, state.page);
+        assert!(state.handle(KeyCode::Char('q'), 2));
+        assert!(!state.handle(KeyCode::Char('q'), 2));
+
+        state.overview = Some(0);
+        assert!(state.handle(KeyCode::Char('q'), 2));
+        assert!(!state.handle(KeyCode::Char('q'), 2));
+    }
+
+    #[test]
+    fn command_message() {
+        assert_eq!(None, super::command_message(Ok(String::new())));
+        assert_eq!(
+            Some("Done".to_string()),
+            super::command_message(Ok("Working\nDone".into())),
+        );
+        assert_eq!(
+            Some("update failed".to_string()),
+            super::command_message(Err("update failed".into())),
+        );
+
+        let configuration = Configuration::default();
+        let widgets = [
+            PageWidget::error("one", &configuration),
+            PageWidget::error("two", &configuration),
+        ];
+        let mut terminal = tui::Terminal::new(TestBackend::new(20, 6)).unwrap();
+        let state = State {
+            message: Some("Done".into()),
+            ..Default::default()
+        };
+        terminal
+            .draw(|frame| {
+                render(frame, &configuration, &widgets, &[], &state, None)
+            })
+            .unwrap();
+        assert_eq!(
+            "╰─Done─────────────╯",
+            rows(terminal.backend().buffer())[4],
+        );
+    }
+
+    #[test]
+    fn notes_title() {
+        assert_eq!("Notes", super::notes_title(0));
+        assert_eq!("Notes (1 problem)", super::notes_title(1));
+        assert_eq!("Notes (3 problems)", super::notes_title(3));
+    }
+
+    #[test]
+    fn no_pages() {
+        let mut state = State::default();
+
+        assert!(state.handle(KeyCode::Right, 0));
+        assert!(state.handle(KeyCode::Tab, 0));
+        assert!(state.handle(KeyCode::Right, 0));
+        assert_eq!(Some(0), state.overview);
     }
 }
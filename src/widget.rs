@@ -1,464 +1,3031 @@
-use std::iter::repeat;
+use std::cell::Ref;
+use std::rc::Rc;
+use std::time::Duration;
 
 use tui::buffer::Buffer;
-use tui::layout::{Constraint, Direction, Layout, Rect};
-use tui::style::{Color, Style};
+use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans, Text};
-use tui::widgets::{Block, Borders, Paragraph, Widget, Wrap};
+use tui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, Widget, Wrap};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
+use crate::configuration::{
+    color, interpolate, BackgroundConfig, ColorDepth, Configuration,
+    FootnoteNumbering, PageLayout, Transition,
+};
+use crate::image;
 use crate::presentation::Page;
-use crate::transform::{Section, Sections};
+use crate::transform::{
+    Diagnostic, Footnotes, Highlights, Section, Sections, TableOptions,
+};
 
 /// A widget representing a page.
+#[derive(Clone)]
 pub struct PageWidget<'a> {
     /// The sections of the page.
     sections: Sections<'a>,
+
+    /// The speaker notes of the page.
+    notes: Vec<String>,
+
+    /// The footnotes referenced from the page.
+    footnotes: FootnoteListing<'a>,
+
+    /// The base style of the page, used to fill its background.
+    style: Style,
+
+    /// The problems found when converting the page.
+    diagnostics: Vec<Diagnostic>,
+
+    /// The layout of the page.
+    page_layout: PageLayout,
 }
 
-impl<'a> Widget for &'a PageWidget<'a> {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        self.sections.render(area, buf);
+impl<'a> PageWidget<'a> {
+    /// The marker shown when the content of a page does not fit.
+    pub const OVERFLOW_MARKER: &'static str = "▾ more";
+
+    /// The speaker notes of this page.
+    pub fn notes(&self) -> &[String] {
+        &self.notes
     }
-}
 
-impl<'a> From<&'a Page<'a>> for PageWidget<'a> {
-    fn from(source: &'a Page<'a>) -> Self {
-        Self {
-            sections: source.into(),
-        }
+    /// The base style of this page.
+    pub fn style(&self) -> Style {
+        self.style
     }
-}
 
-impl<'a> Sections<'a> {
-    /// Calculates the required height for these sections given a width.
+    /// The problems found when converting this page.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// The layout of this page.
+    pub fn page_layout(&self) -> PageLayout {
+        self.page_layout
+    }
+
+    /// The number of words on this page, not counting code blocks and
+    /// footnotes.
+    pub fn word_count(&self) -> usize {
+        self.sections.word_count(false)
+    }
+
+    /// The level and text of the top level headings on this page.
+    pub fn headings(&self) -> Vec<(u8, String)> {
+        self.sections
+            .iter()
+            .filter_map(|section| match section {
+                Section::Heading { text, level, .. } => Some((
+                    *level,
+                    text.0.iter().map(|span| &*span.content).collect(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The source code of all code blocks on this page.
+    pub fn code(&self) -> Vec<&str> {
+        self.sections.code()
+    }
+
+    /// The height of the content of this page, including its footnotes.
     ///
     /// # Arguments
     /// *  `width` - The width of the rendering area.
     pub fn height(&self, width: u16) -> u16 {
-        self.iter()
-            .enumerate()
-            .map(|(i, section)| {
-                self.height_of(section, width, i == 0, i == self.len() - 1)
+        let content = self.sections.height(width);
+        if self.footnotes.is_empty() {
+            content
+        } else {
+            content + 1 + self.footnotes.height(width)
+        }
+    }
+
+    /// Splits this page into pages fitting an area.
+    ///
+    /// Every page shares the speaker notes and footnotes of this page.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the rendering area.
+    /// *  `height` - The height of the rendering area.
+    pub fn paginate(&self, width: u16, height: u16) -> Vec<PageWidget<'a>> {
+        let (content, _) = self.layout(Rect::new(0, 0, width, height));
+        self.sections
+            .paginate(width, content.height)
+            .into_iter()
+            .map(|sections| Self {
+                footnotes: self
+                    .footnotes
+                    .referenced(&sections.footnote_references()),
+                sections,
+                notes: self.notes.clone(),
+                style: self.style,
+                diagnostics: self.diagnostics.clone(),
+                page_layout: self.page_layout,
             })
-            .sum()
+            .collect()
     }
 
-    /// Calculates the required height for a single section.
+    /// The maximum vertical scroll offset for this page in an area.
     ///
     /// # Arguments
-    /// *  `section` - The section shose height to calculate.
     /// *  `width` - The width of the rendering area.
-    /// *  `is_first` - Whether this section is the first section.
-    /// *  `is_last` - Whether this section is the last section.
-    fn height_of(
-        &self,
-        section: &Section<'a>,
-        width: u16,
-        is_first: bool,
-        is_last: bool,
-    ) -> u16 {
-        let padding = section.padding();
-        section.height(width)
-            + if is_first { 0 } else { padding.0 }
-            + if is_last {
-                0
+    /// *  `height` - The height of the rendering area.
+    pub fn max_scroll(&self, width: u16, height: u16) -> u16 {
+        let (content, _) = self.layout(Rect::new(0, 0, width, height));
+        self.sections.height(width).saturating_sub(content.height)
+    }
+
+    /// Splits an area into the area for the content and the area for the
+    /// footnotes.
+    ///
+    /// The footnotes are placed at the bottom, separated from the content by
+    /// an empty line, unless there are none or the area is too small. If the
+    /// content and footnotes do not both fit, the footnotes are given at most
+    /// half of the area.
+    ///
+    /// # Arguments
+    /// *  `area` - The area of the page.
+    fn layout(&self, area: Rect) -> (Rect, Option<Rect>) {
+        let height = self.footnotes.height(area.width);
+        let height =
+            if self.sections.height(area.width) + 1 + height > area.height {
+                height.min(area.height / 2)
             } else {
-                padding.1 + self.inner_margin
-            }
+                height
+            };
+        if self.footnotes.is_empty() || height + 1 >= area.height {
+            (area, None)
+        } else {
+            (
+                Rect {
+                    height: area.height - height - 1,
+                    ..area
+                },
+                Some(Rect {
+                    y: area.bottom() - height,
+                    height,
+                    ..area
+                }),
+            )
+        }
+    }
+
+    /// This page scrolled vertically.
+    ///
+    /// # Arguments
+    /// *  `offset` - The number of lines to scroll. This is clamped to the
+    ///    maximum scroll offset when rendering.
+    pub fn scrolled(&'a self, offset: u16) -> ScrolledPageWidget<'a> {
+        ScrolledPageWidget { page: self, offset }
     }
 }
 
-impl<'a> Widget for &'a Sections<'a> {
+impl<'a> Widget for &'a PageWidget<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let parts = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                self.iter()
-                    .enumerate()
-                    .map(|(i, section)| {
-                        Constraint::Length(self.height_of(
-                            section,
-                            area.width,
-                            i == 0,
-                            i == self.len() - 1,
-                        ))
-                    })
-                    .collect::<Vec<_>>(),
-            )
-            .split(area);
-        for (i, (mut part, section)) in
-            parts.into_iter().zip(self.iter()).enumerate()
-        {
-            let padding = section.padding();
-            let is_first = i == 0;
-            let is_last = i == self.len() - 1;
-            if !is_first {
-                part.y += padding.0;
-                part.height -= padding.0;
+        self.scrolled(0).render(area, buf);
+    }
+}
+
+/// A widget representing a page scrolled vertically.
+pub struct ScrolledPageWidget<'a> {
+    /// The page.
+    page: &'a PageWidget<'a>,
+
+    /// The number of lines to scroll.
+    offset: u16,
+}
+
+impl<'a> Widget for ScrolledPageWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (area, footnotes) = self.page.layout(area);
+        if let Some(footnotes) = footnotes {
+            Sections::fill(footnotes, buf, self.page.style);
+            self.page.footnotes.render(footnotes, buf);
+        }
+
+        let content_height = self.page.sections.height(area.width);
+        let offset = self
+            .offset
+            .min(self.page.max_scroll(area.width, area.height));
+        if offset == 0 {
+            self.page.sections.render_filled(area, buf, self.page.style);
+        } else {
+            // Render the full content off screen over what is already drawn,
+            // and copy the visible part
+            let full = Rect {
+                height: content_height,
+                ..area
+            };
+            let mut content = Buffer::empty(full);
+            for y in area.top()..area.bottom().min(full.bottom() - offset) {
+                for x in area.left()..area.right() {
+                    *content.get_mut(x, y + offset) = buf.get(x, y).clone();
+                }
             }
-            if !is_last {
-                part.height -= padding.1 + self.inner_margin;
+            self.page.sections.render_filled(
+                full,
+                &mut content,
+                self.page.style,
+            );
+            for y in area.top()..area.bottom() {
+                for x in area.left()..area.right() {
+                    *buf.get_mut(x, y) = content.get(x, y + offset).clone();
+                }
             }
-            section.render(part, buf);
+        }
+
+        // Show a marker in the bottom right corner if the content is clipped
+        if content_height > offset + area.height && area.height > 0 {
+            let width = PageWidget::OVERFLOW_MARKER.chars().count() as u16;
+            buf.set_stringn(
+                area.x + area.width.saturating_sub(width),
+                area.bottom() - 1,
+                PageWidget::OVERFLOW_MARKER,
+                area.width as usize,
+                Style::default().add_modifier(Modifier::BOLD),
+            );
         }
     }
 }
 
-impl<'a> Section<'a> {
-    /// Calculates the required height for this section given a width.
+impl<'a> PageWidget<'a> {
+    /// Creates a widget for a page.
     ///
     /// # Arguments
-    /// *  `width` - The width of the rendering area.
-    pub fn height(&self, width: u16) -> u16 {
-        use Section::*;
-        match self {
-            BlockQuote { content } => Self::height_block_quote(width, content),
-            Code { text } => Self::height_code(width, text),
-            Heading { text, level } => Self::height_heading(width, text, level),
-            List { content } => Self::height_list(width, content),
-            ListItemOrdered {
-                content,
-                ordinal,
-                delimiter,
-            } => Self::height_list_item_ordered(
-                width, content, ordinal, delimiter,
-            ),
-            ListItemUnordered { content, bullet } => {
-                Self::height_list_item_unordered(width, content, bullet)
-            }
-            Paragraph { text } => Self::height_paragraph(width, text),
-            ThematicBreak => Self::height_thematic_break(width),
-        }
+    /// *  `page` - The page.
+    /// *  `configuration` - The application configuration.
+    /// *  `footnotes` - The footnotes of the presentation.
+    pub fn new(
+        page: &'a Page<'a>,
+        configuration: &Configuration,
+        footnotes: &Footnotes<'a>,
+    ) -> Self {
+        Self::with_highlights(
+            page,
+            configuration,
+            footnotes,
+            &Highlights::default(),
+        )
     }
 
-    /// The top and bottom padding for this section.
+    /// Creates a widget for a page, using code blocks highlighted up front.
     ///
-    /// The padding is only used if the section has a neighbour in the
-    /// respective direction.
-    pub fn padding(&self) -> (u16, u16) {
-        use Section::*;
-        match self {
-            Heading { .. } => (1, 0),
-            _ => (0, 0),
+    /// # Arguments
+    /// *  `page` - The page.
+    /// *  `configuration` - The application configuration.
+    /// *  `footnotes` - The footnotes of the presentation.
+    /// *  `highlights` - The highlighted code blocks.
+    pub fn with_highlights(
+        page: &'a Page<'a>,
+        configuration: &Configuration,
+        footnotes: &Footnotes<'a>,
+        highlights: &Highlights<'a>,
+    ) -> Self {
+        let (sections, mut diagnostics) = Sections::from_page_with_highlights(
+            page,
+            configuration,
+            highlights,
+        );
+        for index in page.footnote_references() {
+            diagnostics.extend_from_slice(footnotes.diagnostics(index));
+        }
+        Self {
+            sections,
+            notes: page.notes(),
+            footnotes: FootnoteListing::new(page, configuration, footnotes),
+            style: configuration.default_style(),
+            diagnostics,
+            page_layout: configuration.layout,
         }
     }
+}
 
-    fn height_block_quote(width: u16, content: &Sections<'a>) -> u16 {
-        // We add 2 for the head and tail lines
-        2 + content.height(width)
+impl PageWidget<'static> {
+    /// Creates a widget for a table of contents.
+    ///
+    /// # Arguments
+    /// *  `pages` - The pages of the presentation.
+    /// *  `leading` - The number of pages generated before the pages, such as
+    ///    the table of contents itself.
+    /// *  `configuration` - The application configuration.
+    pub fn table_of_contents(
+        pages: &[Page<'_>],
+        leading: usize,
+        configuration: &Configuration,
+    ) -> Self {
+        let titles = pages.iter().map(Page::title).collect::<Vec<_>>();
+        Self {
+            sections: Sections::table_of_contents(
+                &titles,
+                leading,
+                configuration,
+            ),
+            notes: Vec::new(),
+            footnotes: FootnoteListing::default(),
+            style: configuration.default_style(),
+            diagnostics: Vec::new(),
+            page_layout: PageLayout::default(),
+        }
     }
 
-    fn height_code(_width: u16, text: &Text<'a>) -> u16 {
-        // We do not wrap code sections, so the height is the number of lines
-        text.height() as u16
+    /// Creates a page showing the title, subtitle, author and date of the
+    /// presentation, centered vertically.
+    ///
+    /// # Arguments
+    /// *  `configuration` - The application configuration.
+    pub fn metadata(configuration: &Configuration) -> Self {
+        Self {
+            sections: Sections::metadata(configuration),
+            notes: Vec::new(),
+            footnotes: FootnoteListing::default(),
+            style: configuration.default_style(),
+            diagnostics: Vec::new(),
+            page_layout: PageLayout::Title,
+        }
     }
 
-    fn height_heading(width: u16, text: &Spans<'a>, level: &u8) -> u16 {
-        // A heading is a single line, with an additional header determined by
-        // the level
-        Self::height_line(width, *level as u16 + 1, &text.0)
+    /// Creates a widget describing an error.
+    ///
+    /// # Arguments
+    /// *  `message` - The error message.
+    /// *  `configuration` - The application configuration.
+    pub fn error(message: &str, configuration: &Configuration) -> Self {
+        let style = configuration.default_style();
+        Self {
+            sections: vec![Section::Paragraph {
+                text: Text::styled(message.to_string(), style.fg(Color::Red)),
+                wrap: Some(Wrap { trim: true }),
+                ellipsis: configuration.ellipsis.clone(),
+                break_wide: configuration.wraps_wide_characters(),
+            }]
+            .into(),
+            notes: Vec::new(),
+            footnotes: FootnoteListing::default(),
+            style,
+            diagnostics: Vec::new(),
+            page_layout: PageLayout::default(),
+        }
     }
+}
 
-    fn height_list(width: u16, content: &Sections<'a>) -> u16 {
-        // The height of a list is the height of its sections
-        content.height(width)
-    }
+/// A widget showing a frame of the transition between two pages.
+pub struct TransitionWidget<'a> {
+    /// The page shown before the transition.
+    from: &'a PageWidget<'a>,
 
-    fn height_list_item_ordered(
-        width: u16,
-        content: &Sections<'a>,
-        _ordinal: &usize,
-        _delimiter: &char,
-    ) -> u16 {
-        // The height of a list item is the height of its sections
-        content.height(width)
-    }
+    /// The page shown after the transition.
+    to: &'a PageWidget<'a>,
 
-    fn height_list_item_unordered(
-        width: u16,
-        content: &Sections<'a>,
-        _bullet: &char,
-    ) -> u16 {
-        // The height of a list item is the height of its sections
-        content.height(width)
-    }
+    /// The kind of transition.
+    transition: Transition,
 
-    fn height_paragraph(width: u16, text: &Text<'a>) -> u16 {
-        // The height of a paragraph is the height of its wrapped lines if it
-        // contains any non-whitespace characters
-        /*if text
-            .lines
-            .iter()
-            .any(|line| Self::contains_non_whitespace(&line.0))
-        {*/
-        text.lines
-            .iter()
-            .map(|line| Self::height_line(width, 0, &line.0))
-            .sum::<u16>()
-        /*} else {
-            0
-        }*/
-    }
+    /// Whether the transition moves forward through the presentation.
+    forward: bool,
 
-    fn height_thematic_break(_width: u16) -> u16 {
-        // A thematic break is always one lines high
-        1
-    }
+    /// The current frame, in the range `0..=FRAMES`.
+    frame: u16,
+}
 
-    /// Calculates the height of a single line.
+impl<'a> TransitionWidget<'a> {
+    /// The number of frames of a transition.
+    pub const FRAMES: u16 = 8;
+
+    /// The time each frame is shown.
+    pub const FRAME_DURATION: Duration = Duration::from_millis(20);
+
+    /// Creates a transition frame.
     ///
-    /// This function takes wrapping of long lines into account.
+    /// # Arguments
+    /// *  `from` - The page shown before the transition.
+    /// *  `to` - The page shown after the transition.
+    /// *  `transition` - The kind of transition.
+    /// *  `forward` - Whether the transition moves forward.
+    /// *  `frame` - The current frame.
+    pub fn new(
+        from: &'a PageWidget<'a>,
+        to: &'a PageWidget<'a>,
+        transition: Transition,
+        forward: bool,
+        frame: u16,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            transition,
+            forward,
+            frame: frame.min(Self::FRAMES),
+        }
+    }
+
+    /// The number of columns the pages have moved during a slide transition.
     ///
     /// # Arguments
     /// *  `width` - The width of the rendering area.
-    /// *  `indent` - An initial assumed indent.
-    /// *  `value` - The line for which to calculate the height.
-    fn height_line(width: u16, indent: u16, value: &[Span<'_>]) -> u16 {
-        enum Word {
-            None,
-            Started(u16),
-            WrappedAt(u16),
-        }
-        struct State {
-            height: u16,
-            pos: u16,
-            current: Word,
-        }
-        value
-            .iter()
-            .flat_map(|span| span.content.chars())
-            .enumerate()
-            .fold(
-                State {
-                    height: 1,
-                    pos: indent,
-                    current: Word::None,
-                },
-                |mut state, (i, c)| {
-                    use Word::*;
-
-                    state.pos += 1;
-                    state.current = match state.current {
-                        // Start a new word if none active when we
-                        // encounter non-whitesspace
-                        None if !c.is_whitespace() => Started(i as u16),
+    /// *  `frame` - The current frame.
+    pub fn slide_offset(width: u16, frame: u16) -> u16 {
+        (u32::from(width) * u32::from(frame.min(Self::FRAMES))
+            / u32::from(Self::FRAMES)) as u16
+    }
+}
 
-                        // Stop current word on whitespace
-                        Started(_) if c.is_whitespace() => None,
+impl<'a> Widget for TransitionWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut from = Buffer::empty(area);
+        self.from.render(area, &mut from);
+        let mut to = Buffer::empty(area);
+        self.to.render(area, &mut to);
 
-                        // Wrap when we encounter end of line
-                        Started(pos) if state.pos >= width => {
-                            WrappedAt(i as u16 - pos)
+        let offset = Self::slide_offset(area.width, self.frame);
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let column = x - area.left();
+                let cell = match self.transition {
+                    // The new page follows the old page from the right when
+                    // moving forward, and from the left otherwise
+                    Transition::Slide if self.forward => {
+                        if column + offset < area.width {
+                            from.get(x + offset, y).clone()
+                        } else {
+                            to.get(x + offset - area.width, y).clone()
+                        }
+                    }
+                    Transition::Slide => {
+                        if column < offset {
+                            to.get(x + area.width - offset, y).clone()
+                        } else {
+                            from.get(x - offset, y).clone()
                         }
+                    }
 
-                        // Add wrapped word to next line at the end of the
-                        // word, unless the next line is empty
-                        WrappedAt(pos) if c.is_whitespace() => {
-                            state.pos = if state.pos > 0 {
-                                state.pos + pos
-                            } else {
-                                state.pos
-                            };
-                            None
+                    // Terminals cannot blend, so both pages are dimmed while
+                    // fading
+                    Transition::Fade => {
+                        let mut cell = if self.frame * 2 < Self::FRAMES {
+                            from.get(x, y).clone()
+                        } else {
+                            to.get(x, y).clone()
+                        };
+                        if self.frame < Self::FRAMES {
+                            cell.modifier.insert(Modifier::DIM);
                         }
-                        _ => state.current,
-                    };
-
-                    // Increase height and start from the beginning when we
-                    // encounter end of line
-                    if state.pos >= width {
-                        state.pos = 0;
-                        state.height += 1;
+                        cell
                     }
-                    state
-                },
-            )
-            .height
-    }
 
-    /// Determines whether a collection of spans contains non-whitespace.
-    ///
-    /// # Argument
-    /// *  `line` - The line to check.
-    fn contains_non_whitespace(line: &[Span<'a>]) -> bool {
-        line.iter()
-            .flat_map(|span| span.content.chars())
-            .any(|c| !c.is_whitespace())
+                    Transition::None => to.get(x, y).clone(),
+                };
+                *buf.get_mut(x, y) = cell;
+            }
+        }
     }
 }
 
-impl<'a> Section<'a> {
-    /// Renders this section.
-    ///
-    /// # Arguments
-    /// *  `area` - The allocated area for this section.
-    /// *  `buf` - The target buffer.
-    pub fn render(&self, area: Rect, buf: &mut Buffer) {
-        use Section::*;
-        match &self {
-            BlockQuote { content } => {
-                Self::render_block_quote(area, buf, &content)
-            }
-            Code { text } => Self::render_code(area, buf, text),
-            Heading { text, level } => {
-                Self::render_heading(area, buf, text, level)
+/// A widget filling an area with a background colour and pattern.
+///
+/// The pattern is anchored at the top left corner of the area, and pages
+/// rendered over it afterwards hide it on the lines of their content.
+pub struct BackgroundWidget<'a>(pub &'a BackgroundConfig);
+
+impl<'a> Widget for BackgroundWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if let Some(color) = self.0.color {
+            buf.set_style(area, Style::default().bg(color));
+        }
+
+        let lines = self
+            .0
+            .pattern
+            .lines()
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        if lines.iter().all(Vec::is_empty) {
+            return;
+        }
+        for y in area.top()..area.bottom() {
+            let line = &lines[(y - area.y) as usize % lines.len()];
+            if line.is_empty() {
+                continue;
             }
-            List { content } => Self::render_list(area, buf, &content),
-            ListItemOrdered {
-                content,
-                ordinal,
-                delimiter,
-            } => Self::render_list_item_ordered(
-                area, buf, &content, ordinal, delimiter,
-            ),
-            ListItemUnordered { content, bullet } => {
-                Self::render_list_item_unordered(area, buf, &content, bullet)
+            for x in area.left()..area.right() {
+                let cell = buf.get_mut(x, y);
+                cell.set_char(line[(x - area.x) as usize % line.len()]);
+                if let Some(color) = self.0.pattern_color {
+                    cell.fg = color;
+                }
             }
-            Paragraph { text } => Self::render_paragraph(area, buf, text),
-            ThematicBreak => Self::render_thematic_break(area, buf),
         }
     }
+}
 
-    fn render_block_quote(
-        area: Rect,
-        buf: &mut Buffer,
-        content: &Sections<'a>,
-    ) {
-        let parts = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                [
-                    Constraint::Length(1),
-                    Constraint::Max(area.height),
-                    Constraint::Length(1),
-                ]
-                .as_ref(),
-            )
-            .split(area);
-        Paragraph::new(">>>").render(parts[0], buf);
-        Paragraph::new("<<<").render(parts[2], buf);
-        let parts = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(
-                [
-                    Constraint::Length(Self::INDENT / 2),
-                    Constraint::Max(area.width),
-                ]
-                .as_ref(),
-            )
-            .split(parts[1]);
-        content.render(parts[1], buf);
-    }
+/// A widget removing all colours from an area, leaving only modifiers such
+/// as bold and underline.
+pub struct MonochromeWidget;
 
-    fn render_code(area: Rect, buf: &mut Buffer, text: &Text<'a>) {
-        Paragraph::new(text.clone()).render(area, buf);
+impl Widget for MonochromeWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = buf.get_mut(x, y);
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+            }
+        }
     }
+}
 
-    fn render_heading(
-        area: Rect,
-        buf: &mut Buffer,
+/// A widget replacing the RGB colours of an area by the closest colours
+/// supported at a colour depth.
+pub struct PaletteWidget(pub ColorDepth);
+
+impl Widget for PaletteWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = buf.get_mut(x, y);
+                cell.fg = color::quantize(cell.fg, self.0);
+                cell.bg = color::quantize(cell.bg, self.0);
+            }
+        }
+    }
+}
+
+/// A widget listing the footnotes referenced from a page.
+#[derive(Clone, Default)]
+pub struct FootnoteListing<'a> {
+    /// The index in the presentation, displayed index and definition of
+    /// every footnote.
+    ///
+    /// The definitions are shared with all other pages referencing them.
+    footnotes: Vec<(usize, usize, Rc<Sections<'a>>)>,
+
+    /// The line shown after the footnotes when not all of them fit.
+    overflow: String,
+
+    /// The style of the rule drawn above the footnotes, if any.
+    rule: Option<Style>,
+
+    /// The number of cells the definitions are indented by, leaving room for
+    /// the indices.
+    indent: u16,
+}
+
+impl<'a> FootnoteListing<'a> {
+    /// Creates a listing of the footnotes referenced from a page.
+    ///
+    /// # Arguments
+    /// *  `page` - The page.
+    /// *  `configuration` - The application configuration.
+    /// *  `footnotes` - The footnotes of the presentation.
+    pub fn new(
+        page: &Page<'a>,
+        configuration: &Configuration,
+        footnotes: &Footnotes<'a>,
+    ) -> Self {
+        let per_page =
+            configuration.footnote_numbering == FootnoteNumbering::PerPage;
+        Self {
+            footnotes: page
+                .footnote_references()
+                .into_iter()
+                .enumerate()
+                .filter_map(|(position, index)| {
+                    footnotes.get(index).map(|content| {
+                        (
+                            index,
+                            if per_page { position } else { index },
+                            content,
+                        )
+                    })
+                })
+                .collect(),
+            overflow: configuration.footnote_overflow.clone(),
+            rule: configuration.footnote_rule.then(|| {
+                Style::default().fg(configuration.thematic_break.color)
+            }),
+            indent: configuration.indent_width,
+        }
+    }
+
+    /// This listing with only the footnotes referenced from a part of the
+    /// page, such as when the page is split.
+    ///
+    /// # Arguments
+    /// *  `references` - The indices of the footnotes referenced.
+    pub fn referenced(&self, references: &[usize]) -> Self {
+        Self {
+            footnotes: self
+                .footnotes
+                .iter()
+                .filter(|(index, _, _)| references.contains(index))
+                .cloned()
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Whether this listing contains no footnotes.
+    pub fn is_empty(&self) -> bool {
+        self.footnotes.is_empty()
+    }
+
+    /// The displayed index and definition of every footnote in this listing.
+    pub fn footnotes(&self) -> impl Iterator<Item = (usize, &Sections<'a>)> {
+        self.footnotes
+            .iter()
+            .map(|(_, displayed, content)| (*displayed, content.as_ref()))
+    }
+
+    /// Calculates the required height for this listing given a width.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the rendering area.
+    pub fn height(&self, width: u16) -> u16 {
+        let width = width.saturating_sub(self.indent);
+        let rule = if self.rule.is_some() { 1 } else { 0 };
+        rule + self
+            .footnotes
+            .iter()
+            .map(|(_, _, content)| content.height(width))
+            .sum::<u16>()
+    }
+}
+
+impl<'a> Widget for &'a FootnoteListing<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = match self.rule {
+            Some(style) if area.height > 0 => {
+                Section::render_thematic_break(
+                    Rect { height: 1, ..area },
+                    buf,
+                    "─",
+                    true,
+                    style,
+                );
+                Rect {
+                    y: area.y + 1,
+                    height: area.height - 1,
+                    ..area
+                }
+            }
+            _ => area,
+        };
+
+        // If not all footnotes fit, as many as fit are shown, followed by a
+        // line with the number of footnotes left out
+        let width = area.width.saturating_sub(self.indent);
+        let heights = self
+            .footnotes
+            .iter()
+            .map(|(_, _, content)| content.height(width))
+            .collect::<Vec<_>>();
+        let count = if heights.iter().sum::<u16>() <= area.height {
+            heights.len()
+        } else {
+            let available = area.height.saturating_sub(1);
+            heights
+                .iter()
+                .scan(0, |total, height| {
+                    *total += height;
+                    Some(*total)
+                })
+                .take_while(|total| *total <= available)
+                .count()
+        };
+
+        let mut y = area.y;
+        for (height, (_, index, content)) in
+            heights.iter().zip(&self.footnotes).take(count)
+        {
+            let part = Rect {
+                y,
+                height: *height,
+                ..area
+            };
+            y += height;
+            let parts = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(
+                    [
+                        Constraint::Length(self.indent),
+                        Constraint::Max(part.width),
+                    ]
+                    .as_ref(),
+                )
+                .split(part);
+            Paragraph::new(Footnotes::index_to_superscript(*index))
+                .render(parts[0], buf);
+            content.as_ref().render(parts[1], buf);
+        }
+
+        if count < self.footnotes.len() && y < area.bottom() {
+            let remaining = (self.footnotes.len() - count).to_string();
+            Paragraph::new(interpolate(&self.overflow, |name| {
+                (name == "count").then(|| remaining.clone())
+            }))
+            .render(
+                Rect {
+                    y,
+                    height: 1,
+                    ..area
+                },
+                buf,
+            );
+        }
+    }
+}
+
+impl<'a> Sections<'a> {
+    /// Calculates the required height for these sections given a width.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the rendering area.
+    pub fn height(&self, width: u16) -> u16 {
+        self.section_heights(width).iter().sum()
+    }
+
+    /// Splits these sections at section boundaries into collections fitting
+    /// an area.
+    ///
+    /// A single section too tall for the area is put in a collection of its
+    /// own. At least one collection is always returned.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the rendering area.
+    /// *  `height` - The height of the rendering area.
+    pub fn paginate(&self, width: u16, height: u16) -> Vec<Sections<'a>> {
+        let mut result = vec![];
+        let mut start = 0;
+        for end in 1..=self.len() {
+            if end - start > 1 && self.slice(start..end).height(width) > height
+            {
+                result.push(self.slice(start..end - 1));
+                start = end - 1;
+            }
+        }
+        result.push(self.slice(start..self.len()));
+        result
+    }
+
+    /// The required height for every section given a width, including
+    /// padding and margins.
+    ///
+    /// Sections without content have no padding or margins, and are not
+    /// considered neighbours of other sections.
+    ///
+    /// The heights are cached until requested for a different width.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the rendering area.
+    fn section_heights(&self, width: u16) -> Ref<'_, [u16]> {
+        self.heights(width, || {
+            let heights = self
+                .iter()
+                .map(|section| section.height(width))
+                .collect::<Vec<_>>();
+            let (first, last) = Self::bounds(&heights);
+            self.iter()
+                .zip(heights)
+                .enumerate()
+                .map(|(i, (section, height))| {
+                    if height == 0 {
+                        0
+                    } else {
+                        self.height_of(
+                            section,
+                            height,
+                            Some(i) == first,
+                            Some(i) == last,
+                        )
+                    }
+                })
+                .collect()
+        })
+    }
+
+    /// The indices of the first and last sections with content.
+    ///
+    /// # Arguments
+    /// *  `heights` - The heights of the sections.
+    fn bounds(heights: &[u16]) -> (Option<usize>, Option<usize>) {
+        (
+            heights.iter().position(|height| *height > 0),
+            heights.iter().rposition(|height| *height > 0),
+        )
+    }
+
+    /// Calculates the required height for a single section.
+    ///
+    /// # Arguments
+    /// *  `section` - The section shose height to calculate.
+    /// *  `height` - The height of the content of the section.
+    /// *  `is_first` - Whether this section is the first section.
+    /// *  `is_last` - Whether this section is the last section.
+    fn height_of(
+        &self,
+        section: &Section<'a>,
+        height: u16,
+        is_first: bool,
+        is_last: bool,
+    ) -> u16 {
+        let padding = section.padding();
+        height
+            + if is_first { 0 } else { padding.0 }
+            + if is_last {
+                0
+            } else {
+                padding.1 + self.inner_margin
+            }
+    }
+}
+
+impl<'a> Widget for &'a Sections<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for (section, part, _) in self.parts(area) {
+            section.render(part, buf);
+        }
+    }
+}
+
+impl<'a> Sections<'a> {
+    /// Renders these sections, first filling the lines they cover with a
+    /// style.
+    ///
+    /// Widgets draw only the cells of their text, so the lines are filled to
+    /// hide anything drawn behind the page, such as a background pattern.
+    ///
+    /// # Arguments
+    /// *  `area` - The rendering area.
+    /// *  `buf` - The target buffer.
+    /// *  `style` - The style filling the lines.
+    pub fn render_filled(&self, area: Rect, buf: &mut Buffer, style: Style) {
+        for (section, part, lines) in self.parts(area) {
+            Self::fill(lines, buf, style);
+            section.render(part, buf);
+        }
+    }
+
+    /// Clears an area and fills it with a style.
+    ///
+    /// # Arguments
+    /// *  `area` - The area to fill.
+    /// *  `buf` - The target buffer.
+    /// *  `style` - The style filling the area.
+    fn fill(area: Rect, buf: &mut Buffer, style: Style) {
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                buf.get_mut(x, y).reset();
+            }
+        }
+        buf.set_style(area, style);
+    }
+
+    /// The areas of the sections with content.
+    ///
+    /// The sections are stacked from the top, and the last section with
+    /// content is given any remaining space. Every section is returned with
+    /// its area and the part of the area covered by its content.
+    ///
+    /// # Arguments
+    /// *  `area` - The rendering area.
+    fn parts(&self, area: Rect) -> Vec<(&Section<'a>, Rect, Rect)> {
+        let heights = self.section_heights(area.width).to_vec();
+        let (first, last) = Sections::bounds(&heights);
+
+        let mut result = Vec::new();
+        let mut y = area.y;
+        for (i, (section, height)) in self.iter().zip(heights).enumerate() {
+            if height == 0 {
+                continue;
+            }
+            let is_first = Some(i) == first;
+            let is_last = Some(i) == last;
+            let available = area.bottom().saturating_sub(y);
+            let mut part = Rect {
+                y,
+                height: if is_last {
+                    available
+                } else {
+                    height.min(available)
+                },
+                ..area
+            };
+            y += part.height;
+
+            let padding = section.padding();
+            let mut content = height;
+            if !is_first {
+                part.y += padding.0.min(part.height);
+                part.height = part.height.saturating_sub(padding.0);
+                content = content.saturating_sub(padding.0);
+            }
+            if !is_last {
+                part.height =
+                    part.height.saturating_sub(padding.1 + self.inner_margin);
+            }
+            let lines = Rect {
+                height: part.height.min(content),
+                ..part
+            };
+            result.push((section, part, lines));
+        }
+        result
+    }
+}
+
+impl<'a> Section<'a> {
+    /// Calculates the required height for this section given a width.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the rendering area.
+    pub fn height(&self, width: u16) -> u16 {
+        use Section::*;
+        match self {
+            BlockQuote {
+                content,
+                indent,
+                marker,
+            } => Self::height_block_quote(width, content, *indent, marker),
+            Code {
+                text,
+                language,
+                caption,
+                border,
+                ..
+            } => Self::height_code(width, text, language, *caption, *border),
+            Columns { columns } => Self::height_columns(width, columns),
+            Heading {
+                text,
+                level,
+                hash,
+                break_wide,
+                ..
+            } => Self::height_heading(width, text, level, *hash, *break_wide),
+            Image { path, alt } => Self::height_image(width, path, alt),
+            Line {
+                text, break_wide, ..
+            } => Self::height_line(width, 0, &text.0, *break_wide),
+            List { content } => Self::height_list(width, content),
+            ListItemOrdered {
+                content,
+                ordinal,
+                delimiter,
+                indent,
+            } => Self::height_list_item_ordered(
+                width, content, ordinal, delimiter, *indent,
+            ),
+            ListItemUnordered {
+                content,
+                bullet,
+                indent,
+            } => Self::height_list_item_unordered(
+                width, content, bullet, *indent,
+            ),
+            Paragraph {
+                text,
+                wrap,
+                break_wide,
+                ..
+            } => Self::height_paragraph(width, text, wrap, *break_wide),
+            Table {
+                header,
+                rows,
+                options,
+            } => Self::height_table(width, header, rows, options.border),
+            ThematicBreak { symbol, .. } => {
+                Self::height_thematic_break(width, symbol)
+            }
+        }
+    }
+
+    /// The top and bottom padding for this section.
+    ///
+    /// The padding is only used if the section has a neighbour in the
+    /// respective direction.
+    pub fn padding(&self) -> (u16, u16) {
+        use Section::*;
+        match self {
+            Heading { padding, .. } => *padding,
+            _ => (0, 0),
+        }
+    }
+
+    fn height_block_quote(
+        width: u16,
+        content: &Sections<'a>,
+        indent: u16,
+        marker: &str,
+    ) -> u16 {
+        // We add 1 for the tail line, and 1 for the head line unless there is
+        // no marker to show on it
+        let head = if marker.is_empty() { 0 } else { 1 };
+        head + 1 + content.height(width - Self::fit_indent(width, indent / 2))
+    }
+
+    fn height_code(
+        _width: u16,
+        text: &Text<'a>,
+        language: &Option<String>,
+        caption: bool,
+        border: bool,
+    ) -> u16 {
+        // We do not wrap code sections, so the height is the number of lines;
+        // we add 2 for the border lines if any, and otherwise 1 for the
+        // caption line if any
+        text.height() as u16
+            + match (border, caption && language.is_some()) {
+                (true, _) => 2,
+                (false, true) => 1,
+                (false, false) => 0,
+            }
+    }
+
+    fn height_columns(width: u16, columns: &[Sections<'a>]) -> u16 {
+        // The height of a column layout is the height of its highest column
+        let area = Rect::new(0, 0, width, 0);
+        columns
+            .iter()
+            .zip(Self::column_areas(area, columns.len()))
+            .map(|(column, area)| column.height(area.width))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn height_heading(
+        width: u16,
+        text: &Spans<'a>,
+        level: &u8,
+        hash: bool,
+        break_wide: bool,
+    ) -> u16 {
+        // A heading is a single line, with an optional additional header
+        // determined by the level
+        let indent = if hash { *level as u16 + 1 } else { 0 };
+        Self::height_line(width, indent, &text.0, break_wide)
+    }
+
+    fn height_image(width: u16, path: &str, alt: &str) -> u16 {
+        // An image shown by the terminal is assumed to have an aspect ratio
+        // of 16:9, and a cell to be twice as high as it is wide; the fallback
+        // is the alternative text
+        if image::is_supported() && path.to_lowercase().ends_with(".png") {
+            (u32::from(width) * 9 / 32).min(u32::from(u16::MAX)) as u16
+        } else {
+            Self::height_line(width, 0, &[Span::raw(alt)], false)
+        }
+    }
+
+    fn height_list(width: u16, content: &Sections<'a>) -> u16 {
+        // The height of a list is the height of its sections
+        content.height(width)
+    }
+
+    fn height_list_item_ordered(
+        width: u16,
+        content: &Sections<'a>,
+        _ordinal: &usize,
+        _delimiter: &char,
+        indent: u16,
+    ) -> u16 {
+        // The height of a list item is the height of its sections
+        content.height(width - Self::fit_indent(width, indent))
+    }
+
+    fn height_list_item_unordered(
+        width: u16,
+        content: &Sections<'a>,
+        _bullet: &char,
+        indent: u16,
+    ) -> u16 {
+        // The height of a list item is the height of its sections
+        content.height(width - Self::fit_indent(width, indent))
+    }
+
+    fn height_paragraph(
+        width: u16,
+        text: &Text<'a>,
+        wrap: &Option<Wrap>,
+        break_wide: bool,
+    ) -> u16 {
+        // The height of a paragraph is the height of its lines, wrapped
+        // unless they are clipped, if it contains any non-whitespace
+        // characters
+        if !text
+            .lines
+            .iter()
+            .any(|line| Self::contains_non_whitespace(&line.0))
+        {
+            0
+        } else if let Some(wrap) = wrap {
+            text.lines
+                .iter()
+                .map(|line| {
+                    Self::wrap_with(width, 0, &line.0, wrap.trim, break_wide)
+                        .len() as u16
+                })
+                .sum::<u16>()
+        } else {
+            text.lines.len() as u16
+        }
+    }
+
+    fn height_table(
+        _width: u16,
+        header: &[Text<'a>],
+        rows: &[Vec<Text<'a>>],
+        border: bool,
+    ) -> u16 {
+        // A table without columns is not shown; otherwise, we add 2 for the
+        // border lines if any to the height of the rows, including the header
+        if header.is_empty() {
+            0
+        } else {
+            let border = if border { 2 } else { 0 };
+            border
+                + Self::height_row(header)
+                + rows.iter().map(|row| Self::height_row(row)).sum::<u16>()
+        }
+    }
+
+    /// The height of a table row, which is the height of its highest cell.
+    ///
+    /// Rows are at least one line high, even if they have no cells.
+    ///
+    /// # Arguments
+    /// *  `cells` - The cells of the row.
+    fn height_row(cells: &[Text<'a>]) -> u16 {
+        cells
+            .iter()
+            .map(|cell| cell.height() as u16)
+            .max()
+            .unwrap_or(0)
+            .max(1)
+    }
+
+    fn height_thematic_break(_width: u16, symbol: &str) -> u16 {
+        // A thematic break is as high as its symbol
+        symbol.lines().count().max(1) as u16
+    }
+
+    /// Calculates the height of a single line.
+    ///
+    /// This function takes wrapping of long lines into account.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the rendering area.
+    /// *  `indent` - An initial assumed indent.
+    /// *  `value` - The line for which to calculate the height.
+    /// *  `break_wide` - Whether the line may be wrapped between any two wide
+    ///    characters.
+    fn height_line(
+        width: u16,
+        indent: u16,
+        value: &[Span<'_>],
+        break_wide: bool,
+    ) -> u16 {
+        Self::wrap(width, indent, value, break_wide).len() as u16
+    }
+
+    /// The indentation of nested content that fits a width.
+    ///
+    /// Content keeps at least half of the width, so that deeply nested lists
+    /// are indented less rather than running out of room.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the rendering area.
+    /// *  `indent` - The configured indentation.
+    fn fit_indent(width: u16, indent: u16) -> u16 {
+        indent.min(width / 2)
+    }
+
+    /// Wraps a line at word boundaries to fit a width.
+    ///
+    /// Whitespace at the start and end of wrapped lines is dropped; see
+    /// [`Section::wrap_with`].
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the rendering area.
+    /// *  `indent` - The width already used on the first line.
+    /// *  `value` - The line to wrap.
+    /// *  `break_wide` - Whether the line may be wrapped between any two wide
+    ///    characters.
+    fn wrap(
+        width: u16,
+        indent: u16,
+        value: &[Span<'_>],
+        break_wide: bool,
+    ) -> Vec<Spans<'a>> {
+        Self::wrap_with(width, indent, value, true, break_wide)
+    }
+
+    /// Wraps a line at word boundaries to fit a width.
+    ///
+    /// Whitespace at the end of wrapped lines is dropped, as is whitespace at
+    /// their start if trimming, and words wider than the width are broken.
+    /// Text is both measured and rendered wrapped by this function, so that
+    /// the two always agree.
+    ///
+    /// Languages such as Chinese and Japanese are written without spaces
+    /// between words, so text in them may be wrapped between any two wide
+    /// characters when `break_wide` is set.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the rendering area.
+    /// *  `indent` - The width already used on the first line.
+    /// *  `value` - The line to wrap.
+    /// *  `trim` - Whether to drop whitespace at the start of lines.
+    /// *  `break_wide` - Whether the line may be wrapped between any two wide
+    ///    characters.
+    fn wrap_with(
+        width: u16,
+        indent: u16,
+        value: &[Span<'_>],
+        trim: bool,
+        break_wide: bool,
+    ) -> Vec<Spans<'a>> {
+        let width = width.max(1) as usize;
+        let mut lines = vec![Vec::new()];
+        let mut line_width = indent as usize;
+
+        // Split the line into words and runs of whitespace; a non-breaking
+        // space is part of a word, and a zero width space, or two wide
+        // characters if breaking between them, separate words without
+        // anything being drawn
+        let is_wide = |c: char| c.width() == Some(2);
+        let mut tokens: Vec<(bool, Vec<(char, Style)>)> = Vec::new();
+        let mut previous = None;
+        for (c, style) in value
+            .iter()
+            .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+        {
+            let wide_break =
+                break_wide && previous.is_some_and(is_wide) && is_wide(c);
+            previous = Some(c);
+            if c == '\u{200b}' || wide_break {
+                if !matches!(tokens.last(), Some((true, _))) {
+                    tokens.push((true, Vec::new()));
+                }
+                if c == '\u{200b}' {
+                    continue;
+                }
+            }
+            let space = c.is_whitespace() && c != '\u{a0}';
+            match tokens.last_mut() {
+                Some((is_space, token)) if *is_space == space => {
+                    token.push((c, style))
+                }
+                _ => tokens.push((space, vec![(c, style)])),
+            }
+        }
+
+        let mut pending: &[(char, Style)] = &[];
+        for (is_space, token) in tokens.iter() {
+            if *is_space {
+                if line_width > 0 || !trim {
+                    pending = token;
+                }
+                continue;
+            }
+            let token_width = Self::width(token);
+            let pending_width = Self::width(pending);
+            let line = lines.last_mut().unwrap();
+            if line_width + pending_width + token_width <= width {
+                line.extend_from_slice(pending);
+                line.extend_from_slice(token);
+                line_width += pending_width + token_width;
+            } else if !trim && pending_width + token_width <= width {
+                lines.push([pending, token].concat());
+                line_width = pending_width + token_width;
+            } else if token_width <= width {
+                lines.push(token.clone());
+                line_width = token_width;
+            } else {
+                if line_width > 0 {
+                    lines.push(Vec::new());
+                    line_width = 0;
+                }
+                // A character wider than the width is placed alone on a
+                // line, rather than after an empty line
+                for symbol in token {
+                    let symbol_width = Self::width(&[*symbol]);
+                    if line_width > 0 && line_width + symbol_width > width {
+                        lines.push(Vec::new());
+                        line_width = 0;
+                    }
+                    lines.last_mut().unwrap().push(*symbol);
+                    line_width += symbol_width;
+                }
+            }
+            pending = &[];
+        }
+
+        // Merge consecutive characters with the same style into spans
+        lines
+            .into_iter()
+            .map(|line| {
+                let mut spans: Vec<Span<'a>> = Vec::new();
+                for (c, style) in line {
+                    match spans.last_mut() {
+                        Some(span) if span.style == style => {
+                            span.content.to_mut().push(c)
+                        }
+                        _ => spans.push(Span::styled(c.to_string(), style)),
+                    }
+                }
+                Spans::from(spans)
+            })
+            .collect()
+    }
+
+    /// The width of a sequence of characters in terminal cells.
+    ///
+    /// # Arguments
+    /// *  `symbols` - The characters.
+    fn width(symbols: &[(char, Style)]) -> usize {
+        symbols
+            .iter()
+            .map(|(c, _)| UnicodeWidthChar::width(*c).unwrap_or(0))
+            .sum()
+    }
+
+    /// Splits an area into columns of equal width.
+    ///
+    /// The columns are separated by [`Section::COLUMN_GAP`] cells; any cells
+    /// left over are given to the leftmost columns.
+    ///
+    /// # Arguments
+    /// *  `area` - The area to split.
+    /// *  `count` - The number of columns.
+    fn column_areas(area: Rect, count: usize) -> Vec<Rect> {
+        let count = count as u16;
+        if count == 0 {
+            return Vec::new();
+        }
+        let available =
+            area.width.saturating_sub(Self::COLUMN_GAP * (count - 1));
+        let (width, remainder) = (available / count, available % count);
+        let mut x = area.x;
+        (0..count)
+            .map(|i| {
+                let width = width + u16::from(i < remainder);
+                let column = Rect { x, width, ..area };
+                x += width + Self::COLUMN_GAP;
+                column
+            })
+            .collect()
+    }
+
+    /// Determines whether a collection of spans contains non-whitespace.
+    ///
+    /// # Argument
+    /// *  `line` - The line to check.
+    fn contains_non_whitespace(line: &[Span<'a>]) -> bool {
+        line.iter()
+            .flat_map(|span| span.content.chars())
+            .any(|c| !c.is_whitespace())
+    }
+}
+
+impl<'a> Section<'a> {
+    /// Renders this section.
+    ///
+    /// # Arguments
+    /// *  `area` - The allocated area for this section.
+    /// *  `buf` - The target buffer.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        use Section::*;
+        match &self {
+            BlockQuote {
+                content,
+                indent,
+                marker,
+            } => Self::render_block_quote(area, buf, content, *indent, marker),
+            Code {
+                text,
+                language,
+                caption,
+                border,
+                ..
+            } => {
+                Self::render_code(area, buf, text, language, *caption, *border)
+            }
+            Columns { columns } => Self::render_columns(area, buf, columns),
+            Heading {
+                text,
+                level,
+                hash,
+                alignment,
+                break_wide,
+                ..
+            } => Self::render_heading(
+                area,
+                buf,
+                text,
+                level,
+                *hash,
+                *alignment,
+                *break_wide,
+            ),
+            Image { path, alt } => Self::render_image(area, buf, path, alt),
+            List { content } => Self::render_list(area, buf, content),
+            ListItemOrdered {
+                content,
+                ordinal,
+                delimiter,
+                indent,
+            } => Self::render_list_item_ordered(
+                area, buf, content, ordinal, delimiter, *indent,
+            ),
+            ListItemUnordered {
+                content,
+                bullet,
+                indent,
+            } => Self::render_list_item_unordered(
+                area, buf, content, bullet, *indent,
+            ),
+            Line {
+                text,
+                alignment,
+                break_wide,
+            } => Self::render_line(area, buf, text, *alignment, *break_wide),
+            Paragraph {
+                text,
+                wrap,
+                ellipsis,
+                break_wide,
+            } => Self::render_paragraph(
+                area,
+                buf,
+                text,
+                wrap,
+                ellipsis,
+                *break_wide,
+            ),
+            Table {
+                header,
+                rows,
+                options,
+            } => Self::render_table(area, buf, header, rows, options),
+            ThematicBreak {
+                symbol,
+                full_width,
+                style,
+            } => Self::render_thematic_break(
+                area,
+                buf,
+                symbol,
+                *full_width,
+                *style,
+            ),
+        }
+    }
+
+    fn render_block_quote(
+        area: Rect,
+        buf: &mut Buffer,
+        content: &Sections<'a>,
+        indent: u16,
+        marker: &str,
+    ) {
+        let head = if marker.is_empty() { 0 } else { 1 };
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(head),
+                    Constraint::Max(area.height),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+        Paragraph::new(marker).render(parts[0], buf);
+        Paragraph::new("<<<").render(parts[2], buf);
+        let parts = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Length(Self::fit_indent(
+                        area.width,
+                        indent / 2,
+                    )),
+                    Constraint::Max(area.width),
+                ]
+                .as_ref(),
+            )
+            .split(parts[1]);
+        content.render(parts[1], buf);
+    }
+
+    fn render_code(
+        area: Rect,
+        buf: &mut Buffer,
+        text: &Text<'a>,
+        language: &Option<String>,
+        caption: bool,
+        border: bool,
+    ) {
+        let mut paragraph = Paragraph::new(text.clone());
+        if border {
+            // The language is shown in the top border, to the right if shown
+            // as a caption
+            let mut block = Block::default().borders(Borders::ALL);
+            if let Some(language) = language {
+                block = if caption {
+                    block
+                        .title(Span::styled(
+                            language.clone(),
+                            Style::default().add_modifier(Modifier::ITALIC),
+                        ))
+                        .title_alignment(Alignment::Right)
+                } else {
+                    block.title(language.clone())
+                };
+            }
+            paragraph = paragraph.block(block);
+            paragraph.render(area, buf);
+        } else if let Some(language) = language.as_ref().filter(|_| caption) {
+            let caption = Span::styled(
+                language.clone(),
+                Style::default().add_modifier(Modifier::ITALIC),
+            );
+            // The caption is shown on a line of its own above the code
+            let caption_area = Rect {
+                height: area.height.min(1),
+                ..area
+            };
+            Paragraph::new(caption)
+                .alignment(Alignment::Right)
+                .render(caption_area, buf);
+            paragraph.render(
+                Rect {
+                    y: area.y + caption_area.height,
+                    height: area.height - caption_area.height,
+                    ..area
+                },
+                buf,
+            );
+        } else {
+            paragraph.render(area, buf);
+        }
+    }
+
+    fn render_columns(area: Rect, buf: &mut Buffer, columns: &[Sections<'a>]) {
+        for (column, area) in
+            columns.iter().zip(Self::column_areas(area, columns.len()))
+        {
+            column.render(area, buf);
+        }
+    }
+
+    fn render_heading(
+        area: Rect,
+        buf: &mut Buffer,
         text: &Spans<'a>,
         level: &u8,
+        hash: bool,
+        alignment: Alignment,
+        break_wide: bool,
     ) {
-        Paragraph::new({
-            let mut text = text.clone();
-            text.0.insert(
+        let mut text = text.clone();
+        if hash {
+            text.0
+                .insert(0, Span::raw("#".repeat(*level as usize) + " "));
+        }
+        Paragraph::new(Self::wrap(area.width, 0, &text.0, break_wide))
+            .alignment(alignment)
+            .render(area, buf);
+    }
+
+    fn render_image(area: Rect, buf: &mut Buffer, path: &str, alt: &str) {
+        if !image::place(path, area) {
+            Paragraph::new(Self::wrap(
+                area.width,
                 0,
-                Span::raw(
-                    repeat('#').take(*level as usize).collect::<String>() + " ",
-                ),
-            );
-            text
-        })
-        .wrap(Wrap { trim: true })
+                &[Span::styled(
+                    alt.to_string(),
+                    Style::default().add_modifier(Modifier::ITALIC),
+                )],
+                false,
+            ))
+            .render(area, buf);
+        }
+    }
+
+    fn render_list(area: Rect, buf: &mut Buffer, content: &Sections<'a>) {
+        content.render(area, buf);
+    }
+
+    fn render_list_item_ordered(
+        area: Rect,
+        buf: &mut Buffer,
+        content: &Sections<'a>,
+        ordinal: &usize,
+        delimiter: &char,
+        indent: u16,
+    ) {
+        let parts = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Length(Self::fit_indent(area.width, indent)),
+                    Constraint::Max(area.width),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+        Paragraph::new(format!("{}{}", ordinal, delimiter))
+            .render(parts[0], buf);
+        content.render(parts[1], buf);
+    }
+
+    fn render_list_item_unordered(
+        area: Rect,
+        buf: &mut Buffer,
+        content: &Sections<'a>,
+        bullet: &char,
+        indent: u16,
+    ) {
+        let parts = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Length(Self::fit_indent(area.width, indent)),
+                    Constraint::Max(area.width),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+        Paragraph::new(format!("{}", bullet)).render(parts[0], buf);
+        content.render(parts[1], buf);
+    }
+
+    fn render_line(
+        area: Rect,
+        buf: &mut Buffer,
+        text: &Spans<'a>,
+        alignment: Alignment,
+        break_wide: bool,
+    ) {
+        Paragraph::new(Self::wrap(area.width, 0, &text.0, break_wide))
+            .alignment(alignment)
+            .render(area, buf);
+    }
+
+    fn render_paragraph(
+        area: Rect,
+        buf: &mut Buffer,
+        text: &Text<'a>,
+        wrap: &Option<Wrap>,
+        ellipsis: &str,
+        break_wide: bool,
+    ) {
+        if !text
+            .lines
+            .iter()
+            .any(|line| Self::contains_non_whitespace(&line.0))
+        {
+            return;
+        }
+        match wrap {
+            Some(wrap) => Paragraph::new(
+                text.lines
+                    .iter()
+                    .flat_map(|line| {
+                        Self::wrap_with(
+                            area.width, 0, &line.0, wrap.trim, break_wide,
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            None => Paragraph::new(
+                text.lines
+                    .iter()
+                    .map(|line| Self::truncate(area.width, &line.0, ellipsis))
+                    .collect::<Vec<_>>(),
+            ),
+        }
         .render(area, buf);
     }
 
-    fn render_list(area: Rect, buf: &mut Buffer, content: &Sections<'a>) {
-        content.render(area, buf);
+    fn render_table(
+        area: Rect,
+        buf: &mut Buffer,
+        header: &[Text<'a>],
+        rows: &[Vec<Text<'a>>],
+        options: &TableOptions,
+    ) {
+        let TableOptions {
+            stripe,
+            border,
+            ref widths,
+            ref ellipsis,
+        } = *options;
+        if header.is_empty() {
+            return;
+        }
+
+        // The table may be given more space than it needs, so we limit the
+        // area to its height to keep the border around the rows
+        let area = Rect {
+            height: area
+                .height
+                .min(Self::height_table(area.width, header, rows, border)),
+            ..area
+        };
+
+        // The widths of the columns are calculated as by the table, so that
+        // cells too wide for their column can be cut off with an ellipsis
+        let block = Block::default().borders(if border {
+            Borders::ALL
+        } else {
+            Borders::NONE
+        });
+        let widths = Self::column_widths(block.inner(area).width, widths);
+        let row = |cells: &[Text<'a>]| {
+            Row::new(cells.iter().zip(&widths).map(|(cell, width)| {
+                Cell::from(Text::from(
+                    cell.lines
+                        .iter()
+                        .map(|line| Self::truncate(*width, &line.0, ellipsis))
+                        .collect::<Vec<_>>(),
+                ))
+            }))
+            .height(Self::height_row(cells))
+        };
+        let constraints = widths
+            .iter()
+            .map(|width| Constraint::Length(*width))
+            .collect::<Vec<_>>();
+        Table::new(rows.iter().enumerate().map(|(i, cells)| match stripe {
+            Some(stripe) if i % 2 == 1 => row(cells).style(stripe),
+            _ => row(cells),
+        }))
+        .header(
+            row(header)
+                .style(Style::default().add_modifier(Modifier::UNDERLINED)),
+        )
+        .widths(&constraints)
+        .block(block)
+        .render(area, buf);
+    }
+
+    /// The widths of the columns of a table.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the table, excluding any border.
+    /// *  `widths` - The widths of the columns.
+    fn column_widths(width: u16, widths: &[Constraint]) -> Vec<u16> {
+        // The table does not stretch the last column to fill its width, which
+        // is done here by letting an extra column take any remaining space
+        let mut constraints = widths
+            .iter()
+            .flat_map(|width| [*width, Constraint::Length(1)])
+            .collect::<Vec<_>>();
+        constraints.pop();
+        constraints.push(Constraint::Min(0));
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(constraints)
+            .split(Rect::new(0, 0, width, 1))
+            .iter()
+            .step_by(2)
+            .map(|column| column.width)
+            .collect()
+    }
+
+    /// Cuts a line off to fit a width.
+    ///
+    /// A line that is cut off ends with the ellipsis, in the style of the
+    /// last character kept; whitespace before the ellipsis is dropped.
+    ///
+    /// # Arguments
+    /// *  `width` - The width to fit.
+    /// *  `value` - The line.
+    /// *  `ellipsis` - The text ending a line that is cut off.
+    fn truncate(width: u16, value: &[Span<'a>], ellipsis: &str) -> Spans<'a> {
+        let symbols = value
+            .iter()
+            .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+            .collect::<Vec<_>>();
+        let width = width as usize;
+        if Self::width(&symbols) <= width {
+            return Spans::from(value.to_vec());
+        }
+
+        let available = width.saturating_sub(UnicodeWidthStr::width(ellipsis));
+        let mut used = 0;
+        let mut kept = symbols
+            .iter()
+            .take_while(|symbol| {
+                used += Self::width(&[**symbol]);
+                used <= available
+            })
+            .collect::<Vec<_>>();
+        while kept.last().map(|(c, _)| c.is_whitespace()).unwrap_or(false) {
+            kept.pop();
+        }
+        let style = kept
+            .last()
+            .or(symbols.first().as_ref())
+            .map(|(_, style)| *style)
+            .unwrap_or_default();
+        let mut spans: Vec<Span<'a>> = Vec::new();
+        for (c, style) in kept {
+            match spans.last_mut() {
+                Some(span) if span.style == *style => {
+                    span.content.to_mut().push(*c)
+                }
+                _ => spans.push(Span::styled(c.to_string(), *style)),
+            }
+        }
+        spans.push(Span::styled(ellipsis.to_string(), style));
+        Spans::from(spans)
+    }
+
+    fn render_thematic_break(
+        area: Rect,
+        buf: &mut Buffer,
+        symbol: &str,
+        full_width: bool,
+        style: Style,
+    ) {
+        let text = if full_width {
+            Text::from(
+                symbol
+                    .lines()
+                    .map(|line| {
+                        line.chars()
+                            .cycle()
+                            .take(area.width as usize)
+                            .collect::<String>()
+                    })
+                    .map(Spans::from)
+                    .collect::<Vec<_>>(),
+            )
+        } else {
+            Text::from(symbol.to_string())
+        };
+        Paragraph::new(text)
+            .style(style)
+            .alignment(Alignment::Center)
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tui::style::Color;
+
+    use super::*;
+    use crate::configuration::{self, HeadingMarker};
+    use crate::testing;
+
+    #[test]
+    fn paginate() {
+        let pages = testing::load_pages("test-resources/tall.md");
+        let page = PageWidget::new(
+            &pages[0],
+            &Configuration::default(),
+            &Footnotes::default(),
+        );
+
+        assert_eq!(7, page.sections.height(40));
+        assert_eq!(1, page.paginate(40, 7).len());
+
+        let paginated = page.paginate(40, 4);
+        assert_eq!(2, paginated.len());
+        assert_eq!(3, paginated[0].sections.height(40));
+        assert_eq!(3, paginated[1].sections.height(40));
+        assert!(paginated
+            .iter()
+            .all(|page| page.notes() == ["A note".to_string()]));
+    }
+
+    #[test]
+    fn overflow_marker() {
+        let pages = testing::load_pages("test-resources/tall.md");
+        let page = PageWidget::new(
+            &pages[0],
+            &Configuration::default(),
+            &Footnotes::default(),
+        );
+
+        let area = Rect::new(0, 0, 40, 7);
+        let mut buf = Buffer::empty(area);
+        page.render(area, &mut buf);
+        assert_eq!(" ", buf.get(34, 6).symbol);
+
+        let area = Rect::new(0, 0, 40, 4);
+        let mut buf = Buffer::empty(area);
+        page.render(area, &mut buf);
+        assert_eq!("▾", buf.get(34, 3).symbol);
+        assert_eq!("e", buf.get(39, 3).symbol);
+    }
+
+    #[test]
+    fn scroll() {
+        let pages = testing::load_pages("test-resources/tall.md");
+        let page = PageWidget::new(
+            &pages[0],
+            &Configuration::default(),
+            &Footnotes::default(),
+        );
+
+        assert_eq!(0, page.max_scroll(40, 10));
+        assert_eq!(3, page.max_scroll(40, 4));
+
+        let area = Rect::new(0, 0, 40, 4);
+        let mut buf = Buffer::empty(area);
+        page.scrolled(100).render(area, &mut buf);
+        assert_eq!("S", buf.get(0, 1).symbol);
+        assert_eq!("T", buf.get(0, 3).symbol);
+        assert_eq!("h", buf.get(1, 3).symbol);
+    }
+
+    #[test]
+    fn thematic_break() {
+        let section = Section::ThematicBreak {
+            symbol: "* * *".into(),
+            full_width: false,
+            style: Style::default().fg(Color::Red),
+        };
+        let area = Rect::new(0, 0, 11, 1);
+        let mut buf = Buffer::empty(area);
+        section.render(area, &mut buf);
+
+        assert_eq!(1, section.height(11));
+        assert_eq!("   * * *   ", testing::rows(&buf).concat());
+        assert_eq!(Color::Red, buf.get(3, 0).fg);
+
+        let section = Section::ThematicBreak {
+            symbol: "-=\n=-".into(),
+            full_width: true,
+            style: Style::default(),
+        };
+        let area = Rect::new(0, 0, 5, 2);
+        let mut buf = Buffer::empty(area);
+        section.render(area, &mut buf);
+
+        assert_eq!(2, section.height(5));
+        assert_eq!("-=-=-=-=-=", testing::rows(&buf).concat());
+    }
+
+    #[test]
+    fn heading_alignment() {
+        let pages = testing::load_pages("test-resources/headings.md");
+        let mut configuration = Configuration {
+            heading_marker: HeadingMarker::None,
+            ..Default::default()
+        };
+        configuration.headings.h1.alignment = configuration::Alignment::Center;
+
+        let (sections, _) = Sections::from_page(&pages[0], &configuration);
+        let render = |section: &Section| {
+            let area = Rect::new(0, 0, 11, 1);
+            let mut buf = Buffer::empty(area);
+            section.render(area, &mut buf);
+            testing::rows(&buf).concat()
+        };
+        assert_eq!("  Level 1  ", render(&sections[0]));
+        assert_eq!("Level 2    ", render(&sections[2]));
+    }
+
+    #[test]
+    fn heading_without_hash() {
+        let section = Section::Heading {
+            text: Spans::from(vec![Span::raw("§ "), Span::raw("Title")]),
+            level: 2,
+            padding: (1, 0),
+            hash: false,
+            alignment: Alignment::Left,
+            break_wide: false,
+        };
+        let area = Rect::new(0, 0, 10, 1);
+        let mut buf = Buffer::empty(area);
+        section.render(area, &mut buf);
+
+        assert_eq!("§ Title   ", testing::rows(&buf).concat());
+    }
+
+    #[test]
+    fn list_indent() {
+        let pages = testing::load_pages("test-resources/lists.md");
+        let render = |indent_width: u16| {
+            let configuration = Configuration {
+                indent_width,
+                ..Default::default()
+            };
+            let (sections, _) = Sections::from_page(&pages[0], &configuration);
+            let area = Rect::new(0, 0, 12, sections[1].height(12));
+            let mut buf = Buffer::empty(area);
+            sections[1].render(area, &mut buf);
+            testing::rows(&buf)
+        };
+
+        assert_eq!(
+            vec!["-   one     ", "-   two     ", "    three   "],
+            render(4),
+        );
+        assert_eq!(vec!["- one       ", "- two three "], render(2));
+    }
+
+    #[test]
+    fn list_indent_nested() {
+        let pages = testing::load_pages("test-resources/nested-lists.md");
+        let configuration = Configuration::default();
+        let (sections, _) = Sections::from_page(&pages[0], &configuration);
+        let render = |width: u16| {
+            let area = Rect::new(0, 0, width, sections[1].height(width));
+            let mut buf = Buffer::empty(area);
+            sections[1].render(area, &mut buf);
+            testing::rows(&buf)
+        };
+
+        assert_eq!(
+            vec![
+                "-   one             ",
+                "                    ",
+                "    -   two         ",
+                "                    ",
+                "        -   three   ",
+                "                    ",
+                "            -   four",
+                "                    ",
+                "                - fi",
+                "                  ve",
+            ],
+            render(20),
+        );
+        for width in 1..20 {
+            assert!(!render(width).is_empty());
+        }
+    }
+
+    #[test]
+    fn block_quote_marker() {
+        let quote = |marker: &str| Section::BlockQuote {
+            content: Sections::from(vec![Section::Paragraph {
+                text: Text::raw("quoted"),
+                wrap: Some(Wrap { trim: true }),
+                ellipsis: "…".into(),
+                break_wide: false,
+            }]),
+            indent: 4,
+            marker: marker.into(),
+        };
+        let render = |section: &Section<'_>| {
+            let area = Rect::new(0, 0, 8, section.height(8));
+            let mut buf = Buffer::empty(area);
+            section.render(area, &mut buf);
+            testing::rows(&buf)
+        };
+
+        assert_eq!(
+            vec!["❝       ", "  quoted", "<<<     "],
+            render(&quote("❝")),
+        );
+        assert_eq!(2, quote("").height(8));
+        assert_eq!(vec!["  quoted", "<<<     "], render(&quote("")));
+    }
+
+    #[test]
+    fn table() {
+        let pages = testing::load_pages("test-resources/tables.md");
+        let (sections, _) =
+            Sections::from_page(&pages[0], &Configuration::default());
+        let render = |section: &Section<'_>| {
+            let area = Rect::new(0, 0, 16, section.height(16));
+            let mut buf = Buffer::empty(area);
+            section.render(area, &mut buf);
+            testing::rows(&buf)
+        };
+
+        assert_eq!(
+            vec![
+                "┌──────────────┐",
+                "│Name    Value │",
+                "│x       1     │",
+                "│y       2     │",
+                "└──────────────┘",
+            ],
+            render(&sections[1]),
+        );
+
+        // A table with only a header
+        assert_eq!(
+            vec!["┌──────────────┐", "│Header  Only  │", "└──────────────┘"],
+            render(&sections[2]),
+        );
+
+        // A table without columns is not shown
+        let empty = Section::Table {
+            header: Vec::new(),
+            rows: vec![Vec::new()],
+            options: TableOptions {
+                stripe: None,
+                border: true,
+                widths: Vec::new(),
+                ellipsis: "…".into(),
+            },
+        };
+        assert_eq!(0, empty.height(16));
+        render(&empty);
     }
 
-    fn render_list_item_ordered(
-        area: Rect,
-        buf: &mut Buffer,
-        content: &Sections<'a>,
-        ordinal: &usize,
-        delimiter: &char,
-    ) {
-        let parts = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(
-                [
-                    Constraint::Length(Self::INDENT),
-                    Constraint::Max(area.width),
-                ]
-                .as_ref(),
-            )
-            .split(area);
-        Paragraph::new(format!("{}{}", ordinal, delimiter))
-            .render(parts[0], buf);
-        content.render(parts[1], buf);
+    #[test]
+    fn table_widths() {
+        let pages = testing::load_pages("test-resources/tables.md");
+        let render = |table_widths: Vec<configuration::ColumnWidth>| {
+            let configuration = Configuration {
+                table_widths,
+                table_border: false,
+                ..Default::default()
+            };
+            let (sections, diagnostics) =
+                Sections::from_page(&pages[0], &configuration);
+            let area = Rect::new(0, 0, 16, sections[1].height(16));
+            let mut buf = Buffer::empty(area);
+            sections[1].render(area, &mut buf);
+            (testing::rows(&buf), diagnostics.len())
+        };
+
+        let (lines, diagnostics) = render(vec![
+            configuration::ColumnWidth::Length(4),
+            configuration::ColumnWidth::Percentage(50),
+        ]);
+        assert_eq!(
+            vec!["Name Value      ", "x    1          ", "y    2          "],
+            lines,
+        );
+        assert_eq!(0, diagnostics);
+
+        // Widths not matching the columns are ignored
+        let (lines, diagnostics) =
+            render(vec![configuration::ColumnWidth::Length(4)]);
+        assert_eq!(
+            vec!["Name     Value  ", "x        1      ", "y        2      "],
+            lines,
+        );
+        assert_eq!(0, diagnostics);
     }
 
-    fn render_list_item_unordered(
-        area: Rect,
-        buf: &mut Buffer,
-        content: &Sections<'a>,
-        bullet: &char,
-    ) {
-        let parts = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(
-                [
-                    Constraint::Length(Self::INDENT),
-                    Constraint::Max(area.width),
-                ]
-                .as_ref(),
-            )
-            .split(area);
-        Paragraph::new(format!("{}", bullet)).render(parts[0], buf);
-        content.render(parts[1], buf);
+    #[test]
+    fn column_widths() {
+        // Columns narrower than the table are not stretched to fill it
+        assert_eq!(
+            vec![4, 4],
+            Section::column_widths(
+                16,
+                &[Constraint::Length(4), Constraint::Percentage(25)],
+            ),
+        );
+        assert_eq!(
+            vec![8, 7],
+            Section::column_widths(16, &[Constraint::Ratio(1, 2); 2]),
+        );
     }
 
-    fn render_paragraph(area: Rect, buf: &mut Buffer, text: &Text<'a>) {
-        if text
-            .lines
-            .iter()
-            .any(|line| Self::contains_non_whitespace(&line.0))
-        {
-            Paragraph::new(text.clone())
-                .wrap(Wrap { trim: true })
-                .render(area, buf);
-        }
+    #[test]
+    fn table_border() {
+        let table = |border: bool| Section::Table {
+            header: vec![Text::raw("Name"), Text::raw("Value")],
+            rows: vec![vec![Text::raw("x"), Text::raw("1")]],
+            options: TableOptions {
+                stripe: None,
+                border,
+                widths: vec![Constraint::Ratio(1, 2); 2],
+                ellipsis: "…".into(),
+            },
+        };
+
+        assert_eq!(4, table(true).height(16));
+        assert_eq!(2, table(false).height(16));
+
+        let section = table(false);
+        let area = Rect::new(0, 0, 16, section.height(16));
+        let mut buf = Buffer::empty(area);
+        section.render(area, &mut buf);
+        assert_eq!(
+            "Name     Value  x        1      ",
+            testing::rows(&buf).concat(),
+        );
     }
 
-    fn render_thematic_break(area: Rect, buf: &mut Buffer) {
-        Block::default()
-            .borders(Borders::TOP)
-            .border_style(Style::default().fg(Color::White))
-            .render(area, buf);
+    #[test]
+    fn table_ellipsis() {
+        let section = Section::Table {
+            header: vec![Text::raw("Name"), Text::raw("Value")],
+            rows: vec![vec![Text::raw("long name"), Text::raw("1")]],
+            options: TableOptions {
+                stripe: None,
+                border: false,
+                widths: vec![Constraint::Length(6), Constraint::Length(5)],
+                ellipsis: "~".into(),
+            },
+        };
+        let area = Rect::new(0, 0, 12, section.height(12));
+        let mut buf = Buffer::empty(area);
+        section.render(area, &mut buf);
+        assert_eq!("Name   Valuelong~  1    ", testing::rows(&buf).concat());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn truncate() {
+        let style = Style::default().fg(Color::Red);
+        let line = vec![Span::raw("ab"), Span::styled("cdef", style)];
+
+        assert_eq!(Spans::from(line.clone()), Section::truncate(6, &line, "…"));
+        assert_eq!(
+            Spans::from(vec![
+                Span::raw("ab"),
+                Span::styled("c", style),
+                Span::styled("…", style),
+            ]),
+            Section::truncate(4, &line, "…"),
+        );
+        assert_eq!(
+            Spans::from(vec![Span::raw("..")]),
+            Section::truncate(2, &line, ".."),
+        );
+    }
+
+    #[test]
+    fn table_zebra() {
+        let stripe = Style::default().bg(Color::Indexed(236));
+        let section = Section::Table {
+            header: vec![Text::raw("Header")],
+            rows: vec![
+                vec![Text::raw("one")],
+                vec![Text::raw("two")],
+                vec![Text::raw("three")],
+            ],
+            options: TableOptions {
+                stripe: Some(stripe),
+                border: true,
+                widths: vec![Constraint::Percentage(100)],
+                ellipsis: "…".into(),
+            },
+        };
+        let area = Rect::new(0, 0, 10, section.height(10));
+        let mut buf = Buffer::empty(area);
+        section.render(area, &mut buf);
+
+        let style = |y: u16| buf.get(1, y).style();
+        assert!(style(1).add_modifier.contains(Modifier::UNDERLINED));
+        assert_ne!(Some(Color::Indexed(236)), style(2).bg);
+        assert_eq!(Some(Color::Indexed(236)), style(3).bg);
+        assert_ne!(Some(Color::Indexed(236)), style(4).bg);
+    }
+
+    #[test]
+    fn empty_paragraph_spacing() {
+        let paragraph = |text: &'static str| Section::Paragraph {
+            text: Text::raw(text),
+            wrap: Some(Wrap { trim: true }),
+            ellipsis: "…".into(),
+            break_wide: false,
+        };
+        let sections = Sections::from(vec![
+            paragraph("one"),
+            paragraph(" "),
+            paragraph("two"),
+            paragraph(""),
+        ]);
+
+        assert_eq!(
+            Sections::from(vec![paragraph("one"), paragraph("two")]).height(10),
+            sections.height(10),
+        );
+        assert_eq!(3, sections.height(10));
+
+        let area = Rect::new(0, 0, 4, 4);
+        let mut buf = Buffer::empty(area);
+        sections.render(area, &mut buf);
+        assert_eq!("one     two     ", testing::rows(&buf).concat());
+    }
+
+    #[test]
+    fn columns() {
+        let column = |text: &'static str| {
+            Sections::from(vec![Section::Paragraph {
+                text: Text::raw(text),
+                wrap: Some(Wrap { trim: true }),
+                ellipsis: "…".into(),
+                break_wide: false,
+            }])
+        };
+        let section = Section::Columns {
+            columns: vec![column("left"), column("right side")],
+        };
+        assert_eq!(2, section.height(12));
+
+        let area = Rect::new(0, 0, 12, 2);
+        let mut buf = Buffer::empty(area);
+        section.render(area, &mut buf);
+        assert_eq!(vec!["left   right", "       side "], testing::rows(&buf));
+    }
+
+    #[test]
+    fn code_border() {
+        let code = |border: bool| Section::Code {
+            text: Text::raw("one\ntwo"),
+            code: "one\ntwo".into(),
+            language: Some("rust".into()),
+            caption: false,
+            border,
+            indent: 4,
+        };
+        assert_eq!(2, code(false).height(10));
+        assert_eq!(4, code(true).height(10));
+    }
+
+    #[test]
+    fn code_border_language() {
+        let section = Section::Code {
+            text: Text::raw("one"),
+            code: "one".into(),
+            language: Some("rust".into()),
+            caption: false,
+            border: true,
+            indent: 4,
+        };
+        let area = Rect::new(0, 0, 8, 3);
+        let mut buf = Buffer::empty(area);
+        section.render(area, &mut buf);
+        assert_eq!("┌rust──┐│one   │└──────┘", testing::rows(&buf).concat());
+    }
+
+    #[test]
+    fn code_caption() {
+        let render = |language: Option<&str>, border: bool| {
+            let section = Section::Code {
+                text: Text::raw("one"),
+                code: "one".into(),
+                language: language.map(String::from),
+                caption: true,
+                border,
+                indent: 4,
+            };
+            let area = Rect::new(0, 0, 8, section.height(8));
+            let mut buf = Buffer::empty(area);
+            section.render(area, &mut buf);
+            testing::rows(&buf).concat()
+        };
+        assert_eq!("┌──rust┐│one   │└──────┘", render(Some("rust"), true));
+        assert_eq!("┌──────┐│one   │└──────┘", render(None, true));
+        assert_eq!("    rustone     ", render(Some("rust"), false));
+        assert_eq!("one     ", render(None, false));
+    }
 
     #[test]
     fn height_line() {
-        assert_eq!(1, Section::height_line(10, 0, &["one".into()]));
-        assert_eq!(2, Section::height_line(10, 0, &["one two three".into()]));
+        assert_eq!(1, Section::height_line(10, 0, &["one".into()], false));
+        assert_eq!(
+            2,
+            Section::height_line(10, 0, &["one two three".into()], false)
+        );
         assert_eq!(
             2,
-            Section::height_line(10, 0, &["one two".into(), " three".into()]),
+            Section::height_line(
+                10,
+                0,
+                &["one two".into(), " three".into()],
+                false
+            ),
         );
         assert_eq!(
             5,
             Section::height_line(
                 10,
                 0,
-                &["a long wooooooooooooooooooooooooooooooooooord".into()]
+                &["a long wooooooooooooooooooooooooooooooooooord".into()],
+                false,
             )
         );
+
+        // Words exactly filling a line
+        assert_eq!(1, Section::height_line(3, 0, &["one".into()], false));
+        assert_eq!(
+            1,
+            Section::height_line(10, 0, &["0123456789".into()], false)
+        );
+        assert_eq!(
+            2,
+            Section::height_line(7, 0, &["one two three".into()], false)
+        );
+        assert_eq!(1, Section::height_line(7, 0, &["one two  ".into()], false));
+
+        // Words wider than the width, and narrow widths
+        let word = "w".repeat(100);
+        assert_eq!(
+            10,
+            Section::height_line(10, 0, &[word.as_str().into()], false)
+        );
+        assert_eq!(
+            15,
+            Section::height_line(7, 0, &[word.as_str().into()], false)
+        );
+        assert_eq!(
+            100,
+            Section::height_line(1, 0, &[word.as_str().into()], false)
+        );
+        assert_eq!(
+            100,
+            Section::height_line(0, 0, &[word.as_str().into()], false)
+        );
+        assert_eq!(3, Section::height_line(1, 0, &["a b c".into()], false));
+        // An indent wider than the width fills the first line
+        assert_eq!(3, Section::height_line(1, 5, &["ab".into()], false));
+
+        // Whitespace only
+        assert_eq!(1, Section::height_line(10, 0, &["     ".into()], false));
+        assert_eq!(
+            1,
+            Section::height_line(1, 0, &[" ".repeat(100).into()], false)
+        );
+        assert_eq!(1, Section::height_line(1, 0, &["".into()], false));
+
+        // Wide characters
+        assert_eq!(2, Section::height_line(4, 0, &["日本語".into()], false));
+        assert_eq!(3, Section::height_line(1, 0, &["日本語".into()], false));
+        assert_eq!(3, Section::height_line(1, 0, &["a日b".into()], false));
+        assert_eq!(
+            vec!["ab 日", "本語"],
+            Section::wrap(6, 0, &["ab 日本語".into()], true)
+                .iter()
+                .map(|line| line.0.iter().map(|s| s.content.as_ref()).collect())
+                .collect::<Vec<String>>(),
+        );
+
+        // The heights agree with the wrapping of tui
+        for (width, text) in [
+            (3, "one"),
+            (7, "one two three"),
+            (10, "a long wooooooooooooooooooooooooooooooooooord"),
+            (5, "   leading and trailing   "),
+            (6, "x\u{a0}y\u{a0}z\u{a0}w"),
+            (8, "mixed 日本語 text"),
+        ] {
+            let area = Rect::new(0, 0, width, 20);
+            let mut buf = Buffer::empty(area);
+            Paragraph::new(text)
+                .wrap(tui::widgets::Wrap { trim: true })
+                .render(area, &mut buf);
+            let rows = (0..area.height)
+                .filter(|y| {
+                    (0..area.width).any(|x| buf.get(x, *y).symbol != " ")
+                })
+                .max()
+                .map_or(0, |y| y + 1);
+            assert_eq!(
+                rows,
+                Section::height_line(width, 0, &[text.into()], false),
+                "{:?} at width {}",
+                text,
+                width,
+            );
+        }
+    }
+
+    #[test]
+    fn footnotes_shared() {
+        let presentation = testing::load("test-resources/footnotes.md");
+        let pages = testing::pages(&presentation);
+        let configuration = Configuration::default();
+        let footnotes =
+            Footnotes::from_presentation(&presentation, &configuration);
+        let first = PageWidget::new(&pages[0], &configuration, &footnotes);
+        let second = PageWidget::new(&pages[1], &configuration, &footnotes);
+
+        assert_eq!(2, first.footnotes.footnotes.len());
+        assert_eq!(1, second.footnotes.footnotes.len());
+        assert!(Rc::ptr_eq(
+            &first.footnotes.footnotes[0].2,
+            &second.footnotes.footnotes[0].2,
+        ));
+
+        // A page split in parts lists the footnotes on the parts referencing
+        // them
+        let paginated = first.paginate(40, 4);
+        assert_eq!(2, paginated.len());
+        assert!(paginated[0].footnotes.is_empty());
+        assert_eq!(2, paginated[1].footnotes.footnotes.len());
+        assert!(Rc::ptr_eq(
+            &paginated[1].footnotes.footnotes[0].2,
+            &first.footnotes.footnotes[0].2,
+        ));
+
+        let area = Rect::new(0, 0, 40, 8);
+        let mut buf = Buffer::empty(area);
+        first.render(area, &mut buf);
+        assert_eq!("¹", buf.get(0, 6).symbol);
+        assert_eq!("T", buf.get(4, 6).symbol);
+        assert_eq!("²", buf.get(0, 7).symbol);
+        assert_eq!("A", buf.get(4, 7).symbol);
+    }
+
+    #[test]
+    fn footnotes_overflow() {
+        let presentation =
+            testing::load("test-resources/footnotes-overflow.md");
+        let pages = testing::pages(&presentation);
+        let configuration = Configuration::default();
+        let footnotes =
+            Footnotes::from_presentation(&presentation, &configuration);
+        let page = PageWidget::new(&pages[0], &configuration, &footnotes);
+
+        let area = Rect::new(0, 0, 30, 3);
+        let mut buf = Buffer::empty(area);
+        (&page.footnotes).render(area, &mut buf);
+        assert_eq!("¹   The first footnote.       ", testing::rows(&buf)[0]);
+        assert_eq!("³   The third footnote.       ", testing::rows(&buf)[2]);
+
+        let area = Rect::new(0, 0, 30, 2);
+        let mut buf = Buffer::empty(area);
+        (&page.footnotes).render(area, &mut buf);
+        assert_eq!("¹   The first footnote.       ", testing::rows(&buf)[0]);
+        assert_eq!("… 2 more                      ", testing::rows(&buf)[1]);
+
+        // The footnotes are given at most half of the page when the content
+        // does not fit
+        let area = Rect::new(0, 0, 30, 5);
+        let mut buf = Buffer::empty(area);
+        page.render(area, &mut buf);
+        assert_eq!("¹   The first footnote.       ", testing::rows(&buf)[3]);
+        assert_eq!("… 2 more                      ", testing::rows(&buf)[4]);
+    }
+
+    #[test]
+    fn footnote_rule() {
+        let presentation = testing::load("test-resources/footnotes.md");
+        let pages = testing::pages(&presentation);
+        let configuration = Configuration {
+            footnote_rule: true,
+            ..Default::default()
+        };
+        let footnotes =
+            Footnotes::from_presentation(&presentation, &configuration);
+        let page = PageWidget::new(&pages[0], &configuration, &footnotes);
+
+        assert_eq!(3, page.footnotes.height(30));
+        let area = Rect::new(0, 0, 30, 8);
+        let mut buf = Buffer::empty(area);
+        page.render(area, &mut buf);
+        assert_eq!("─".repeat(30), testing::rows(&buf)[5]);
+        assert_eq!("¹   The source of the claim.  ", testing::rows(&buf)[6]);
+        assert_eq!("²   A remark.                 ", testing::rows(&buf)[7]);
+    }
+
+    #[test]
+    fn footnotes_rich() {
+        let presentation = testing::load("test-resources/footnotes-rich.md");
+        let pages = testing::pages(&presentation);
+        let configuration = Configuration::default();
+        let footnotes =
+            Footnotes::from_presentation(&presentation, &configuration);
+        let page = PageWidget::new(&pages[0], &configuration, &footnotes);
+
+        // The code block ends with an empty line
+        assert_eq!(7, page.footnotes.height(40));
+        assert_eq!(3 + 1 + 7, page.height(40));
+        let area = Rect::new(0, 0, 40, 16);
+        let mut buf = Buffer::empty(area);
+        page.render(area, &mut buf);
+        assert_eq!(
+            vec![
+                "¹   The claim is supported by:          ",
+                "                                        ",
+                "    *   a list                          ",
+                "    *   of reasons                      ",
+                "                                        ",
+                "    and some code                       ",
+                "                                        ",
+            ],
+            testing::rows(&buf)[9..16],
+        );
+
+        // The footnotes take up more than half of the page when everything
+        // fits
+        let area = Rect::new(0, 0, 40, 11);
+        let mut buf = Buffer::empty(area);
+        page.render(area, &mut buf);
+        assert_eq!(
+            "    and some code                       ",
+            testing::rows(&buf)[9],
+        );
+    }
+
+    #[test]
+    fn background() {
+        let render = |background: &BackgroundConfig| {
+            let area = Rect::new(1, 1, 5, 3);
+            let mut buf = Buffer::empty(Rect::new(0, 0, 6, 4));
+            BackgroundWidget(background).render(area, &mut buf);
+            buf
+        };
+
+        let buf = render(&BackgroundConfig {
+            color: Some(Color::Blue),
+            ..Default::default()
+        });
+        assert_eq!(Color::Reset, buf.get(0, 0).bg);
+        assert_eq!(Color::Blue, buf.get(1, 1).bg);
+        assert_eq!(Color::Blue, buf.get(5, 3).bg);
+        assert_eq!("      ", testing::rows(&buf)[1]);
+
+        let buf = render(&BackgroundConfig {
+            pattern: "ab\nc".into(),
+            pattern_color: Some(Color::DarkGray),
+            ..Default::default()
+        });
+        assert_eq!(
+            vec!["      ", " ababa", " ccccc", " ababa"],
+            testing::rows(&buf),
+        );
+        assert_eq!(Color::Reset, buf.get(1, 1).bg);
+        assert_eq!(Color::DarkGray, buf.get(1, 1).fg);
+        assert_eq!(Color::Reset, buf.get(0, 1).fg);
+    }
+
+    #[test]
+    fn background_behind_page() {
+        let pages = testing::load_pages("test-resources/tall.md");
+        let page = PageWidget::new(
+            &pages[0],
+            &Configuration::default(),
+            &Footnotes::default(),
+        );
+        let render = |offset: u16| {
+            let area = Rect::new(0, 0, 8, 9);
+            let mut buf = Buffer::empty(area);
+            BackgroundWidget(&BackgroundConfig {
+                pattern: "·".into(),
+                ..Default::default()
+            })
+            .render(area, &mut buf);
+            page.scrolled(offset).render(area, &mut buf);
+            testing::rows(&buf)
+        };
+
+        // The pattern is hidden on the lines of the content only, also when
+        // scrolled
+        assert_eq!(
+            vec![
+                "# A tall",
+                "page    ",
+                "········",
+                "First   ",
+                "paragrap",
+                "h.      ",
+                "········",
+                "Second  ",
+                "··▾ more",
+            ],
+            render(0),
+        );
+        assert_eq!(
+            vec![
+                "········",
+                "First   ",
+                "paragrap",
+                "h.      ",
+                "········",
+                "Second  ",
+                "paragrap",
+                "h.      ",
+                "··▾ more",
+            ],
+            render(2),
+        );
+    }
+
+    #[test]
+    fn background_behind_footnotes() {
+        let presentation = testing::load("test-resources/footnotes.md");
+        let pages = testing::pages(&presentation);
+        let configuration = Configuration::default();
+        let footnotes =
+            Footnotes::from_presentation(&presentation, &configuration);
+        let page = PageWidget::new(&pages[0], &configuration, &footnotes);
+        let area = Rect::new(0, 0, 30, 8);
+        let mut buf = Buffer::empty(area);
+        BackgroundWidget(&BackgroundConfig {
+            pattern: "·".into(),
+            ..Default::default()
+        })
+        .render(area, &mut buf);
+        page.render(area, &mut buf);
+
+        assert_eq!(
+            vec![
+                "······························",
+                "¹   The source of the claim.  ",
+                "²   A remark.                 ",
+            ],
+            testing::rows(&buf)[5..8],
+        );
+    }
+
+    #[test]
+    fn wrap_wide_characters() {
+        let pages = testing::load_pages("test-resources/cjk.md");
+        let footnotes = Footnotes::default();
+        let render = |language: Option<&str>| {
+            let configuration = Configuration {
+                language: language.map(String::from),
+                ..Default::default()
+            };
+            let page = PageWidget::new(&pages[0], &configuration, &footnotes);
+            let area = Rect::new(0, 0, 12, page.height(12));
+            let mut buf = Buffer::empty(area);
+            page.render(area, &mut buf);
+            // The cells following wide characters are skipped
+            (2..area.height)
+                .map(|y| {
+                    let mut row = String::new();
+                    let mut x = 0;
+                    while x < area.width {
+                        let symbol = &buf.get(x, y).symbol;
+                        row.push_str(symbol);
+                        x += symbol
+                            .chars()
+                            .next()
+                            .and_then(|c| c.width())
+                            .unwrap_or(1)
+                            .max(1) as u16;
+                    }
+                    row.trim_end().to_string()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec!["長い", "日本語の文章", "を折り返しま", "す。"],
+            render(None),
+        );
+        assert_eq!(
+            vec!["長い 日本語", "の文章を折り", "返します。"],
+            render(Some("ja")),
+        );
+        assert_eq!(render(Some("ja")), render(Some("zh-TW")));
+        assert_eq!(render(None), render(Some("en")));
+    }
+
+    #[test]
+    fn metadata() {
+        let configuration = Configuration {
+            title: "Title".into(),
+            subtitle: Some("Subtitle".into()),
+            author: Some("Author".into()),
+            date: Some("Date".into()),
+            ..Default::default()
+        };
+        let page = PageWidget::metadata(&configuration);
+        assert_eq!(PageLayout::Title, page.page_layout());
+        assert_eq!(vec![(1, "Title".to_string())], page.headings());
+
+        let area = Rect::new(0, 0, 12, page.height(12));
+        let mut buf = Buffer::empty(area);
+        page.render(area, &mut buf);
+        let rows = testing::rows(&buf);
+        assert_eq!(
+            vec![
+                "    Title   ",
+                "            ",
+                "  Subtitle  ",
+                "            ",
+                "   Author   ",
+                "            ",
+                "    Date    ",
+            ],
+            rows,
+        );
+
+        let page = PageWidget::metadata(&Configuration {
+            title: "Title".into(),
+            date: Some("Date".into()),
+            ..Default::default()
+        });
+        assert_eq!(3, page.height(12));
+    }
+
+    #[test]
+    fn paragraph_wrap() {
+        let render = |wrap: Option<Wrap>| {
+            let section = Section::Paragraph {
+                text: Text::raw("one  two three"),
+                wrap,
+                ellipsis: "…".into(),
+                break_wide: false,
+            };
+            let area = Rect::new(0, 0, 5, section.height(5));
+            let mut buf = Buffer::empty(area);
+            section.render(area, &mut buf);
+            testing::rows(&buf)
+        };
+
+        assert_eq!(
+            vec!["one  ", "two  ", "three"],
+            render(Some(Wrap { trim: true })),
+        );
+        assert_eq!(
+            vec!["one  ", "  two", "three"],
+            render(Some(Wrap { trim: false })),
+        );
+        assert_eq!(vec!["one… "], render(None));
+
+        // The configuration decides how paragraphs are wrapped
+        let pages = testing::load_pages("test-resources/footnotes.md");
+        for (paragraph_wrap, paragraph_trim, expected) in [
+            (true, true, Some(true)),
+            (true, false, Some(false)),
+            (false, true, None),
+            (false, false, None),
+        ] {
+            let configuration = Configuration {
+                paragraph_wrap,
+                paragraph_trim,
+                ..Default::default()
+            };
+            let (sections, _) = Sections::from_page(&pages[0], &configuration);
+            assert!(sections.iter().any(|section| matches!(
+                section,
+                Section::Paragraph { wrap, .. }
+                    if wrap.map(|wrap| wrap.trim) == expected
+            )));
+        }
+    }
+
+    #[test]
+    fn height_cache() {
+        let sections = Sections::from(vec![Section::Paragraph {
+            text: Text::raw("one two three"),
+            wrap: Some(Wrap { trim: true }),
+            ellipsis: "…".into(),
+            break_wide: false,
+        }]);
+
+        assert_eq!(2, sections.height(10));
+        assert_eq!(vec![2], sections.heights(10, || unreachable!()).to_vec());
+        assert_eq!(1, sections.height(40));
+        assert_eq!(vec![1], sections.heights(40, || unreachable!()).to_vec());
+        assert_eq!(vec![3], sections.heights(10, || vec![3]).to_vec());
+    }
+
+    #[test]
+    fn footnotes_across_pages() {
+        let presentation = testing::load("test-resources/footnotes-across.md");
+        let pages = testing::pages(&presentation);
+        let configuration = Configuration::default();
+        let footnotes =
+            Footnotes::from_presentation(&presentation, &configuration);
+        let widgets = pages
+            .iter()
+            .map(|page| PageWidget::new(page, &configuration, &footnotes))
+            .collect::<Vec<_>>();
+
+        assert_eq!(3, widgets.len());
+        assert_eq!(2, widgets[0].sections.len());
+        assert_eq!(
+            vec![vec![0], vec![], vec![0]],
+            widgets
+                .iter()
+                .map(|widget| widget
+                    .footnotes
+                    .footnotes
+                    .iter()
+                    .map(|(_, index, _)| *index)
+                    .collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+        );
+
+        let area = Rect::new(0, 0, 40, 6);
+        let mut buf = Buffer::empty(area);
+        (&widgets[2]).render(area, &mut buf);
+        assert_eq!("¹", buf.get(0, 5).symbol);
+        assert_eq!("T", buf.get(4, 5).symbol);
+    }
+
+    #[test]
+    fn footnote_numbering() {
+        let presentation =
+            testing::load("test-resources/footnote-numbering.md");
+        let pages = testing::pages(&presentation);
+        let mut configuration = Configuration::default();
+        let footnotes =
+            Footnotes::from_presentation(&presentation, &configuration);
+        let text = |page: &PageWidget| match &page.sections[1] {
+            Section::Paragraph { text, .. } => text.lines[0]
+                .0
+                .iter()
+                .map(|span| span.content.clone())
+                .collect::<String>(),
+            _ => panic!("expected a paragraph"),
+        };
+        let indices = |page: &PageWidget| {
+            page.footnotes
+                .footnotes
+                .iter()
+                .map(|(_, index, _)| *index)
+                .collect::<Vec<_>>()
+        };
+
+        let page = PageWidget::new(&pages[1], &configuration, &footnotes);
+        assert_eq!("Two² and three¹.", text(&page));
+        assert_eq!(vec![1, 0], indices(&page));
+
+        configuration.footnote_numbering = FootnoteNumbering::PerPage;
+        let page = PageWidget::new(&pages[1], &configuration, &footnotes);
+        assert_eq!("Two¹ and three².", text(&page));
+        assert_eq!(vec![0, 1], indices(&page));
+    }
+
+    #[test]
+    fn table_of_contents() {
+        let pages = testing::load_pages("test-resources/presentation.md");
+        let page =
+            PageWidget::table_of_contents(pages, 1, &Configuration::default());
+
+        let area = Rect::new(0, 0, 20, 4);
+        let mut buf = Buffer::empty(area);
+        page.render(area, &mut buf);
+        let lines = testing::rows(&buf)
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["# Contents", "", "2.  Page 1", "3.  Page 2"], lines);
+    }
+
+    #[test]
+    fn slide_offset() {
+        assert_eq!(
+            vec![0, 10, 20, 30, 40, 50, 60, 70, 80, 80],
+            (0..=TransitionWidget::FRAMES + 1)
+                .map(|frame| TransitionWidget::slide_offset(80, frame))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(2, TransitionWidget::slide_offset(5, 4));
+        assert_eq!(0, TransitionWidget::slide_offset(0, 4));
+    }
+
+    #[test]
+    fn slide_transition() {
+        let pages = testing::load_pages("test-resources/presentation.md");
+        let configuration = Configuration::default();
+        let footnotes = Footnotes::default();
+        let first = PageWidget::new(&pages[0], &configuration, &footnotes);
+        let second = PageWidget::new(&pages[1], &configuration, &footnotes);
+
+        let area = Rect::new(0, 0, 16, 3);
+        let line = |widget: TransitionWidget| {
+            let mut buf = Buffer::empty(area);
+            widget.render(area, &mut buf);
+            testing::rows(&buf).swap_remove(0)
+        };
+
+        assert_eq!(
+            "# Page 1        ",
+            line(TransitionWidget::new(
+                &first,
+                &second,
+                Transition::Slide,
+                true,
+                0,
+            )),
+        );
+        assert_eq!(
+            "        # Page 2",
+            line(TransitionWidget::new(
+                &first,
+                &second,
+                Transition::Slide,
+                true,
+                4,
+            )),
+        );
+        assert_eq!(
+            "        # Page 1",
+            line(TransitionWidget::new(
+                &first,
+                &second,
+                Transition::Slide,
+                false,
+                4,
+            )),
+        );
+    }
+
+    #[test]
+    fn empty_sections() {
+        let sections = Sections::from(vec![]);
+        let area = Rect::new(0, 0, 10, 2);
+        let mut buf = Buffer::empty(area);
+        sections.render(area, &mut buf);
+
+        assert_eq!(0, sections.height(10));
+        assert_eq!(1, sections.paginate(10, 2).len());
+        assert_eq!(Buffer::empty(area), buf);
     }
 }
@@ -0,0 +1,3 @@
+#!/bin/sh
+# Succeeds only when passed the number of the first page
+test "$1" = 1
@@ -0,0 +1,54 @@
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+
+use rupert::configuration::Configuration;
+use rupert::{load_presentation, render_page, render_page_to_buffer};
+
+#[test]
+fn load_and_render() {
+    let configuration = Configuration::default();
+    let arena = comrak::Arena::new();
+    let deck =
+        load_presentation(&arena, "test-resources/headings.md", &configuration)
+            .unwrap();
+    assert_eq!(1, deck.pages().count());
+
+    let pages = deck.widgets();
+    let area = Rect::new(0, 0, 20, 12);
+    let mut buf = Buffer::empty(area);
+    render_page(&pages[0], area, &mut buf);
+    let row = |y: u16| {
+        (0..area.width)
+            .map(|x| buf.get(x, y).symbol.as_str())
+            .collect::<String>()
+    };
+    assert_eq!("# Level 1", row(0).trim_end());
+    assert!((0..area.height).any(|y| row(y).trim_end() == "Text."));
+}
+
+#[test]
+fn render_to_buffer() {
+    let configuration = Configuration::default();
+    let arena = comrak::Arena::new();
+    let deck =
+        load_presentation(&arena, "test-resources/headings.md", &configuration)
+            .unwrap();
+
+    let pages = deck.widgets();
+    let buf = render_page_to_buffer(&pages[0], 20, 12);
+    assert_eq!(Rect::new(0, 0, 20, 12), buf.area);
+    assert_eq!("#", buf.get(0, 0).symbol);
+    assert_eq!("L", buf.get(2, 0).symbol);
+}
+
+#[test]
+fn load_missing() {
+    let configuration = Configuration::default();
+    let arena = comrak::Arena::new();
+    assert!(load_presentation(
+        &arena,
+        "test-resources/missing.md",
+        &configuration
+    )
+    .is_err());
+}
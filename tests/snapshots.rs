@@ -0,0 +1,83 @@
+//! Snapshot tests of rendered pages.
+//!
+//! Every test renders a page and compares the symbols of the cells, one line
+//! per row with trailing spaces removed, to a file in `tests/snapshots`. Set
+//! `RUPERT_UPDATE_SNAPSHOTS=1` to write the rendered pages to the files
+//! instead, and review the changes before committing them.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use tui::buffer::Buffer;
+
+use rupert::configuration::Configuration;
+use rupert::{load_presentation, render_page_to_buffer};
+
+/// The directory containing the snapshots.
+const SNAPSHOTS: &str = "tests/snapshots";
+
+/// Converts the symbols of a buffer to text.
+///
+/// # Arguments
+/// *  `buf` - The buffer.
+fn text(buf: &Buffer) -> String {
+    (buf.area.top()..buf.area.bottom())
+        .map(|y| {
+            let row = (buf.area.left()..buf.area.right())
+                .map(|x| buf.get(x, y).symbol.as_str())
+                .collect::<String>();
+            format!("{}\n", row.trim_end())
+        })
+        .collect()
+}
+
+/// Compares a buffer to a snapshot, or updates the snapshot if
+/// `RUPERT_UPDATE_SNAPSHOTS` is set to `1`.
+///
+/// # Arguments
+/// *  `name` - The name of the snapshot.
+/// *  `buf` - The buffer.
+fn assert_snapshot(name: &str, buf: &Buffer) {
+    let path = Path::new(SNAPSHOTS).join(name).with_extension("txt");
+    let actual = text(buf);
+    if env::var("RUPERT_UPDATE_SNAPSHOTS").is_ok_and(|v| v == "1") {
+        fs::write(&path, actual).unwrap();
+    } else {
+        let expected = fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!("Failed to read snapshot {}: {}", path.display(), e)
+        });
+        assert_eq!(expected, actual, "snapshot {}", name);
+    }
+}
+
+/// Renders the first page of a presentation document and compares it to a
+/// snapshot.
+///
+/// # Arguments
+/// *  `name` - The name of the presentation document in `test-resources`,
+///    and of the snapshot.
+/// *  `width` - The width of the rendering area.
+/// *  `height` - The height of the rendering area.
+fn snapshot(name: &str, width: u16, height: u16) {
+    let configuration = Configuration::default();
+    let arena = comrak::Arena::new();
+    let deck = load_presentation(
+        &arena,
+        Path::new("test-resources").join(name).with_extension("md"),
+        &configuration,
+    )
+    .unwrap();
+    let pages = deck.widgets();
+    assert_snapshot(name, &render_page_to_buffer(&pages[0], width, height));
+}
+
+#[test]
+fn headings() {
+    snapshot("headings", 30, 12);
+}
+
+#[test]
+fn tables() {
+    snapshot("tables", 40, 12);
+}